@@ -56,7 +56,7 @@ pub(crate) fn main_with_warnings(
             root_config,
             options,
             manifest.packages,
-            telemetry,
+            Box::new(telemetry),
             warnings,
             paths.clone(),
             io,
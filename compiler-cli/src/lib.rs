@@ -279,7 +279,17 @@ enum Command {
 
     /// Run the language server, to be used by editors
     #[command(name = "lsp")]
-    LanguageServer,
+    LanguageServer {
+        /// Run as a daemon listening for clients on this address, such as
+        /// `127.0.0.1:7777`, rather than talking over stdio
+        #[arg(long, conflicts_with = "socket")]
+        listen: Option<String>,
+
+        /// Connect to a client listening on this port of the local machine,
+        /// rather than talking over stdio
+        #[arg(long)]
+        socket: Option<u16>,
+    },
 
     /// Export something useful from the Gleam project
     #[command(subcommand)]
@@ -653,7 +663,14 @@ fn parse_and_run_command() -> Result<(), Error> {
             clean(&paths)
         }
 
-        Command::LanguageServer => lsp::main(),
+        Command::LanguageServer { listen, socket } => {
+            let transport = match (listen, socket) {
+                (Some(address), _) => lsp::Transport::Listen { address },
+                (None, Some(port)) => lsp::Transport::Socket { port },
+                (None, None) => lsp::Transport::Stdio,
+            };
+            lsp::main(transport)
+        }
 
         Command::Export(ExportTarget::ErlangShipment) => {
             let paths = find_project_paths()?;
@@ -3,17 +3,41 @@ use crate::{
     fs::ProjectIO,
 };
 use gleam_core::{
-    Result,
+    Error, Result,
     build::{Mode, NullTelemetry, Target},
     language_server::{LanguageServer, LockGuard, Locker},
     paths::ProjectPaths,
 };
+use std::{
+    io::BufReader,
+    net::{Shutdown, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, SendError},
+    },
+};
+
+type Worker = mpsc::Sender<TcpStream>;
 
-pub fn main() -> Result<()> {
+/// How the language server talks to the language clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transport {
+    /// Standard input and output, for a client that started the server.
+    Stdio,
+    /// A socket on the local machine that a client is listening on.
+    Socket { port: u16 },
+    /// An address the server listens on as a long-lived daemon, serving every
+    /// client that connects to it.
+    Listen { address: String },
+}
+
+pub fn main(transport: Transport) -> Result<()> {
     tracing::info!("language_server_starting");
 
-    eprintln!(
-        "Hello human!
+    match transport {
+        Transport::Stdio => {
+            eprintln!(
+                "Hello human!
 
 This command is intended to be run by language server clients such
 as a text editor rather than being run directly in the console.
@@ -28,24 +52,220 @@ ignore this message.
 If you have run `gleam lsp` yourself in your terminal then exit
 this program by pressing ctrl+c.
 "
-    );
+            );
 
-    // Create the transport. Includes the stdio (stdin and stdout) versions but this could
-    // also be implemented to use sockets or HTTP.
-    let (connection, io_threads) = lsp_server::Connection::stdio();
+            let (connection, io_threads) = lsp_server::Connection::stdio();
+            serve(connection)?;
+            io_threads.join().expect("joining_lsp_threads");
+        }
 
-    // Run the server and wait for the two threads to end, typically by trigger
-    // LSP Exit event.
+        Transport::Socket { port } => {
+            let address = format!("127.0.0.1:{port}");
+            let (connection, io_threads) = lsp_server::Connection::connect(&address)
+                .map_err(|error| connection_error(&address, error))?;
+            serve(connection)?;
+            io_threads.join().expect("joining_lsp_threads");
+        }
+
+        Transport::Listen { address } => listen(&address)?,
+    }
+
+    tracing::info!("language_server_stopped");
+    Ok(())
+}
+
+/// Run the server and wait for the client to end the session, typically by
+/// sending the LSP Exit event.
+fn serve(connection: lsp_server::Connection) -> Result<()> {
     LanguageServer::new(&connection, ProjectIO::new())?.run()?;
 
     // Shut down gracefully.
     drop(connection);
-    io_threads.join().expect("joining_lsp_threads");
+    Ok(())
+}
+
+/// Accept clients until the process is stopped, serving each one on a worker
+/// thread of its own. Once its client has disconnected a worker waits for
+/// another one, keeping its language server and the projects it has compiled
+/// so that a restarted editor doesn't need them compiling again. The build
+/// directory is locked while compiling, so workers serving the same project
+/// don't trample on each other.
+fn listen(address: &str) -> Result<()> {
+    let listener = TcpListener::bind(address).map_err(|error| connection_error(address, error))?;
+    let address = listener
+        .local_addr()
+        .map_err(|error| connection_error(address, error))?;
+    eprintln!("Listening for language server clients on {address}");
+
+    let idle_workers: Arc<Mutex<Vec<Worker>>> = Arc::new(Mutex::new(Vec::new()));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                tracing::warn!(?error, "language_server_connection_failed");
+                continue;
+            }
+        };
+
+        // A worker whose client has disconnected serves the new one, if there
+        // is any.
+        let idle_worker = idle_workers.lock().expect("idle workers lock").pop();
+        let stream = match idle_worker {
+            Some(worker) => match worker.send(stream) {
+                Ok(()) => continue,
+                Err(SendError(stream)) => stream,
+            },
+            None => stream,
+        };
+        start_worker(idle_workers.clone())
+            .send(stream)
+            .expect("send client to language server worker");
+    }
 
-    tracing::info!("language_server_stopped");
     Ok(())
 }
 
+/// Start a thread serving the clients sent to it one after another, which
+/// adds itself back to the idle workers each time a client disconnects.
+fn start_worker(idle_workers: Arc<Mutex<Vec<Worker>>>) -> Worker {
+    let (worker, streams) = mpsc::channel();
+    let idle_worker = worker.clone();
+    _ = std::thread::spawn(move || {
+        serve_streams(streams, || {
+            idle_workers
+                .lock()
+                .expect("idle workers lock")
+                .push(idle_worker.clone())
+        })
+    });
+    worker
+}
+
+/// Serve each client connected over TCP in turn with the same language
+/// server, calling `disconnected` after each client has gone.
+fn serve_streams(streams: mpsc::Receiver<TcpStream>, disconnected: impl Fn()) {
+    let (server, client) = lsp_server::Connection::memory();
+    let mut language_server = None;
+
+    for stream in streams {
+        let peer = stream.peer_addr().ok();
+        tracing::info!(?peer, "language_server_client_connected");
+        if let Err(error) = serve_stream(stream, &server, &client, &mut language_server) {
+            tracing::error!(?error, "language_server_session_failed");
+        }
+        tracing::info!(?peer, "language_server_client_disconnected");
+        disconnected();
+    }
+}
+
+/// Serve a single client connected over TCP, returning once it disconnects.
+/// The language server is passed messages through `client`, and is created
+/// for the first client or reconnected for any other. A client that goes
+/// before initialising the server results in an error, and the next client
+/// gets to initialise it instead.
+fn serve_stream<'a>(
+    stream: TcpStream,
+    server: &'a lsp_server::Connection,
+    client: &lsp_server::Connection,
+    language_server: &mut Option<LanguageServer<'a, ProjectIO>>,
+) -> Result<()> {
+    let address = stream
+        .peer_addr()
+        .map_or_else(|_| "client".into(), |address| address.to_string());
+    let mut reader = BufReader::new(
+        stream
+            .try_clone()
+            .map_err(|error| connection_error(&address, error))?,
+    );
+    let mut writer = stream;
+    let sender = client.sender.clone();
+    let receiver = client.receiver.clone();
+
+    // Messages from the client are passed on to the server until the client
+    // exits or disconnects. The server only stops once it has been shut down,
+    // so that is done for clients that go without asking for it.
+    let reading = std::thread::spawn(move || {
+        let mut shut_down = false;
+        while let Ok(Some(message)) = lsp_server::Message::read(&mut reader) {
+            if is_exit(&message) {
+                break;
+            }
+            shut_down |= matches!(
+                &message,
+                lsp_server::Message::Request(request) if request.method == "shutdown"
+            );
+            if sender.send(message).is_err() {
+                return;
+            }
+        }
+        if !shut_down {
+            _ = sender.send(shutdown_request());
+        }
+        _ = sender.send(exit_notification());
+    });
+
+    // Messages from the server are passed on to the client until the server
+    // stops. Messages sent once the client has disconnected are dropped, so
+    // that they are not passed on to the next client.
+    let writing = std::thread::spawn(move || {
+        let mut connected = true;
+        for message in receiver {
+            if is_exit(&message) {
+                break;
+            }
+            connected = connected && message.write(&mut writer).is_ok();
+        }
+        _ = writer.shutdown(Shutdown::Both);
+    });
+
+    let result = match language_server {
+        Some(language_server) => language_server
+            .reconnect()
+            .and_then(|()| language_server.run()),
+        None => LanguageServer::new(server, ProjectIO::new())
+            .and_then(|new| language_server.insert(new).run()),
+    };
+
+    // The server never sends an exit notification itself, so one is used to
+    // tell the writing thread that the server has stopped.
+    _ = server.sender.send(exit_notification());
+    reading.join().expect("joining_lsp_reader_thread");
+    writing.join().expect("joining_lsp_writer_thread");
+
+    // Anything left from this client is of no use to the next one.
+    while server.receiver.try_recv().is_ok() {}
+    result
+}
+
+fn is_exit(message: &lsp_server::Message) -> bool {
+    matches!(
+        message,
+        lsp_server::Message::Notification(notification) if notification.method == "exit"
+    )
+}
+
+fn shutdown_request() -> lsp_server::Message {
+    lsp_server::Message::Request(lsp_server::Request {
+        id: "gleam-lsp-disconnected".to_string().into(),
+        method: "shutdown".into(),
+        params: serde_json::Value::Null,
+    })
+}
+
+fn exit_notification() -> lsp_server::Message {
+    lsp_server::Message::Notification(lsp_server::Notification {
+        method: "exit".into(),
+        params: serde_json::Value::Null,
+    })
+}
+
+fn connection_error(address: &str, error: std::io::Error) -> Error {
+    Error::LanguageServerConnection {
+        address: address.into(),
+        err: error.kind(),
+    }
+}
+
 #[derive(Debug)]
 pub struct LspLocker(BuildLock);
 
@@ -62,3 +282,146 @@ impl Locker for LspLocker {
         Ok(LockGuard(Box::new(guard)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::{Message, Notification, Request, RequestId, Response};
+
+    fn request(id: i32, method: &str, params: serde_json::Value) -> Message {
+        Message::Request(Request {
+            id: id.into(),
+            method: method.into(),
+            params,
+        })
+    }
+
+    fn notification(method: &str, params: serde_json::Value) -> Message {
+        Message::Notification(Notification {
+            method: method.into(),
+            params,
+        })
+    }
+
+    /// Reads messages from the server until the response to the given request,
+    /// skipping any requests and notifications it sends in the meantime.
+    fn response(reader: &mut BufReader<TcpStream>, id: i32) -> Response {
+        let id = RequestId::from(id);
+        loop {
+            match Message::read(reader).expect("read message") {
+                Some(Message::Response(response)) if response.id == id => return response,
+                Some(_) => continue,
+                None => panic!("connection closed before response {id}"),
+            }
+        }
+    }
+
+    /// Serves the given number of clients connecting one after another with a
+    /// single worker, returning once they have all disconnected.
+    fn serve_clients(listener: TcpListener, clients: usize) -> std::thread::JoinHandle<usize> {
+        std::thread::spawn(move || {
+            let (worker, streams) = mpsc::channel();
+            let accepting = std::thread::spawn(move || {
+                for _ in 0..clients {
+                    let (stream, _) = listener.accept().expect("accept");
+                    worker.send(stream).expect("send stream");
+                }
+            });
+            let disconnected = std::cell::Cell::new(0);
+            serve_streams(streams, || disconnected.set(disconnected.get() + 1));
+            accepting.join().expect("accepting thread");
+            disconnected.get()
+        })
+    }
+
+    /// Connects to the server and initialises it, returning the streams used
+    /// to write to and read from it.
+    fn connect(address: std::net::SocketAddr) -> (TcpStream, BufReader<TcpStream>) {
+        let mut writer = TcpStream::connect(address).expect("connect");
+        let mut reader = BufReader::new(writer.try_clone().expect("clone stream"));
+
+        request(1, "initialize", serde_json::json!({ "capabilities": {} }))
+            .write(&mut writer)
+            .expect("write initialize");
+        let initialised = response(&mut reader, 1);
+        assert!(initialised.error.is_none());
+        assert!(
+            initialised
+                .result
+                .is_some_and(|result| result.get("capabilities").is_some())
+        );
+        notification("initialized", serde_json::json!({}))
+            .write(&mut writer)
+            .expect("write initialized");
+        (writer, reader)
+    }
+
+    fn shut_down(mut writer: TcpStream, mut reader: BufReader<TcpStream>) {
+        request(2, "shutdown", serde_json::Value::Null)
+            .write(&mut writer)
+            .expect("write shutdown");
+        assert!(response(&mut reader, 2).error.is_none());
+        notification("exit", serde_json::Value::Null)
+            .write(&mut writer)
+            .expect("write exit");
+    }
+
+    #[test]
+    fn serve_client_over_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let address = listener.local_addr().expect("address");
+        let server = serve_clients(listener, 1);
+
+        let (writer, reader) = connect(address);
+        shut_down(writer, reader);
+
+        assert_eq!(server.join().expect("server thread"), 1);
+    }
+
+    #[test]
+    fn serve_client_that_disconnects_without_shutting_down() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let address = listener.local_addr().expect("address");
+        let server = serve_clients(listener, 1);
+
+        let (writer, reader) = connect(address);
+        drop(reader);
+        drop(writer);
+
+        assert_eq!(server.join().expect("server thread"), 1);
+    }
+
+    #[test]
+    fn serve_client_after_one_that_disconnects_before_initialising() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let address = listener.local_addr().expect("address");
+        let server = serve_clients(listener, 4);
+
+        drop(TcpStream::connect(address).expect("connect"));
+        let (writer, reader) = connect(address);
+        drop(reader);
+        drop(writer);
+        drop(TcpStream::connect(address).expect("connect"));
+        let (writer, reader) = connect(address);
+        shut_down(writer, reader);
+
+        assert_eq!(server.join().expect("server thread"), 4);
+    }
+
+    #[test]
+    fn serve_clients_one_after_another_with_the_same_worker() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let address = listener.local_addr().expect("address");
+        let server = serve_clients(listener, 3);
+
+        let (writer, reader) = connect(address);
+        shut_down(writer, reader);
+        let (writer, reader) = connect(address);
+        drop(reader);
+        drop(writer);
+        let (writer, reader) = connect(address);
+        shut_down(writer, reader);
+
+        assert_eq!(server.join().expect("server thread"), 3);
+    }
+}
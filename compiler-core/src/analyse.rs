@@ -29,7 +29,7 @@ use crate::{
         environment::*,
         error::{Error, FeatureKind, MissingAnnotation, Named, Problems, convert_unify_error},
         expression::{ExprTyper, FunctionDefinition, Implementations},
-        fields::FieldMapBuilder,
+        fields::{self, FieldMapBuilder},
         hydrator::Hydrator,
         prelude::*,
     },
@@ -1017,6 +1017,7 @@ impl<'a, A> ModuleAnalyzer<'a, A> {
                 label,
                 ast,
                 location,
+                doc,
                 ..
             } in constructor.arguments.iter()
             {
@@ -1046,6 +1047,9 @@ impl<'a, A> ModuleAnalyzer<'a, A> {
                 if let Err(error) = field_map_builder.add(label, label_location) {
                     self.problems.error(error);
                 }
+                if let (Some(label), Some((_, documentation))) = (label, doc) {
+                    field_map_builder.document(label, documentation.trim().into());
+                }
             }
             let field_map = field_map_builder.finish();
             // Insert constructor function into module scope
@@ -1418,6 +1422,11 @@ impl<'a, A> ModuleAnalyzer<'a, A> {
 
             builder.add(names.get_label(), *location)?;
         }
+        if let Some((_, documentation)) = documentation {
+            for (label, label_documentation) in fields::labels_documentation(documentation) {
+                builder.document(&label, label_documentation);
+            }
+        }
         let field_map = builder.finish();
         let mut hydrator = Hydrator::new();
 
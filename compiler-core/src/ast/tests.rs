@@ -103,6 +103,7 @@ fn compile_expression(src: &str) -> TypedStatement {
         field_map: Some(FieldMap {
             arity: 2,
             fields: [("name".into(), 0), ("age".into(), 1)].into(),
+            documentation: std::collections::HashMap::new(),
         }),
         module: "mymod".into(),
         variant_index: 0,
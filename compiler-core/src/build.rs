@@ -364,6 +364,25 @@ pub enum Located<'a> {
 }
 
 impl<'a> Located<'a> {
+    /// The location of the node in the source code.
+    pub fn location(&self) -> SrcSpan {
+        match self {
+            Self::Pattern(pattern) => pattern.location(),
+            Self::PatternSpread {
+                spread_location, ..
+            } => *spread_location,
+            Self::Statement(statement) => statement.location(),
+            Self::Expression(expression) => expression.location(),
+            Self::ModuleStatement(definition) => definition.location(),
+            Self::VariantConstructorDefinition(record) => record.location,
+            Self::FunctionBody(function) => function.full_location(),
+            Self::Arg(arg) => arg.location,
+            Self::Annotation { ast, .. } => ast.location(),
+            Self::UnqualifiedImport(UnqualifiedImport { location, .. }) => **location,
+            Self::Label(location, _) | Self::ModuleName { location, .. } => *location,
+        }
+    }
+
     // Looks up the type constructor for the given type and then create the location.
     fn type_location(
         &self,
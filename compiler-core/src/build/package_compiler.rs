@@ -4,7 +4,7 @@ use crate::io::files_with_extension;
 use crate::line_numbers::{self, LineNumbers};
 use crate::type_::PRELUDE_MODULE_NAME;
 use crate::{
-    Error, Result, Warning,
+    Error, Result, STDLIB_PACKAGE_NAME, Warning,
     ast::{SrcSpan, TypedModule, UntypedModule},
     build::{
         Mode, Module, Origin, Outcome, Package, SourceFingerprint, Target,
@@ -193,6 +193,7 @@ where
             warnings,
             self.target_support,
             incomplete_modules,
+            telemetry,
         );
 
         let modules = match outcome {
@@ -211,7 +212,13 @@ where
 
         tracing::debug!("performing_code_generation");
 
-        if let Err(error) = self.perform_codegen(&modules) {
+        let stdlib_package = StdlibPackage::find(
+            &self.config,
+            existing_modules
+                .values()
+                .map(|module| module.package.as_str()),
+        );
+        if let Err(error) = self.perform_codegen(&modules, stdlib_package) {
             return error.into();
         }
 
@@ -317,7 +324,7 @@ where
         Ok(())
     }
 
-    fn perform_codegen(&mut self, modules: &[Module]) -> Result<()> {
+    fn perform_codegen(&mut self, modules: &[Module], stdlib_package: StdlibPackage) -> Result<()> {
         if !self.perform_codegen {
             tracing::debug!("skipping_codegen");
             return Ok(());
@@ -331,6 +338,7 @@ where
                 modules,
                 *emit_typescript_definitions,
                 prelude_location,
+                stdlib_package,
             ),
             TargetCodegenConfiguration::Erlang { app_file } => {
                 self.perform_erlang_codegen(modules, app_file.as_ref())
@@ -392,6 +400,7 @@ where
         modules: &[Module],
         typescript: bool,
         prelude_location: &Utf8Path,
+        stdlib_package: StdlibPackage,
     ) -> Result<(), Error> {
         let mut written = HashSet::new();
         let typescript = if typescript {
@@ -407,7 +416,7 @@ where
             &self.root,
             self.target_support,
         )
-        .render(&self.io, modules, self.stdlib_package())?;
+        .render(&self.io, modules, stdlib_package)?;
 
         if self.copy_native_files {
             self.copy_project_native_files(&self.out, &mut written)?;
@@ -459,10 +468,23 @@ where
 
         Ok(())
     }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StdlibPackage {
+    Present,
+    Missing,
+}
 
-    fn stdlib_package(&self) -> StdlibPackage {
-        if self.config.dependencies.contains_key("gleam_stdlib")
-            || self.config.dev_dependencies.contains_key("gleam_stdlib")
+impl StdlibPackage {
+    /// The standard library is present if it's one of the packages the given
+    /// modules come from, whether the package depends on it directly or not.
+    ///
+    pub fn find<'a>(config: &PackageConfig, packages: impl IntoIterator<Item = &'a str>) -> Self {
+        if config.name != STDLIB_PACKAGE_NAME
+            && packages
+                .into_iter()
+                .any(|package| package == STDLIB_PACKAGE_NAME)
         {
             StdlibPackage::Present
         } else {
@@ -471,12 +493,6 @@ where
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum StdlibPackage {
-    Present,
-    Missing,
-}
-
 fn analyse(
     package_config: &PackageConfig,
     target: Target,
@@ -487,6 +503,7 @@ fn analyse(
     warnings: &WarningEmitter,
     target_support: TargetSupport,
     incomplete_modules: &mut HashSet<EcoString>,
+    telemetry: &dyn Telemetry,
 ) -> Outcome<Vec<Module>, Error> {
     let mut modules = Vec::with_capacity(parsed_modules.len() + 1);
     let direct_dependencies = package_config.dependencies_for(mode).expect("Package deps");
@@ -511,6 +528,7 @@ fn analyse(
     } in parsed_modules
     {
         tracing::debug!(module = ?name, "Type checking");
+        telemetry.checking_module(&name);
 
         let line_numbers = LineNumbers::new(&code);
 
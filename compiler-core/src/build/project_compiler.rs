@@ -120,7 +120,7 @@ pub struct ProjectCompiler<IO> {
     /// successful compilation.
     incomplete_modules: HashSet<EcoString>,
     warnings: WarningEmitter,
-    telemetry: &'static dyn Telemetry,
+    telemetry: Box<dyn Telemetry>,
     options: Options,
     paths: ProjectPaths,
     ids: UniqueIdGenerator,
@@ -141,7 +141,7 @@ where
         config: PackageConfig,
         options: Options,
         packages: Vec<ManifestPackage>,
-        telemetry: &'static dyn Telemetry,
+        telemetry: Box<dyn Telemetry>,
         warning_emitter: Rc<dyn WarningEmitterIO>,
         paths: ProjectPaths,
         io: IO,
@@ -636,7 +636,7 @@ where
             &mut self.defined_modules,
             &mut self.stale_modules,
             &mut self.incomplete_modules,
-            self.telemetry,
+            self.telemetry.as_ref(),
         )
     }
 }
@@ -15,6 +15,44 @@ pub trait Telemetry: Debug {
     fn compiling_package(&self, name: &str);
     fn checked_package(&self, duration: Duration);
     fn checking_package(&self, name: &str);
+
+    /// Called as each module of a package is type checked. This is too fine
+    /// grained for the command line, so it is only reported by the language
+    /// server.
+    fn checking_module(&self, _name: &str) {}
+}
+
+impl<T: Telemetry + ?Sized> Telemetry for &T {
+    fn waiting_for_build_directory_lock(&self) {
+        (**self).waiting_for_build_directory_lock()
+    }
+    fn running(&self, name: &str) {
+        (**self).running(name)
+    }
+    fn resolving_package_versions(&self) {
+        (**self).resolving_package_versions()
+    }
+    fn downloading_package(&self, name: &str) {
+        (**self).downloading_package(name)
+    }
+    fn packages_downloaded(&self, start: Instant, count: usize) {
+        (**self).packages_downloaded(start, count)
+    }
+    fn compiled_package(&self, duration: Duration) {
+        (**self).compiled_package(duration)
+    }
+    fn compiling_package(&self, name: &str) {
+        (**self).compiling_package(name)
+    }
+    fn checked_package(&self, duration: Duration) {
+        (**self).checked_package(duration)
+    }
+    fn checking_package(&self, name: &str) {
+        (**self).checking_package(name)
+    }
+    fn checking_module(&self, name: &str) {
+        (**self).checking_module(name)
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1,3 +1,5 @@
+pub mod definition_spans;
+
 use crate::{
     Result,
     analyse::TargetSupport,
@@ -0,0 +1,55 @@
+//! Finding out which part of a generated module was produced by each of the
+//! Gleam module's definitions.
+//!
+//! The code generators wrap the document of each definition in a pair of
+//! markers carrying the index of the definition. The markers take no space,
+//! so the code is laid out exactly as it would be without them, and they are
+//! removed once the document has been printed.
+
+use std::collections::HashMap;
+
+use ecow::eco_format;
+
+use crate::{ast::SrcSpan, pretty::Document};
+
+const MARKER: char = '\u{0}';
+
+/// Wraps the document generated for the definition at the given index.
+///
+pub fn mark(index: usize, document: Document<'_>) -> Document<'_> {
+    let marker = || Document::zero_width_string(eco_format!("{MARKER}{index}{MARKER}"));
+    marker().append(document).append(marker())
+}
+
+/// Removes the markers from the printed code, returning the code along with
+/// the span of the code generated for each marked definition, by index.
+///
+pub fn remove_markers(marked: &str) -> (String, HashMap<usize, SrcSpan>) {
+    let mut code = String::with_capacity(marked.len());
+    let mut starts = HashMap::new();
+    let mut spans = HashMap::new();
+
+    let mut rest = marked;
+    while let Some((before, after)) = rest.split_once(MARKER) {
+        code.push_str(before);
+        let marker = after.split_once(MARKER).and_then(|(index, after)| {
+            let index = index.parse::<usize>().ok()?;
+            Some((index, after))
+        });
+        let Some((index, after)) = marker else {
+            // Not one of our markers, so it's part of the generated code.
+            code.push(MARKER);
+            rest = after;
+            continue;
+        };
+        let position = code.len() as u32;
+        match starts.remove(&index) {
+            Some(start) => _ = spans.insert(index, SrcSpan::new(start, position)),
+            None => _ = starts.insert(index, position),
+        }
+        rest = after;
+    }
+    code.push_str(rest);
+
+    (code, spans)
+}
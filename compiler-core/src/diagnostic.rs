@@ -49,12 +49,21 @@ pub struct Location {
     pub extra_labels: Vec<ExtraLabel>,
 }
 
+/// Extra information about a diagnostic, used by editors to display it
+/// differently. For example unused code might be faded out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tag {
+    Unnecessary,
+    Deprecated,
+}
+
 // TODO: split this into locationed diagnostics and locationless diagnostics
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Diagnostic {
     pub title: String,
     pub text: String,
     pub level: Level,
+    pub tags: Vec<Tag>,
     pub location: Option<Location>,
     pub hint: Option<String>,
 }
@@ -12,6 +12,7 @@ use crate::type_::is_prelude_module;
 use crate::{
     Result,
     ast::{CustomType, Function, Import, ModuleConstant, TypeAlias, *},
+    codegen::definition_spans,
     docvec,
     line_numbers::LineNumbers,
     pretty::*,
@@ -168,13 +169,26 @@ pub fn module<'a>(
     line_numbers: &'a LineNumbers,
     root: &'a Utf8Path,
 ) -> Result<String> {
-    Ok(module_document(module, line_numbers, root)?.to_pretty_string(MAX_COLUMNS))
+    Ok(module_document(module, line_numbers, root, false)?.to_pretty_string(MAX_COLUMNS))
+}
+
+/// Generates the code for a module along with the span of the code generated
+/// for each of its definitions, keyed by the definition's index in the module.
+///
+pub fn module_with_definition_spans<'a>(
+    module: &'a TypedModule,
+    line_numbers: &'a LineNumbers,
+    root: &'a Utf8Path,
+) -> Result<(String, HashMap<usize, SrcSpan>)> {
+    let code = module_document(module, line_numbers, root, true)?.to_pretty_string(MAX_COLUMNS);
+    Ok(definition_spans::remove_markers(&code))
 }
 
 fn module_document<'a>(
     module: &'a TypedModule,
     line_numbers: &'a LineNumbers,
     root: &'a Utf8Path,
+    mark_definitions: bool,
 ) -> Result<Document<'a>> {
     let mut exports = vec![];
     let mut type_defs = vec![];
@@ -241,7 +255,7 @@ fn module_document<'a>(
     let mut needs_function_docs = false;
     let mut echo_used = false;
     let mut statements = Vec::with_capacity(module.definitions.len());
-    for definition in module.definitions.iter() {
+    for (index, definition) in module.definitions.iter().enumerate() {
         if let Some((statement_document, env)) = module_statement(
             definition,
             &module.name,
@@ -252,7 +266,11 @@ fn module_document<'a>(
         ) {
             needs_function_docs = needs_function_docs || env.needs_function_docs;
             echo_used = echo_used || env.echo_used;
-            statements.push(statement_document);
+            if mark_definitions {
+                statements.push(definition_spans::mark(index, statement_document));
+            } else {
+                statements.push(statement_document);
+            }
         }
     }
 
@@ -134,6 +134,15 @@ pub enum Error {
         err: Option<std::io::ErrorKind>,
     },
 
+    #[error("language server connection failed")]
+    LanguageServerConnection {
+        address: String,
+        err: std::io::ErrorKind,
+    },
+
+    #[error("language server initialisation failed")]
+    LanguageServerInitialisation { error: String },
+
     #[error("source code incorrectly formatted")]
     Format { problem_files: Vec<Unformatted> },
 
@@ -862,6 +871,7 @@ package deletion or account suspension.
                     title: "Invalid Hex package".into(),
                     text,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                     hint: None,
                 }]
@@ -880,6 +890,7 @@ of the Gleam dependency modules."
                     title: "Failed to decode module metadata".into(),
                     text,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                     hint: None,
                 }]
@@ -893,6 +904,7 @@ of the Gleam dependency modules."
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -905,6 +917,7 @@ Module names can only contain lowercase letters, underscore, and
 forward slash and must not end with a slash."
                 ),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: None,
             }],
@@ -918,6 +931,7 @@ forward slash and must not end with a slash."
                     title: "Module does not exist".into(),
                     text: format!("Module `{module}` was not found."),
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                     hint: Some(hint),
                 }]
@@ -929,6 +943,7 @@ forward slash and must not end with a slash."
                     "`{module}` does not have a main function so the module can not be run."
                 ),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: Some(format!(
                     "Add a public `main` function to \
@@ -943,6 +958,7 @@ to `src/{module}.gleam`."
 target, so it cannot be run."
                 ),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: None,
             }],
@@ -953,6 +969,7 @@ target, so it cannot be run."
                     "`{module}:main` should have an arity of 0 to be run but its arity is {arity}."
                 ),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: Some("Change the function signature of main to `pub fn main() {}`.".into()),
             }],
@@ -961,6 +978,7 @@ target, so it cannot be run."
                 title: "Project folder already exists".into(),
                 text: format!("Project folder root:\n\n  {path}"),
                 level: Level::Error,
+                tags: vec![],
                 hint: None,
                 location: None,
             }],
@@ -985,6 +1003,7 @@ If you want to overwrite these files, delete them and run the command again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                tags: vec![],
                 hint: None,
                 location: None,
             }],
@@ -1004,6 +1023,7 @@ be removed.
                         .join("\n")
                     ),
                     level: Level::Error,
+                    tags: vec![],
                     hint: None,
                     location: None,
                 }
@@ -1024,6 +1044,7 @@ Please remove them and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                tags: vec![],
                 hint: None,
                 location: None,
             }],
@@ -1043,6 +1064,7 @@ Please remove them and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                tags: vec![],
                 hint: None,
                 location: None,
             }],
@@ -1056,6 +1078,7 @@ code to run on an earlier version like v{wrongfully_allowed_version}, \
 resulting in compilation errors!"
                 )),
                 level: Level::Error,
+                tags: vec![],
                 hint: Some(format!(
                     "Remove the version constraint from your `gleam.toml` or update it to be:
 
@@ -1079,6 +1102,7 @@ Please make sure internal types do not appear in public functions and try again.
                         .join("\n")
                 ),
                 level: Level::Error,
+                tags: vec![],
                 hint: None,
                 location: None,
             }],
@@ -1094,6 +1118,7 @@ We searched in {path} and all parent directories."
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1108,6 +1133,7 @@ your app.src file \"{app_ver}\"."
                     hint: None,
                     text,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1195,6 +1221,7 @@ https://git-scm.com/book/en/v2/Getting-Started-Installing-Git",
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1210,6 +1237,7 @@ https://git-scm.com/book/en/v2/Getting-Started-Installing-Git",
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1232,6 +1260,7 @@ The error from the shell command library was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1254,6 +1283,7 @@ The error from the shell command was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1271,6 +1301,7 @@ This was error from the gzip library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1289,6 +1320,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1306,6 +1338,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1323,6 +1356,7 @@ This was error from the tar library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1340,6 +1374,7 @@ This was error from the Hex client library:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1361,6 +1396,7 @@ Second: {second}"
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1381,6 +1417,7 @@ same name and extension, unintentionally overwriting the native file.");
                     text,
                     hint: Some("Consider renaming one of the files, such as by adding an `_ffi` suffix to the native file's name, and trying again.".into()),
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             },
@@ -1390,6 +1427,7 @@ same name and extension, unintentionally overwriting the native file.");
                 text: format!("The file `{file}` is defined multiple times."),
                 hint: None,
                 level: Level::Error,
+                tags: vec![],
                 location: None,
             }],
 
@@ -1413,6 +1451,7 @@ Erlang modules must have unique names regardless of the subfolders where their
                     text,
                     hint: Some("Rename one of the native Erlang modules and try again.".into()),
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             },
@@ -1452,6 +1491,7 @@ https://learn.microsoft.com/en-us/windows/apps/get-started/enable-your-device-fo
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1468,6 +1508,7 @@ The error from the encryption library was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1483,6 +1524,7 @@ The error from the encryption library was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1496,6 +1538,7 @@ The error from the encryption library was:
                     title: "Non UTF-8 Path Encountered".into(),
                     text,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                     hint: None,
                 }]
@@ -1512,6 +1555,7 @@ The error from the encryption library was:
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -1529,6 +1573,7 @@ Erlang's floating point type. To avoid this error float values must be in the ra
 -1.7976931348623157e308 - 1.7976931348623157e308."),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -1560,6 +1605,7 @@ module to the src directory.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("Imported here".into()),
@@ -1622,6 +1668,7 @@ constructor accepts."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label,
                             path: path.clone(),
@@ -1641,6 +1688,7 @@ not expect any. Please remove the label `{label}`."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1664,6 +1712,7 @@ also be labelled.");
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1690,6 +1739,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("Reimported here".into()),
@@ -1728,6 +1778,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("Redefined here".into()),
@@ -1761,6 +1812,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("Redefined here".into()),
@@ -1787,6 +1839,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1806,6 +1859,7 @@ Names in a Gleam module must be unique so one will need to be renamed."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1829,6 +1883,7 @@ Hint: Add some type annotations and try again.")
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1852,6 +1907,7 @@ Hint: Add some type annotations and try again.")
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -1944,6 +2000,7 @@ to call a method on this value you may want to use the function syntax instead."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -1985,6 +2042,7 @@ But this argument has this type:
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2034,6 +2092,7 @@ But function expects:
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("This function does not accept the piped type".into()),
@@ -2084,6 +2143,7 @@ But function expects:
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: main_message_text,
@@ -2114,6 +2174,7 @@ called with their expected number of arguments.");
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some(format!("Expected {expected}, got {given}")),
@@ -2153,6 +2214,7 @@ called with their expected number of arguments.");
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -2175,6 +2237,7 @@ assigned variables to all of them."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2202,6 +2265,7 @@ constructing a new record with its values.");
                                 text,
                                 hint: None,
                                 level: Level::Error,
+                                tags: vec![],
                                 location: Some(Location {
                                     label: Label {
                                         text: Some(format!("I'm not sure this is always a `{constructed_variant}`")),
@@ -2225,6 +2289,7 @@ specify all fields explicitly instead of using the record update syntax.");
                                 text,
                                 hint: None,
                                 level: Level::Error,
+                                tags: vec![],
                                 location: Some(Location {
                                     label: Label {
                                         text: Some(format!("This is a `{spread_variant}`")),
@@ -2250,6 +2315,7 @@ Note: If the same type variable is used for multiple fields, all those fields ne
                                 text,
                                 hint: None,
                                 level: Level::Error,
+                                tags: vec![],
                                 location: Some(Location {
                                     label: Label {
                                         text: Some(format!("This is a `{record_variant}`")),
@@ -2294,6 +2360,7 @@ but no type in scope with that name."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: label_text,
@@ -2328,6 +2395,7 @@ but no type in scope with that name."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: did_you_mean(name, variables),
@@ -2362,6 +2430,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2383,6 +2452,7 @@ Private types can only be used within the module that defines them.",
                     text: format!("No module has been found with the name `{name}`."),
                     hint: suggestions.first().map(|suggestion| suggestion.suggestion(name)),
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2411,6 +2481,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: if *imported_type_as_value {
@@ -2450,6 +2521,7 @@ Private types can only be used within the module that defines them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: if *imported_value_as_type && matches!(context, ModuleValueUsageContext::UnqualifiedImport) {
@@ -2479,6 +2551,7 @@ them to functions, or anything else that you would do with a value."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2506,6 +2579,7 @@ Each clause must have a pattern for every subject value.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some(format!("Expected {expected} patterns, got {given}")),
@@ -2529,6 +2603,7 @@ function, or be an argument to the function. The variable \
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("Is not locally defined".into()),
@@ -2552,6 +2627,7 @@ previously defined.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("Has not been previously defined".into()),
@@ -2574,6 +2650,7 @@ as the initial pattern, but the `{name}` variable is missing.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("This does not define all required variables".into()),
@@ -2599,6 +2676,7 @@ e.g. (x, y) if x == y -> ...",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("This has already been used".into()),
@@ -2618,6 +2696,7 @@ e.g. (x, y) if x == y -> ...",
                     text: "This tuple has no elements so it cannot be indexed at all.".into(),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2646,6 +2725,7 @@ tuple has {} elements so the highest valid index is {}.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("This index is too large".into()),
@@ -2671,6 +2751,7 @@ tuple has {} elements so the highest valid index is {}.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("This is not a tuple".into()),
@@ -2693,6 +2774,7 @@ Please add some type annotations so we can continue."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("What type is this?".into()),
@@ -2715,6 +2797,7 @@ function and try again.");
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("I don't know what type this is".into()),
@@ -2812,6 +2895,7 @@ function and try again.");
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some(label.into()),
@@ -2829,6 +2913,7 @@ function and try again.");
                     text: "Only record constructors can be used with the update syntax.".into(),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: Some("This is not a record constructor".into()),
@@ -2845,6 +2930,7 @@ function and try again.");
                     text: "We need to know the exact type here so type holes cannot be used.".into(),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: Some("I need to know what this is".into()),
@@ -2867,6 +2953,7 @@ Try a different name for this module."
                         hint: None,
                         location: None,
                         level: Level::Error,
+                        tags: vec![],
                     }
                 }
 
@@ -2882,6 +2969,7 @@ Try a different name for this module."
                         hint: None,
                         location: None,
                         level: Level::Error,
+                        tags: vec![],
                     }
                 }
 
@@ -2915,6 +3003,7 @@ value, or use `let assert` rather than `let`."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2932,6 +3021,7 @@ value, or use `let assert` rather than `let`."
                     text: format!("Two `{name}` arguments have been defined for this function."),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2948,6 +3038,7 @@ value, or use `let assert` rather than `let`."
                     text: wrap("All unlabelled arguments must come before any labelled arguments."),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -2971,6 +3062,7 @@ forever in a loop, and we'd never get the final type.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -2999,6 +3091,7 @@ so we can tell what type of values they accept and return.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -3021,6 +3114,7 @@ using the `@external` attribute."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -3047,6 +3141,7 @@ implementation but the module path `{module}` is not valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -3073,6 +3168,7 @@ implementation but the function name `{function}` is not valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -3103,6 +3199,7 @@ The missing patterns are:\n");
                         text,
                         hint: Some("Use a more general pattern or use `let assert` instead.".into()),
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -3132,6 +3229,7 @@ The missing patterns are:\n"
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -3153,6 +3251,7 @@ The missing patterns are:\n"
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -3183,6 +3282,7 @@ and there is no implementation for the {} target.\n",
                         text,
                         hint: Some(hint),
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -3214,6 +3314,7 @@ must be able to compile for a module to be valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -3235,6 +3336,7 @@ must be able to compile for a module to be valid."
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -3257,6 +3359,7 @@ Rename or remove one of them.",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             path: path.clone(),
                             src: src.clone(),
@@ -3286,6 +3389,7 @@ See: https://tour.gleam.run/advanced-features/use/",
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -3310,6 +3414,7 @@ See: https://tour.gleam.run/advanced-features/use/");
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some("Expected no arguments, got 1".into()),
@@ -3358,6 +3463,7 @@ so it cannot take the `use` callback function as a final argument.\n")
                         text: wrap(&text),
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -3386,6 +3492,7 @@ See: https://tour.gleam.run/advanced-features/use/",
                         text: wrap(&text),
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -3420,6 +3527,7 @@ See: https://tour.gleam.run/advanced-features/use/");
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: None,
@@ -3466,6 +3574,7 @@ Try: _{}", kind_str.to_title_case(), name.to_snake_case()),
                         text,
                         hint: None,
                         level: Level::Error,
+                        tags: vec![],
                         location: Some(Location {
                             label: Label {
                                 text: Some(label),
@@ -3492,6 +3601,7 @@ Try: _{}", kind_str.to_title_case(), name.to_snake_case()),
                                 text,
                                 hint: None,
                                 level: Level::Error,
+                                tags: vec![],
                                 location: Some(Location {
                                     label: Label {
                                         text: None,
@@ -3513,6 +3623,7 @@ Consider removing the deprecation attribute on the variant.");
                                 text,
                                 hint: None,
                                 level: Level::Error,
+                                tags: vec![],
                                 location: Some(Location {
                                     label: Label {
                                         text: None,
@@ -3530,6 +3641,7 @@ Consider removing the deprecation attribute on the variant.");
                     text: wrap("The `echo` keyword should be followed by a value to print."),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: Some("I was expecting a value after this".into()),
@@ -3562,6 +3674,7 @@ Consider removing the deprecation attribute on the variant.");
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: Some(label.to_string()),
@@ -3597,6 +3710,7 @@ cycle to continue.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: Some("Imported here".into()),
@@ -3623,6 +3737,7 @@ cycle to continue.",
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -3644,6 +3759,7 @@ but it cannot be found."
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: did_you_mean(import, modules),
@@ -3676,6 +3792,46 @@ but it cannot be found."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
+                }]
+            }
+
+            Error::LanguageServerConnection { address, err } => {
+                let text = format!(
+                    "An error occurred while the language server was connecting to or \
+listening on `{address}`.
+
+The error message from the operating system was:
+
+    {}",
+                    std_io_error_kind_text(err)
+                );
+                vec![Diagnostic {
+                    title: "Language server connection failed".into(),
+                    text,
+                    hint: None,
+                    level: Level::Error,
+                    tags: vec![],
+                    location: None,
+                }]
+            }
+
+            Error::LanguageServerInitialisation { error } => {
+                let text = format!(
+                    "The language server could not complete its initialisation with \
+the language client.
+
+The error was:
+
+    {error}"
+                );
+                vec![Diagnostic {
+                    title: "Language server initialisation failed".into(),
+                    text,
+                    hint: None,
+                    level: Level::Error,
+                    tags: vec![],
+                    location: None,
                 }]
             }
 
@@ -3694,6 +3850,7 @@ but it cannot be found."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3711,6 +3868,7 @@ Fix the warnings and try again."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3720,6 +3878,7 @@ Fix the warnings and try again."
                     text: format!("{feature} is not supported for JavaScript compilation."),
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: Some(Location {
                         label: Label {
                             text: None,
@@ -3749,6 +3908,7 @@ The error from the package manager client was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3765,6 +3925,7 @@ The error from the HTTP client was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3781,6 +3942,7 @@ The error from the parser was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3793,6 +3955,7 @@ The error from the parser was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3811,6 +3974,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3829,6 +3993,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3847,6 +4012,7 @@ The error from the version resolver library was:
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3861,6 +4027,7 @@ dev-dependencies sections of the gleam.toml file."
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3892,6 +4059,7 @@ licences = ["Apache-2.0"]"#
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3905,6 +4073,7 @@ because dependency `{package}` is not a Hex dependency.",
                 hint: None,
                 location: None,
                 level: Level::Error,
+                tags: vec![],
             }],
 
             Error::UnsupportedBuildTool {
@@ -3926,6 +4095,7 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3942,6 +4112,7 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
                     text,
                     hint: None,
                     level: Level::Error,
+                    tags: vec![],
                     location: None,
                 }]
             }
@@ -3961,6 +4132,7 @@ satisfying {required_version} but you are using v{gleam_version}.",
                     hint: None,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3986,6 +4158,7 @@ satisfying {required_version} but you are using v{gleam_version}.",
                     hint,
                     location: None,
                     level: Level::Error,
+                    tags: vec![],
                 }]
             }
 
@@ -3994,6 +4167,7 @@ satisfying {required_version} but you are using v{gleam_version}.",
                 text: "The --javascript-prelude flag must be given when compiling to JavaScript."
                     .into(),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: None,
             }],
@@ -4001,6 +4175,7 @@ satisfying {required_version} but you are using v{gleam_version}.",
                 title: "Corrupt manifest.toml".into(),
                 text: "The `manifest.toml` file is corrupt.".into(),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: Some("Please run `gleam update` to fix it.".into()),
             }],
@@ -4016,6 +4191,7 @@ to compile and load your module it would overwrite the Erlang one, potentially \
 causing confusing errors and crashes.
 "),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: Some("Rename this module and try again.".into()),
             }],
@@ -4026,6 +4202,7 @@ causing confusing errors and crashes.
 This release has been recently published so you can replace it \
 or you can publish it using a different version number"),
                 level: Level::Error,
+                tags: vec![],
                 location: None,
                 hint: Some("Please add the --replace flag if you want to replace the release.".into()),
             }]
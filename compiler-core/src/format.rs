@@ -39,6 +39,99 @@ pub fn pretty(writer: &mut impl Utf8Writer, src: &EcoString, path: &Utf8Path) ->
         .pretty_print(80, writer)
 }
 
+/// Formats only the part of a module touched by the given range, leaving the
+/// rest of the code untouched.
+///
+/// If the range is inside a single statement of a function body then only
+/// that statement is formatted, otherwise each top level definition touched
+/// by the range is formatted.
+/// Returns the span of each piece of code that was formatted along with its
+/// new formatted code.
+///
+pub fn pretty_range(
+    src: &EcoString,
+    path: &Utf8Path,
+    range: SrcSpan,
+) -> Result<Vec<(SrcSpan, String)>> {
+    let parsed = crate::parse::parse_module(path.to_owned(), src, &WarningEmitter::null())
+        .map_err(|error| Error::Parse {
+            path: path.to_path_buf(),
+            src: src.clone(),
+            error,
+        })?;
+    let intermediate = Intermediate::from_extra(&parsed.extra, src);
+
+    let touched_definitions = parsed
+        .module
+        .definitions
+        .iter()
+        .filter(|definition| {
+            let location = definition_location(src, &definition.definition);
+            location.start <= range.end && range.start <= location.end
+        })
+        .collect_vec();
+
+    let statement = match touched_definitions.as_slice() {
+        [
+            TargetedDefinition {
+                definition: Definition::Function(function),
+                ..
+            },
+        ] => function.body.iter().find(|statement| {
+            let location = statement.location();
+            location.start <= range.start && range.end <= location.end
+        }),
+        _ => None,
+    };
+
+    let mut edits = vec![];
+    if let Some(statement) = statement {
+        let location = statement.location();
+        let mut formatter = Formatter::with_comments(&intermediate).within(location);
+        let document = formatter.statement(statement);
+        if let Some(edit) = formatter.print_edit(src, location, document)? {
+            edits.push(edit);
+        }
+        return Ok(edits);
+    }
+
+    for definition in touched_definitions {
+        let location = definition_location(src, &definition.definition);
+        let mut formatter = Formatter::with_comments(&intermediate).within(location);
+        let document = formatter.targeted_definition(definition);
+        if let Some(edit) = formatter.print_edit(src, location, document)? {
+            edits.push(edit);
+        }
+    }
+    Ok(edits)
+}
+
+/// The location of a whole definition, including the attributes written
+/// before it.
+///
+fn definition_location(src: &str, definition: &UntypedDefinition) -> SrcSpan {
+    let location = match definition {
+        Definition::Function(function) => function.full_location(),
+        Definition::CustomType(custom_type) => custom_type.full_location(),
+        Definition::TypeAlias(alias) => alias.location.merge(&alias.type_ast.location()),
+        Definition::ModuleConstant(constant) => constant.location.merge(&constant.value.location()),
+        Definition::Import(import) => import.location,
+    };
+
+    // Attributes are not part of the location of a definition, so we include
+    // any line right before it starting with an `@`.
+    let mut start = location.start as usize;
+    while let Some(before) = src.get(..start) {
+        let line_start = before.trim_end().rfind('\n').map_or(0, |index| index + 1);
+        match before.get(line_start..) {
+            Some(line) if line.trim_start().starts_with('@') => start = line_start,
+            _ => break,
+        }
+    }
+
+    SrcSpan::new(start as u32, location.end)
+}
+
 pub(crate) struct Intermediate<'a> {
     comments: Vec<Comment<'a>>,
     doc_comments: Vec<Comment<'a>>,
@@ -120,6 +213,65 @@ impl<'comments> Formatter<'comments> {
         }
     }
 
+    /// Only keeps the comments and empty lines inside the given span, so that
+    /// the code there can be formatted on its own.
+    ///
+    fn within(self, location: SrcSpan) -> Self {
+        let comments_within = |comments: &'comments [Comment<'comments>]| {
+            let start = comments.partition_point(|comment| comment.start < location.start);
+            let end = comments.partition_point(|comment| comment.start < location.end);
+            comments.get(start..end).unwrap_or(&[])
+        };
+        let start = self
+            .empty_lines
+            .partition_point(|line| *line < location.start);
+        let end = self
+            .empty_lines
+            .partition_point(|line| *line < location.end);
+
+        Self {
+            comments: comments_within(self.comments),
+            doc_comments: comments_within(self.doc_comments),
+            module_comments: &[],
+            empty_lines: self.empty_lines.get(start..end).unwrap_or(&[]),
+            new_lines: self.new_lines,
+        }
+    }
+
+    /// Prints the formatted document of the code at the given location,
+    /// returning the edit to replace it with. The first line of the code is
+    /// already indented in the source, all the others are indented to match.
+    ///
+    /// If not all the comments in the formatted code could be printed, no
+    /// edit is returned so that no comment is lost.
+    ///
+    fn print_edit(
+        &self,
+        src: &str,
+        location: SrcSpan,
+        document: Document<'_>,
+    ) -> Result<Option<(SrcSpan, String)>> {
+        if !self.comments.is_empty() || !self.doc_comments.is_empty() {
+            return Ok(None);
+        }
+
+        let line_start = src
+            .get(..location.start as usize)
+            .and_then(|before| before.rfind('\n'))
+            .map_or(0, |index| index + 1);
+        let indent = location.start as usize - line_start;
+
+        let mut formatted = String::new();
+        docvec![" ".repeat(indent), document]
+            .nest(indent as isize)
+            .pretty_print(80, &mut formatted)?;
+        let formatted = formatted.get(indent..).unwrap_or_default().to_string();
+
+        let unchanged =
+            src.get(location.start as usize..location.end as usize) == Some(formatted.as_str());
+        Ok((!unchanged).then_some((location, formatted)))
+    }
+
     fn any_comments(&self, limit: u32) -> bool {
         self.comments
             .first()
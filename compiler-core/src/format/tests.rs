@@ -13,6 +13,7 @@ mod function;
 mod guards;
 mod imports;
 mod pipeline;
+mod range;
 mod record_update;
 mod tuple;
 mod use_;
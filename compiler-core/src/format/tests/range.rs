@@ -0,0 +1,233 @@
+use crate::ast::SrcSpan;
+
+/// Formats the code selected between the two `|` in the source and applies
+/// the resulting edits.
+///
+fn format_range(src: &str) -> String {
+    let start = src.find('|').expect("range start");
+    let end = src.rfind('|').expect("range end") - 1;
+    let src = src.replacen('|', "", 2);
+    let range = SrcSpan::new(start as u32, end as u32);
+    let mut edits = crate::format::pretty_range(
+        &src.as_str().into(),
+        camino::Utf8Path::new("<stdin>"),
+        range,
+    )
+    .unwrap();
+
+    let mut formatted = src;
+    edits.sort_by_key(|(location, _)| std::cmp::Reverse(location.start));
+    for (location, new_text) in edits {
+        formatted.replace_range(location.start as usize..location.end as usize, &new_text);
+    }
+    formatted
+}
+
+#[test]
+fn formats_only_the_selected_function() {
+    assert_eq!(
+        format_range(
+            "fn wibble()   {   1 }
+
+fn |wobble|()   {   2 }
+"
+        ),
+        "fn wibble()   {   1 }
+
+fn wobble() {
+  2
+}
+"
+    );
+}
+
+#[test]
+fn formats_all_the_touched_definitions() {
+    assert_eq!(
+        format_range(
+            "fn wibble()   {   |1 }
+
+const   x   =   1
+
+fn wobble()   {   2 }
+
+fn wubble()   {  | 3 }
+"
+        ),
+        "fn wibble() {
+  1
+}
+
+const x = 1
+
+fn wobble() {
+  2
+}
+
+fn wubble() {
+  3
+}
+"
+    );
+}
+
+#[test]
+fn formats_only_the_selected_statement() {
+    assert_eq!(
+        format_range(
+            "fn main()   {
+  let   x   =   |1|
+  let   y   =   2
+  x
+}
+"
+        ),
+        "fn main()   {
+  let x = 1
+  let   y   =   2
+  x
+}
+"
+    );
+}
+
+#[test]
+fn formats_a_nested_statement_with_its_indentation() {
+    assert_eq!(
+        format_range(
+            "fn main() {
+  let x = {
+    wibble(  1,  2  )
+  }
+  [  |x|  ]
+}
+"
+        ),
+        "fn main() {
+  let x = {
+    wibble(  1,  2  )
+  }
+  [x]
+}
+"
+    );
+}
+
+#[test]
+fn statement_that_breaks_keeps_its_indentation() {
+    assert_eq!(
+        format_range(
+            "fn main() {
+  let x = |wibble|(\"aaaaaaaaaaaaaaaaaaaa\", \"bbbbbbbbbbbbbbbbbbbbbbbbbbbb\", \"cccccccccccccccccccccccccccc\")
+  x
+}
+"
+        ),
+        "fn main() {
+  let x =
+    wibble(
+      \"aaaaaaaaaaaaaaaaaaaa\",
+      \"bbbbbbbbbbbbbbbbbbbbbbbbbbbb\",
+      \"cccccccccccccccccccccccccccc\",
+    )
+  x
+}
+"
+    );
+}
+
+#[test]
+fn formats_the_statement_keeping_its_comments() {
+    assert_eq!(
+        format_range(
+            "fn main()   {
+  let   x   =   [
+    // A comment
+    |1|,
+  ]
+  x
+}
+"
+        ),
+        "fn main()   {
+  let x = [
+    // A comment
+    1,
+  ]
+  x
+}
+"
+    );
+}
+
+#[test]
+fn formats_the_definition_keeping_its_comments_and_attributes() {
+    assert_eq!(
+        format_range(
+            "// Not part of the function
+fn   wibble() { 1 }
+
+/// Documentation
+@deprecated(\"Use something else\")
+pub   fn   |wobble|() {
+  // A comment
+  2
+}
+"
+        ),
+        "// Not part of the function
+fn   wibble() { 1 }
+
+/// Documentation
+@deprecated(\"Use something else\")
+pub fn wobble() {
+  // A comment
+  2
+}
+"
+    );
+}
+
+#[test]
+fn already_formatted_code_produces_no_edits() {
+    let src = "fn main() {
+  let x = 1
+  x
+}
+";
+    let edits = crate::format::pretty_range(
+        &src.into(),
+        camino::Utf8Path::new("<stdin>"),
+        SrcSpan::new(0, src.len() as u32),
+    )
+    .unwrap();
+    assert!(edits.is_empty());
+}
+
+#[test]
+fn selection_outside_any_definition_produces_no_edits() {
+    assert_eq!(
+        format_range(
+            "fn main()   {   1 }
+|
+|
+fn other()   {   2 }
+"
+        ),
+        "fn main()   {   1 }
+
+
+fn other()   {   2 }
+"
+    );
+}
+
+#[test]
+fn invalid_code_is_an_error() {
+    let result = crate::format::pretty_range(
+        &"fn main( {".into(),
+        camino::Utf8Path::new("<stdin>"),
+        SrcSpan::new(0, 1),
+    );
+    assert!(result.is_err());
+}
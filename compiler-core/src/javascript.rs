@@ -15,6 +15,7 @@ use crate::codegen::TypeScriptDeclarations;
 use crate::type_::PRELUDE_MODULE_NAME;
 use crate::{
     ast::{CustomType, Function, Import, ModuleConstant, TypeAlias, *},
+    codegen::definition_spans,
     docvec,
     line_numbers::LineNumbers,
     pretty::*,
@@ -23,6 +24,7 @@ use camino::Utf8Path;
 use ecow::{EcoString, eco_format};
 use expression::Context;
 use itertools::Itertools;
+use std::collections::HashMap;
 
 use self::import::{Imports, Member};
 
@@ -50,6 +52,7 @@ pub struct Generator<'a> {
     target_support: TargetSupport,
     typescript: TypeScriptDeclarations,
     stdlib_package: StdlibPackage,
+    mark_definitions: bool,
 }
 
 impl<'a> Generator<'a> {
@@ -76,6 +79,25 @@ impl<'a> Generator<'a> {
             target_support,
             typescript,
             stdlib_package,
+            mark_definitions: false,
+        }
+    }
+
+    /// Wraps the code generated for each definition in markers, so that
+    /// [`definition_spans::remove_markers`] can find where it ends up.
+    ///
+    pub fn mark_definitions(mut self) -> Self {
+        self.mark_definitions = true;
+        self
+    }
+
+    fn mark_definition(&self, index: usize, outputs: Vec<Output<'a>>) -> Vec<Output<'a>> {
+        if !self.mark_definitions || outputs.is_empty() {
+            return outputs;
+        }
+        match outputs.into_iter().collect::<Result<Vec<_>, _>>() {
+            Ok(documents) => vec![Ok(definition_spans::mark(index, join(documents, lines(2))))],
+            Err(error) => vec![Err(error)],
         }
     }
 
@@ -112,7 +134,11 @@ impl<'a> Generator<'a> {
             self.module
                 .definitions
                 .iter()
-                .flat_map(|s| self.statement(s)),
+                .enumerate()
+                .flat_map(|(index, statement)| {
+                    let outputs = self.statement(statement).into_iter().collect();
+                    self.mark_definition(index, outputs)
+                }),
         );
 
         // Two lines between each statement
@@ -367,13 +393,17 @@ impl<'a> Generator<'a> {
         self.module
             .definitions
             .iter()
-            .flat_map(|statement| match statement {
+            .enumerate()
+            .flat_map(|(index, statement)| match statement {
                 Definition::CustomType(CustomType {
                     publicity,
                     constructors,
                     opaque,
                     ..
-                }) => self.custom_type_definition(constructors, *publicity, *opaque),
+                }) => {
+                    let outputs = self.custom_type_definition(constructors, *publicity, *opaque);
+                    self.mark_definition(index, outputs)
+                }
 
                 Definition::Function(Function { .. })
                 | Definition::TypeAlias(TypeAlias { .. })
@@ -632,6 +662,23 @@ pub fn module(config: ModuleConfig<'_>) -> Result<String, crate::Error> {
     Ok(document.to_pretty_string(80))
 }
 
+/// Generates the code for a module along with the span of the code generated
+/// for each of its definitions, keyed by the definition's index in the module.
+///
+pub fn module_with_definition_spans(
+    config: ModuleConfig<'_>,
+) -> Result<(String, HashMap<usize, SrcSpan>), crate::Error> {
+    let path = config.path.to_path_buf();
+    let src = config.src.clone();
+    let document = Generator::new(config)
+        .mark_definitions()
+        .compile()
+        .map_err(|error| crate::Error::JavaScript { path, src, error })?;
+    Ok(definition_spans::remove_markers(
+        &document.to_pretty_string(80),
+    ))
+}
+
 pub fn ts_declaration(
     module: &TypedModule,
     path: &Utf8Path,
@@ -807,7 +854,7 @@ fn escape_identifier(word: &str) -> EcoString {
     eco_format!("{word}$")
 }
 
-fn maybe_escape_identifier(word: &str) -> EcoString {
+pub(crate) fn maybe_escape_identifier(word: &str) -> EcoString {
     if is_usable_js_identifier(word) {
         EcoString::from(word)
     } else {
@@ -1,17 +1,31 @@
+mod call_hierarchy;
+mod change_signature;
 mod code_action;
+mod code_lens;
 mod compiler;
 mod completer;
+mod configuration;
+mod document_highlight;
 mod edits;
 mod engine;
 mod feedback;
 mod files;
+mod folding_range;
+mod generated_code;
+mod implementation;
+mod inlay_hints;
 mod messages;
+mod move_definition;
 mod progress;
 mod reference;
 mod rename;
+mod rename_module;
 mod router;
+mod selection_range;
+mod semantic_tokens;
 mod server;
 mod signature_help;
+mod workspace_symbols;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Range, SymbolKind,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{Definition, SrcSpan, TypedFunction, visit::Visit},
+    build::Module,
+    reference::ReferenceKind,
+    type_::{ModuleInterface, ModuleValueConstructor, ValueConstructor, ValueConstructorVariant},
+};
+
+use super::{compiler::ModuleSourceInformation, src_span_to_lsp_range, url_from_path};
+
+/// The information needed to find a function again when the client asks for
+/// its incoming or outgoing calls. It is stored in the `data` field of the
+/// call hierarchy item, which the client sends back unchanged.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CallHierarchyData {
+    module: EcoString,
+    name: EcoString,
+}
+
+/// Everything needed to look up functions across the modules of a project.
+///
+pub struct CallHierarchy<'a> {
+    /// The modules of the root package, with their typed AST.
+    pub modules: &'a HashMap<EcoString, Module>,
+    /// The interfaces of all the modules that can be imported, including the
+    /// ones from dependencies.
+    pub importable_modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    pub sources: &'a HashMap<EcoString, ModuleSourceInformation>,
+}
+
+impl CallHierarchy<'_> {
+    /// Returns the call hierarchy item for a module function, if there's one
+    /// with the given name.
+    ///
+    pub fn item(&self, module: &EcoString, name: &EcoString) -> Option<CallHierarchyItem> {
+        let location = match &self
+            .importable_modules
+            .get(module)?
+            .values
+            .get(name)?
+            .variant
+        {
+            ValueConstructorVariant::ModuleFn { location, .. } => *location,
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::ModuleConstant { .. }
+            | ValueConstructorVariant::LocalConstant { .. }
+            | ValueConstructorVariant::Record { .. } => return None,
+        };
+
+        // Functions from the root package have their whole definition
+        // selected, for dependencies only the head is known.
+        let (range, selection_range) = match self.function(module, name) {
+            Some(function) => (
+                function.full_location(),
+                function
+                    .name
+                    .as_ref()
+                    .map_or(location, |(location, _)| *location),
+            ),
+            None => (location, location),
+        };
+
+        let source = self.sources.get(module)?;
+        Some(CallHierarchyItem {
+            name: name.to_string(),
+            kind: SymbolKind::FUNCTION,
+            tags: None,
+            detail: Some(module.to_string()),
+            uri: url_from_path(&source.path)?,
+            range: src_span_to_lsp_range(range, &source.line_numbers),
+            selection_range: src_span_to_lsp_range(selection_range, &source.line_numbers),
+            data: serde_json::to_value(CallHierarchyData {
+                module: module.clone(),
+                name: name.clone(),
+            })
+            .ok(),
+        })
+    }
+
+    /// Returns all the functions of the root package referencing the function
+    /// of the given item, along with the ranges of each reference.
+    ///
+    pub fn incoming_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+        let Some(CallHierarchyData { module, name }) = item_data(item) else {
+            return vec![];
+        };
+        let key = (module, name);
+
+        let mut calls = vec![];
+        for (caller_module, module) in self.modules.iter().sorted_by_key(|(name, _)| *name) {
+            let Some(references) = module.ast.type_info.references.value_references.get(&key)
+            else {
+                continue;
+            };
+            let Some(source) = self.sources.get(caller_module) else {
+                continue;
+            };
+
+            let references = references.iter().filter(|reference| match reference.kind {
+                ReferenceKind::Qualified | ReferenceKind::Unqualified | ReferenceKind::Alias => {
+                    true
+                }
+                ReferenceKind::Import | ReferenceKind::Definition => false,
+            });
+
+            // Each reference is grouped with the others made from the same
+            // function.
+            let callers = references
+                .filter_map(|reference| {
+                    let caller = module_functions(module).find(|function| {
+                        function.full_location().contains(reference.location.start)
+                    })?;
+                    let (_, caller) = caller.name.as_ref()?;
+                    Some((caller, reference.location))
+                })
+                .into_group_map();
+
+            for (caller, locations) in callers.into_iter().sorted_by_key(|(_, locations)| {
+                locations.iter().map(|location| location.start).min()
+            }) {
+                let Some(from) = self.item(caller_module, caller) else {
+                    continue;
+                };
+                calls.push(CallHierarchyIncomingCall {
+                    from,
+                    from_ranges: ranges(&locations, source),
+                });
+            }
+        }
+        calls
+    }
+
+    /// Returns all the module functions referenced by the function of the
+    /// given item, along with the ranges of each reference. Only functions
+    /// from the root package can be inspected.
+    ///
+    pub fn outgoing_calls(&self, item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+        let Some(CallHierarchyData { module, name }) = item_data(item) else {
+            return vec![];
+        };
+        let (Some(function), Some(source)) =
+            (self.function(&module, &name), self.sources.get(&module))
+        else {
+            return vec![];
+        };
+
+        let mut finder = FindFunctionReferences { references: vec![] };
+        finder.visit_typed_function(function);
+
+        let mut calls = vec![];
+        let callees = finder
+            .references
+            .into_iter()
+            .into_group_map_by(|(module, name, _)| (module.clone(), name.clone()));
+        for ((module, name), references) in callees.into_iter().sorted_by_key(|(_, references)| {
+            references
+                .iter()
+                .map(|(_, _, location)| location.start)
+                .min()
+        }) {
+            let Some(to) = self.item(&module, &name) else {
+                continue;
+            };
+            let locations = references
+                .into_iter()
+                .map(|(_, _, location)| location)
+                .collect_vec();
+            calls.push(CallHierarchyOutgoingCall {
+                to,
+                from_ranges: ranges(&locations, source),
+            });
+        }
+        calls
+    }
+
+    fn function(&self, module: &EcoString, name: &EcoString) -> Option<&TypedFunction> {
+        module_functions(self.modules.get(module)?).find(
+            |function| matches!(&function.name, Some((_, function_name)) if function_name == name),
+        )
+    }
+}
+
+fn item_data(item: &CallHierarchyItem) -> Option<CallHierarchyData> {
+    serde_json::from_value(item.data.clone()?).ok()
+}
+
+fn module_functions(module: &Module) -> impl Iterator<Item = &TypedFunction> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(function) => Some(function),
+            Definition::TypeAlias(_)
+            | Definition::CustomType(_)
+            | Definition::Import(_)
+            | Definition::ModuleConstant(_) => None,
+        })
+}
+
+fn ranges(locations: &[SrcSpan], source: &ModuleSourceInformation) -> Vec<Range> {
+    locations
+        .iter()
+        .map(|location| src_span_to_lsp_range(*location, &source.line_numbers))
+        .collect()
+}
+
+/// Finds all the references to module functions, qualified or not.
+///
+struct FindFunctionReferences {
+    references: Vec<(EcoString, EcoString, SrcSpan)>,
+}
+
+impl<'ast> Visit<'ast> for FindFunctionReferences {
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        _name: &'ast EcoString,
+    ) {
+        if let ValueConstructorVariant::ModuleFn { module, name, .. } = &constructor.variant {
+            self.references
+                .push((module.clone(), name.clone(), *location));
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        field_start: &'ast u32,
+        type_: &'ast std::sync::Arc<crate::type_::Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        if let ModuleValueConstructor::Fn { module, name, .. } = constructor {
+            self.references.push((
+                module.clone(),
+                name.clone(),
+                SrcSpan::new(*field_start, location.end),
+            ));
+        }
+        crate::ast::visit::visit_typed_expr_module_select(
+            self,
+            location,
+            field_start,
+            type_,
+            label,
+            module_name,
+            module_alias,
+            constructor,
+        );
+    }
+}
@@ -0,0 +1,708 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionParams, Command, Location, Position, Url, WorkspaceEdit,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{
+        ArgNames, Definition, ImplicitCallArgOrigin, SrcSpan, TypedArg, TypedDefinition, TypedExpr,
+        TypedFunction,
+        visit::{Visit, visit_typed_expr_call},
+    },
+    build::Module,
+    line_numbers::LineNumbers,
+    reference::ReferenceKind,
+    type_::{ModuleValueConstructor, Type, TypedCallArg, ValueConstructorVariant},
+};
+
+use super::{
+    code_action::{CodeActionBuilder, is_valid_lowercase_name},
+    engine::overlaps,
+    move_definition::{code_at, references, text_edits},
+    reference::find_variable_references,
+    src_span_to_lsp_range, url_from_path,
+};
+
+/// The command clients run to change the parameters of a function.
+pub const CHANGE_SIGNATURE_COMMAND: &str = "gleam.changeSignature";
+
+/// The arguments of the change signature command: the function whose head is
+/// at `position` in the module at `uri` gets the given `parameters`, in
+/// order.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureArguments {
+    pub uri: Url,
+    pub position: Position,
+    pub parameters: Vec<Parameter>,
+}
+
+/// A parameter of the changed function. Any existing parameter that is not
+/// listed is removed.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Parameter {
+    /// The parameter at `index` in the original signature, with its new
+    /// label.
+    Existing {
+        index: usize,
+        label: Option<EcoString>,
+    },
+    /// A new parameter. Every call of the function passes it `default`.
+    New {
+        name: EcoString,
+        label: Option<EcoString>,
+        annotation: Option<EcoString>,
+        default: EcoString,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeSignatureOutcome {
+    /// The signature is changed by applying the edit. The `unsafe_calls`
+    /// couldn't be rewritten safely and need to be updated by hand.
+    Changed {
+        edit: WorkspaceEdit,
+        unsafe_calls: Vec<Location>,
+    },
+    /// The signature can't be changed as requested.
+    Refused { reason: String },
+}
+
+fn changeable_function(definition: &TypedDefinition) -> Option<&TypedFunction> {
+    match definition {
+        Definition::Function(function)
+            if function.name.is_some()
+                && function.external_erlang.is_none()
+                && function.external_javascript.is_none() =>
+        {
+            Some(function)
+        }
+        _ => None,
+    }
+}
+
+/// Code actions to change the signature of the function whose parameter is
+/// under the cursor: the parameter can be moved, labelled or removed if it's
+/// unused, and a new parameter can be added after it.
+///
+pub fn code_actions(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    params: &CodeActionParams,
+) -> Vec<CodeAction> {
+    let Some((function, index, argument)) = module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(changeable_function)
+        .find_map(|function| {
+            let (index, argument) = function.arguments.iter().find_position(|argument| {
+                overlaps(
+                    params.range,
+                    src_span_to_lsp_range(argument.location, line_numbers),
+                )
+            })?;
+            Some((function, index, argument))
+        })
+    else {
+        return vec![];
+    };
+
+    let name = parameter_name(argument);
+    let current = function
+        .arguments
+        .iter()
+        .enumerate()
+        .map(|(index, argument)| Parameter::Existing {
+            index,
+            label: argument.names.get_label().cloned(),
+        })
+        .collect_vec();
+
+    let mut changes = vec![];
+    if index > 0 {
+        let mut parameters = current.clone();
+        parameters.swap(index - 1, index);
+        changes.push((format!("Move parameter `{name}` left"), parameters));
+    }
+    if index + 1 < current.len() {
+        let mut parameters = current.clone();
+        parameters.swap(index, index + 1);
+        changes.push((format!("Move parameter `{name}` right"), parameters));
+    }
+    if is_unused(module, argument) {
+        let mut parameters = current.clone();
+        let _ = parameters.remove(index);
+        changes.push((format!("Remove unused parameter `{name}`"), parameters));
+    }
+    if let ArgNames::Named { name, .. } = &argument.names {
+        let mut parameters = current.clone();
+        if let Some(parameter) = parameters.get_mut(index) {
+            *parameter = Parameter::Existing {
+                index,
+                label: Some(name.clone()),
+            };
+        }
+        changes.push((format!("Label parameter `{name}`"), parameters));
+    }
+    let mut parameters = current;
+    parameters.insert(
+        index + 1,
+        Parameter::New {
+            name: new_parameter_name(function),
+            label: None,
+            annotation: None,
+            default: "todo".into(),
+        },
+    );
+    changes.push((format!("Add parameter after `{name}`"), parameters));
+
+    let position = src_span_to_lsp_range(function.location, line_numbers).start;
+    let mut actions = vec![];
+    for (title, parameters) in changes {
+        let arguments = ChangeSignatureArguments {
+            uri: params.text_document.uri.clone(),
+            position,
+            parameters,
+        };
+        let Ok(arguments) = serde_json::to_value(arguments) else {
+            continue;
+        };
+
+        CodeActionBuilder::new(&title)
+            .kind(CodeActionKind::REFACTOR)
+            .command(Command {
+                title: title.clone(),
+                command: CHANGE_SIGNATURE_COMMAND.into(),
+                arguments: Some(vec![arguments]),
+            })
+            .preferred(false)
+            .push_to(&mut actions);
+    }
+    actions
+}
+
+/// Changes the parameters of the function whose head is at `position` in
+/// `module`, rewriting all of its calls in the project.
+///
+/// Calls are rewritten even when piped into or used with `use`, as long as
+/// the piped value stays the first argument and the `use` callback the last
+/// one. Calls that can't be safely rewritten, like those passing an
+/// expression with side effects to a removed parameter, are left untouched
+/// and reported instead.
+///
+pub fn change_signature(
+    modules: &HashMap<EcoString, Module>,
+    module: &Module,
+    position: Position,
+    parameters: Vec<Parameter>,
+) -> Option<ChangeSignatureOutcome> {
+    let line_numbers = LineNumbers::new(&module.code);
+    let byte_index = line_numbers.byte_index(position.line, position.character);
+    let function = module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) if function.location.contains(byte_index) => {
+                Some(function)
+            }
+            _ => None,
+        })?;
+    let (_, name) = function.name.as_ref()?;
+
+    let refuse = |reason: String| Some(ChangeSignatureOutcome::Refused { reason });
+
+    if function.external_erlang.is_some() || function.external_javascript.is_some() {
+        return refuse(format!(
+            "The signature of the external function `{name}` can't be changed"
+        ));
+    }
+    if let Some(reason) = invalid_parameters(module, function, &parameters) {
+        return refuse(reason);
+    }
+
+    let changer = SignatureChanger {
+        function,
+        parameters: &parameters,
+    };
+    let mut changes = HashMap::new();
+    let mut unsafe_calls = vec![];
+    for other in modules.values().sorted_by_key(|other| &other.name) {
+        let mut edits = vec![];
+        if other.name == module.name {
+            edits.push(changer.definition_edit(&module.code));
+        }
+
+        let mut finder = CallFinder {
+            module: &module.name,
+            name,
+            calls: vec![],
+        };
+        finder.visit_typed_module(&other.ast);
+
+        let mut unsafe_locations = vec![];
+        for call in &finder.calls {
+            match changer.call_edit(&other.code, call) {
+                Some(edit) => edits.push(edit),
+                None => unsafe_locations.push(call.location),
+            }
+        }
+        if !changer.only_changes_labels() {
+            let references =
+                references(other).filter(|(_, referenced_module, referenced, reference)| {
+                    *referenced_module == &module.name
+                        && *referenced == name
+                        && matches!(
+                            reference.kind,
+                            ReferenceKind::Qualified | ReferenceKind::Unqualified
+                        )
+                        && !finder
+                            .calls
+                            .iter()
+                            .any(|call| call.fun.contains(reference.location.start))
+                });
+            unsafe_locations.extend(references.map(|(_, _, _, reference)| reference.location));
+        }
+
+        let edits = edits
+            .into_iter()
+            .filter(|(span, new_text)| code_at(&other.code, *span) != new_text)
+            .collect_vec();
+        if edits.is_empty() && unsafe_locations.is_empty() {
+            continue;
+        }
+        let Some(uri) = url_from_path(other.input_path.as_str()) else {
+            continue;
+        };
+        let other_line_numbers = LineNumbers::new(&other.code);
+        unsafe_calls.extend(
+            unsafe_locations
+                .into_iter()
+                .sorted_by_key(|location| location.start)
+                .map(|location| Location {
+                    uri: uri.clone(),
+                    range: src_span_to_lsp_range(location, &other_line_numbers),
+                }),
+        );
+        if !edits.is_empty() {
+            let _ = changes.insert(uri, text_edits(other, edits));
+        }
+    }
+
+    Some(ChangeSignatureOutcome::Changed {
+        edit: WorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+            change_annotations: None,
+        },
+        unsafe_calls,
+    })
+}
+
+/// The reason why the new parameters are not a valid signature for the
+/// function, if any.
+///
+fn invalid_parameters(
+    module: &Module,
+    function: &TypedFunction,
+    parameters: &[Parameter],
+) -> Option<String> {
+    let arguments = &function.arguments;
+    let mut kept = vec![];
+    let mut names = vec![];
+    let mut labels = vec![];
+    for parameter in parameters {
+        let (shown_name, name, label) = match parameter {
+            Parameter::Existing { index, label } => {
+                let Some(argument) = arguments.get(*index) else {
+                    return Some(format!("There's no parameter at position {index}"));
+                };
+                if kept.contains(index) {
+                    return Some(format!(
+                        "The parameter `{}` is used more than once",
+                        parameter_name(argument)
+                    ));
+                }
+                kept.push(*index);
+                (
+                    parameter_name(argument),
+                    argument.names.get_variable_name(),
+                    label,
+                )
+            }
+            Parameter::New {
+                name,
+                label,
+                default,
+                ..
+            } => {
+                if !is_valid_lowercase_name(name) {
+                    return Some(format!("`{name}` is not a valid parameter name"));
+                }
+                if default.trim().is_empty() {
+                    return Some(format!("The new parameter `{name}` needs a default value"));
+                }
+                (name, Some(name), label)
+            }
+        };
+
+        // Labelled parameters must come after all the unlabelled ones.
+        if label.is_none()
+            && let Some(last_label) = labels.last()
+        {
+            let problem = "can't come after the labelled parameter";
+            return Some(format!(
+                "The unlabelled parameter `{shown_name}` {problem} `{last_label}`"
+            ));
+        }
+
+        if let Some(name) = name {
+            if names.contains(&name) {
+                return Some(format!("There's more than one parameter named `{name}`"));
+            }
+            names.push(name);
+        }
+        if let Some(label) = label {
+            if !is_valid_lowercase_name(label) {
+                return Some(format!("`{label}` is not a valid label"));
+            }
+            if labels.contains(&label) {
+                return Some(format!(
+                    "There's more than one parameter labelled `{label}`"
+                ));
+            }
+            labels.push(label);
+        }
+    }
+
+    arguments
+        .iter()
+        .enumerate()
+        .find(|(index, argument)| !kept.contains(index) && !is_unused(module, argument))
+        .map(|(_, argument)| {
+            format!(
+                "The parameter `{}` is used by the function and can't be removed",
+                parameter_name(argument)
+            )
+        })
+}
+
+struct SignatureChanger<'a> {
+    function: &'a TypedFunction,
+    parameters: &'a [Parameter],
+}
+
+impl SignatureChanger<'_> {
+    /// Whether the new parameters are the same as the old ones, only with
+    /// different labels. Labels are not part of a function's type, so
+    /// references to the function that are not calls are not affected.
+    ///
+    fn only_changes_labels(&self) -> bool {
+        self.parameters.len() == self.function.arguments.len()
+            && self
+                .parameters
+                .iter()
+                .enumerate()
+                .all(|(position, parameter)| match parameter {
+                    Parameter::Existing { index, .. } => *index == position,
+                    Parameter::New { .. } => false,
+                })
+    }
+
+    /// Replaces the parameters in the function's head.
+    ///
+    fn definition_edit(&self, code: &str) -> (SrcSpan, String) {
+        let parameters = self
+            .parameters
+            .iter()
+            .filter_map(|parameter| match parameter {
+                Parameter::Existing { index, label } => {
+                    let argument = self.function.arguments.get(*index)?;
+                    let name_start = match &argument.names {
+                        ArgNames::Discard { location, .. } | ArgNames::Named { location, .. } => {
+                            location.start
+                        }
+                        ArgNames::LabelledDiscard { name_location, .. }
+                        | ArgNames::NamedLabelled { name_location, .. } => name_location.start,
+                    };
+                    let parameter = code_at(code, SrcSpan::new(name_start, argument.location.end));
+                    Some(match label {
+                        Some(label) => format!("{label} {parameter}"),
+                        None => parameter.into(),
+                    })
+                }
+                Parameter::New {
+                    name,
+                    label,
+                    annotation,
+                    ..
+                } => {
+                    let mut parameter = String::new();
+                    if let Some(label) = label {
+                        parameter.push_str(&format!("{label} "));
+                    }
+                    parameter.push_str(name);
+                    if let Some(annotation) = annotation {
+                        parameter.push_str(&format!(": {annotation}"));
+                    }
+                    Some(parameter)
+                }
+            })
+            .join(", ");
+
+        let name_end = self
+            .function
+            .name
+            .as_ref()
+            .map_or(self.function.location.start, |(location, _)| location.end);
+        let last_end = self
+            .function
+            .arguments
+            .last()
+            .map_or(name_end, |argument| argument.location.end);
+        let span =
+            arguments_span(code, name_end, last_end).unwrap_or(SrcSpan::new(name_end, name_end));
+        (span, parameters)
+    }
+
+    /// Rewrites the arguments of a call to match the new parameters, or
+    /// returns `None` if the call can't be safely rewritten.
+    ///
+    fn call_edit(&self, code: &str, call: &Call<'_>) -> Option<(SrcSpan, String)> {
+        let arguments = call.arguments;
+        if arguments.len() != self.function.arguments.len() {
+            return None;
+        }
+
+        let mut positional = vec![];
+        let mut labelled = vec![];
+        // The indices of the arguments kept in the rewritten call, positional
+        // and labelled ones apart.
+        let mut positional_order = vec![];
+        let mut labelled_order = vec![];
+        let mut piped = false;
+        for (position, parameter) in self.parameters.iter().enumerate() {
+            let (index, new_label) = match parameter {
+                Parameter::Existing { index, label } => (*index, label),
+                Parameter::New { label, default, .. } => {
+                    match label {
+                        Some(label) => labelled.push(format!("{label}: {default}")),
+                        None if lines_up(position, &positional, piped) => {
+                            positional.push(default.to_string())
+                        }
+                        None => return None,
+                    }
+                    continue;
+                }
+            };
+
+            let argument = arguments.get(index)?;
+            match argument.implicit {
+                None => (),
+                // The piped value is always passed as the first positional
+                // argument.
+                Some(ImplicitCallArgOrigin::Pipe) if positional.is_empty() && !piped => {
+                    piped = true;
+                    continue;
+                }
+                // The `use` callback is always passed as the last argument.
+                Some(ImplicitCallArgOrigin::Use) if position + 1 == self.parameters.len() => {
+                    continue;
+                }
+                Some(_) => return None,
+            }
+
+            let value = if argument.uses_label_shorthand() {
+                argument.label.clone().unwrap_or_default().to_string()
+            } else {
+                code_at(code, argument.value.location()).to_string()
+            };
+            // An argument for a labelled parameter is always passed with its
+            // label, as it could otherwise end up in the wrong position.
+            match (&argument.label, new_label) {
+                (Some(old), Some(new)) if old == new => {
+                    labelled.push(code_at(code, argument.location).to_string());
+                    labelled_order.push(index);
+                }
+                (_, Some(new)) => {
+                    labelled.push(format!("{new}: {value}"));
+                    labelled_order.push(index);
+                }
+                (_, None) if lines_up(position, &positional, piped) => {
+                    positional.push(value);
+                    positional_order.push(index);
+                }
+                (_, None) => return None,
+            }
+        }
+
+        // Arguments are evaluated in the order they are written in, so the
+        // ones with side effects must keep their order.
+        let impure_starts = positional_order
+            .into_iter()
+            .chain(labelled_order)
+            .filter_map(|index| arguments.get(index))
+            .filter(|argument| !argument.value.is_pure_value_constructor())
+            .map(|argument| argument.location.start)
+            .collect_vec();
+        if !impure_starts.is_sorted() {
+            return None;
+        }
+
+        // The values passed to removed parameters are dropped, so they
+        // must not have any side effects.
+        let can_drop_removed = arguments.iter().enumerate().all(|(index, argument)| {
+            self.keeps(index)
+                || (argument.implicit.is_none()
+                    && !argument.is_capture_hole()
+                    && argument.value.is_pure_value_constructor())
+        });
+        if !can_drop_removed {
+            return None;
+        }
+
+        let new_arguments = positional.into_iter().chain(labelled).join(", ");
+        let last_end = arguments
+            .iter()
+            .filter(|argument| !argument.is_implicit())
+            .map(|argument| argument.location.end)
+            .max()
+            .unwrap_or(call.fun.end);
+        match arguments_span(code, call.fun.end, last_end) {
+            Some(span) => Some((span, new_arguments)),
+            // A function called without parentheses, like in `a |> wibble`
+            // or `use <- wibble`.
+            None if new_arguments.is_empty() => {
+                Some((SrcSpan::new(call.fun.end, call.fun.end), String::new()))
+            }
+            None => Some((
+                SrcSpan::new(call.fun.end, call.fun.end),
+                format!("({new_arguments})"),
+            )),
+        }
+    }
+
+    fn keeps(&self, index: usize) -> bool {
+        self.parameters.iter().any(|parameter| match parameter {
+            Parameter::Existing {
+                index: kept_index, ..
+            } => *kept_index == index,
+            Parameter::New { .. } => false,
+        })
+    }
+}
+
+/// Whether an unlabelled argument for the parameter at the given position
+/// would be passed to that same parameter, that is if all the parameters
+/// before it are also passed positionally.
+///
+fn lines_up(position: usize, positional: &[String], piped: bool) -> bool {
+    positional.len() + usize::from(piped) == position
+}
+
+/// The span between the parentheses of a call or of a function's head,
+/// starting from the end of the function's name. `last_end` is the end of
+/// the last argument, if there's any.
+///
+fn arguments_span(code: &str, name_end: u32, last_end: u32) -> Option<SrcSpan> {
+    let after_name = code.get(name_end as usize..)?;
+    let open = name_end + (after_name.len() - after_name.trim_start().len()) as u32;
+    if !after_name.trim_start().starts_with('(') {
+        return None;
+    }
+    let search_start = last_end.max(open + 1);
+    let close = search_start + code.get(search_start as usize..)?.find(')')? as u32;
+    Some(SrcSpan::new(open + 1, close))
+}
+
+fn parameter_name(argument: &TypedArg) -> &EcoString {
+    match &argument.names {
+        ArgNames::Discard { name, .. }
+        | ArgNames::LabelledDiscard { name, .. }
+        | ArgNames::Named { name, .. }
+        | ArgNames::NamedLabelled { name, .. } => name,
+    }
+}
+
+fn is_unused(module: &Module, argument: &TypedArg) -> bool {
+    match &argument.names {
+        ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => true,
+        ArgNames::Named { location, .. }
+        | ArgNames::NamedLabelled {
+            name_location: location,
+            ..
+        } => find_variable_references(&module.ast, *location).is_empty(),
+    }
+}
+
+/// A name for a new parameter that doesn't clash with the existing ones.
+///
+fn new_parameter_name(function: &TypedFunction) -> EcoString {
+    let is_taken = |name: &str| {
+        function
+            .arguments
+            .iter()
+            .any(|argument| parameter_name(argument) == name)
+    };
+    let mut name = EcoString::from("parameter");
+    let mut suffix = 2;
+    while is_taken(&name) {
+        name = format!("parameter_{suffix}").into();
+        suffix += 1;
+    }
+    name
+}
+
+struct Call<'a> {
+    location: SrcSpan,
+    /// The location of the called function, like `wibble` or `wobble.wibble`.
+    fun: SrcSpan,
+    arguments: &'a [TypedCallArg],
+}
+
+/// Finds all the calls to a module function.
+///
+struct CallFinder<'a> {
+    module: &'a EcoString,
+    name: &'a EcoString,
+    calls: Vec<Call<'a>>,
+}
+
+impl<'a> Visit<'a> for CallFinder<'a> {
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'a SrcSpan,
+        type_: &'a Arc<Type>,
+        fun: &'a TypedExpr,
+        args: &'a [TypedCallArg],
+    ) {
+        let called = match fun {
+            TypedExpr::Var { constructor, .. } => match &constructor.variant {
+                ValueConstructorVariant::ModuleFn { module, name, .. } => Some((module, name)),
+                _ => None,
+            },
+            TypedExpr::ModuleSelect {
+                constructor: ModuleValueConstructor::Fn { module, name, .. },
+                ..
+            } => Some((module, name)),
+            _ => None,
+        };
+        if called.is_some_and(|(module, name)| module == self.module && name == self.name) {
+            self.calls.push(Call {
+                location: *location,
+                fun: fun.location(),
+                arguments: args,
+            });
+        }
+
+        visit_typed_expr_call(self, location, type_, fun, args);
+    }
+}
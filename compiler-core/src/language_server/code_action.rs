@@ -14,23 +14,30 @@ use crate::{
     io::{BeamCompiler, CommandExecutor, FileSystemReader, FileSystemWriter},
     line_numbers::LineNumbers,
     parse::{extra::ModuleExtra, lexer::str_to_keyword},
+    reference::ReferenceKind,
     type_::{
         self, FieldMap, ModuleValueConstructor, Type, TypeVar, TypedCallArg, ValueConstructor,
-        error::{ModuleSuggestion, VariableOrigin},
+        error::{ModuleSuggestion, UnifyErrorSituation, VariableOrigin},
         printer::{Names, Printer},
     },
+    warning::WarningEmitter,
 };
 use ecow::{EcoString, eco_format};
 use heck::ToSnakeCase;
 use im::HashMap;
 use itertools::Itertools;
-use lsp_types::{CodeAction, CodeActionKind, CodeActionParams, Position, Range, TextEdit, Url};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionParams, Command, Position, Range, TextEdit, Url,
+};
 use vec1::{Vec1, vec1};
 
 use super::{
     TextEdits,
     compiler::LspProjectCompiler,
-    edits::{add_newlines_after_import, get_import_edit, position_of_first_definition_if_import},
+    edits::{
+        add_newlines_after_import, documentation_start, get_import_edit,
+        position_of_first_definition_if_import, trailing_whitespace_end,
+    },
     engine::{overlaps, within},
     reference::find_variable_references,
     src_span_to_lsp_range,
@@ -72,6 +79,11 @@ impl CodeActionBuilder {
         self
     }
 
+    pub fn command(mut self, command: Command) -> Self {
+        self.action.command = Some(command);
+        self
+    }
+
     pub fn preferred(mut self, is_preferred: bool) -> Self {
         self.action.is_preferred = Some(is_preferred);
         self
@@ -1047,6 +1059,460 @@ pub fn code_action_add_missing_patterns(
     }
 }
 
+const INT_MODULE: &str = "gleam/int";
+const FLOAT_MODULE: &str = "gleam/float";
+const STRING_MODULE: &str = "gleam/string";
+const OPTION_MODULE: &str = "gleam/option";
+const RESULT_MODULE: &str = "gleam/result";
+
+/// Quick fixes for a value whose type doesn't match the expected one when
+/// there's an obvious way to convert it: wrapping it in `Ok`, `Error` or
+/// `Some`, converting between `Int` and `Float`, concatenating a list of
+/// strings, or unwrapping a `Result` with `result.try`.
+///
+/// Fixes using a function from the standard library are only offered if the
+/// module it's defined in can be imported, as told by `can_import`.
+///
+pub fn code_action_fix_type_mismatch(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    params: &CodeActionParams,
+    error: &Option<Error>,
+    can_import: impl Fn(&str) -> bool,
+    actions: &mut Vec<CodeAction>,
+) {
+    let uri = &params.text_document.uri;
+    let Some(Error::Type { errors, .. }) = error else {
+        return;
+    };
+
+    for error in errors {
+        let type_::Error::CouldNotUnify {
+            location,
+            situation,
+            expected,
+            given,
+        } = error
+        else {
+            continue;
+        };
+        // When the types of two functions don't match there's no single
+        // value that could be converted.
+        if let Some(
+            UnifyErrorSituation::PipeTypeMismatch | UnifyErrorSituation::FunctionsMismatch { .. },
+        ) = situation
+        {
+            continue;
+        }
+        let range = src_span_to_lsp_range(*location, line_numbers);
+        if !overlaps(params.range, range) {
+            continue;
+        }
+
+        let printer = Printer::new(&module.ast.names);
+        let mut fixes = vec![];
+        let mut wrap = |title: String, function: String, import: Option<&str>| {
+            let mut edits = TextEdits::new(line_numbers);
+            edits.insert(location.start, format!("{function}("));
+            edits.insert(location.end, ")".into());
+            if let Some(import) = import {
+                maybe_import(&mut edits, module, import);
+            }
+            fixes.push((title, edits.edits));
+        };
+
+        if let Some((ok, error)) = expected.result_types() {
+            if could_unify(&ok, given) {
+                wrap("Wrap in `Ok`".into(), "Ok".into(), None);
+            }
+            if could_unify(&error, given) {
+                wrap("Wrap in `Error`".into(), "Error".into(), None);
+            }
+        }
+        if option_value_type(expected).is_some_and(|value| could_unify(&value, given))
+            && can_import(OPTION_MODULE)
+        {
+            let (some, import) = option_some(module, &printer);
+            wrap("Wrap in `Some`".into(), some, import);
+        }
+        if expected.is_float() && given.is_int() && can_import(INT_MODULE) {
+            let function = format!("{}.to_float", printer.print_module(INT_MODULE));
+            wrap("Use `int.to_float`".into(), function, Some(INT_MODULE));
+        }
+        if expected.is_int() && given.is_float() && can_import(FLOAT_MODULE) {
+            let function = format!("{}.round", printer.print_module(FLOAT_MODULE));
+            wrap("Use `float.round`".into(), function, Some(FLOAT_MODULE));
+        }
+        if expected.is_string()
+            && given.list_type().is_some_and(|element| element.is_string())
+            && can_import(STRING_MODULE)
+        {
+            let function = format!("{}.concat", printer.print_module(STRING_MODULE));
+            wrap("Use `string.concat`".into(), function, Some(STRING_MODULE));
+        }
+        if can_import(RESULT_MODULE)
+            && let Some(edits) =
+                use_result_try(module, line_numbers, &printer, *location, expected, given)
+        {
+            fixes.push(("Use `result.try`".into(), edits));
+        }
+
+        // When there's more than one way to fix the error, it's up to the
+        // programmer to pick one.
+        let preferred = fixes.len() == 1;
+        for (title, edits) in fixes {
+            CodeActionBuilder::new(&title)
+                .kind(CodeActionKind::QUICKFIX)
+                .changes(uri.clone(), edits)
+                .preferred(preferred)
+                .push_to(actions);
+        }
+    }
+}
+
+/// The type wrapped by an `Option`, if the type is a `gleam/option.Option`.
+///
+fn option_value_type(type_: &Type) -> Option<Arc<Type>> {
+    match type_ {
+        Type::Named {
+            module, name, args, ..
+        } if module == OPTION_MODULE && name == "Option" => args.first().cloned(),
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Link { type_ } => option_value_type(type_),
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => None,
+        },
+        Type::Named { .. } | Type::Fn { .. } | Type::Tuple { .. } => None,
+    }
+}
+
+/// Finds the `let` assigning the `Result` used where its `Ok` value is
+/// expected: the one defining the variable with the mismatched name that is
+/// in scope where it's used, or the one whose value is the mismatched
+/// expression. Along with it, the type of the function body or block the
+/// `let` is in, which is what `result.try` would have to return.
+///
+struct ResultAssignmentFinder<'a> {
+    location: SrcSpan,
+    name: &'a str,
+    /// For each function body and block being visited, whether it contains
+    /// the mismatched expression and the type it evaluates to.
+    scopes: Vec<(bool, Option<Arc<Type>>)>,
+    /// Whether the pattern being visited defines variables that are in scope
+    /// where the mismatched expression is.
+    pattern_in_scope: bool,
+    /// The start of the innermost definition of the mismatched variable, and
+    /// the `let` defining it if that's what it is.
+    definition: Option<(u32, Option<ResultAssignment<'a>>)>,
+    value_assignment: Option<ResultAssignment<'a>>,
+}
+
+type ResultAssignment<'a> = (&'a TypedAssignment, Option<Arc<Type>>);
+
+impl<'a> ResultAssignmentFinder<'a> {
+    fn new(location: SrcSpan, name: &'a str) -> Self {
+        Self {
+            location,
+            name,
+            scopes: vec![],
+            pattern_in_scope: false,
+            definition: None,
+            value_assignment: None,
+        }
+    }
+
+    fn find(mut self, function: &'a ast::TypedFunction) -> Option<ResultAssignment<'a>> {
+        self.visit_typed_function(function);
+        self.value_assignment
+            .or_else(|| self.definition.and_then(|(_, assignment)| assignment))
+    }
+
+    fn define(&mut self, name: &str, location: SrcSpan, assignment: Option<ResultAssignment<'a>>) {
+        // Variables defined later shadow the ones defined before them.
+        if name == self.name
+            && self
+                .definition
+                .as_ref()
+                .is_none_or(|(start, _)| *start <= location.start)
+        {
+            self.definition = Some((location.start, assignment));
+        }
+    }
+
+    fn define_arguments(&mut self, arguments: &[TypedArg]) {
+        for argument in arguments {
+            if let Some(name) = argument.names.get_variable_name() {
+                self.define(name, argument.location, None);
+            }
+        }
+    }
+
+    fn visit_scope(
+        &mut self,
+        location: SrcSpan,
+        type_: Option<Arc<Type>>,
+        visit: impl FnOnce(&mut Self),
+    ) {
+        self.scopes
+            .push((location.contains(self.location.start), type_));
+        visit(self);
+        _ = self.scopes.pop();
+    }
+}
+
+impl<'a> ast::visit::Visit<'a> for ResultAssignmentFinder<'a> {
+    fn visit_typed_function(&mut self, fun: &'a ast::TypedFunction) {
+        self.define_arguments(&fun.arguments);
+        self.visit_scope(fun.full_location(), Some(fun.return_type.clone()), |this| {
+            ast::visit::visit_typed_function(this, fun)
+        });
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'a SrcSpan,
+        type_: &'a Arc<Type>,
+        kind: &'a FunctionLiteralKind,
+        args: &'a [TypedArg],
+        body: &'a Vec1<TypedStatement>,
+        return_annotation: &'a Option<ast::TypeAst>,
+    ) {
+        if location.contains(self.location.start) {
+            self.define_arguments(args);
+        }
+        self.visit_scope(*location, type_.return_type(), |this| {
+            ast::visit::visit_typed_expr_fn(
+                this,
+                location,
+                type_,
+                kind,
+                args,
+                body,
+                return_annotation,
+            )
+        });
+    }
+
+    fn visit_typed_expr_block(&mut self, location: &'a SrcSpan, statements: &'a [TypedStatement]) {
+        let type_ = statements.last().map(TypedStatement::type_);
+        self.visit_scope(*location, type_, |this| {
+            ast::visit::visit_typed_expr_block(this, location, statements)
+        });
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'a TypedAssignment) {
+        let (scope_contains_location, scope_type) = self.scopes.last().cloned().unwrap_or_default();
+        let is_let_variable =
+            !assignment.kind.is_assert() && matches!(assignment.pattern, Pattern::Variable { .. });
+        if is_let_variable && assignment.value.location() == self.location {
+            self.value_assignment = Some((assignment, scope_type.clone()));
+        }
+
+        self.visit_typed_expr(&assignment.value);
+
+        // The variables defined by the `let` are only in scope for the
+        // statements following it.
+        self.pattern_in_scope =
+            scope_contains_location && assignment.location.end <= self.location.start;
+        self.visit_typed_pattern(&assignment.pattern);
+        if self.pattern_in_scope
+            && is_let_variable
+            && let Pattern::Variable { name, location, .. } = &assignment.pattern
+        {
+            self.define(name, *location, Some((assignment, scope_type)));
+        }
+        self.pattern_in_scope = false;
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'a ast::TypedClause) {
+        self.pattern_in_scope = clause.location.contains(self.location.start);
+        for pattern in clause
+            .pattern
+            .iter()
+            .chain(clause.alternative_patterns.iter().flatten())
+        {
+            self.visit_typed_pattern(pattern);
+        }
+        self.pattern_in_scope = false;
+        if let Some(guard) = &clause.guard {
+            self.visit_typed_clause_guard(guard);
+        }
+        self.visit_typed_expr(&clause.then);
+    }
+
+    fn visit_typed_pattern_variable(
+        &mut self,
+        location: &'a SrcSpan,
+        name: &'a EcoString,
+        _type_: &'a Arc<Type>,
+        _origin: &'a VariableOrigin,
+    ) {
+        if self.pattern_in_scope {
+            self.define(name, *location, None);
+        }
+    }
+
+    fn visit_typed_pattern_assign(
+        &mut self,
+        location: &'a SrcSpan,
+        name: &'a EcoString,
+        pattern: &'a TypedPattern,
+    ) {
+        if self.pattern_in_scope {
+            self.define(name, *location, None);
+        }
+        ast::visit::visit_typed_pattern_assign(self, location, name, pattern);
+    }
+}
+
+/// How the `Some` constructor can be referred to in a module, and the module
+/// that needs to be imported to use it, if any.
+///
+fn option_some(module: &Module, printer: &Printer<'_>) -> (String, Option<&'static str>) {
+    let unqualified = module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            ast::Definition::Import(import) if import.module == OPTION_MODULE => import
+                .unqualified_values
+                .iter()
+                .find(|value| value.name == "Some"),
+            _ => None,
+        });
+    match unqualified {
+        Some(some) => (some.used_name().to_string(), None),
+        None => (
+            format!("{}.Some", printer.print_module(OPTION_MODULE)),
+            Some(OPTION_MODULE),
+        ),
+    }
+}
+
+/// Whether a value of one type could be used where the other type is
+/// expected, ignoring any type variable that could be either type.
+///
+fn could_unify(one: &Type, other: &Type) -> bool {
+    match (one, other) {
+        (Type::Var { type_ }, _) => match &*type_.borrow() {
+            TypeVar::Link { type_ } => could_unify(type_, other),
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => true,
+        },
+        (_, Type::Var { .. }) => could_unify(other, one),
+        (
+            Type::Named {
+                module, name, args, ..
+            },
+            Type::Named {
+                module: other_module,
+                name: other_name,
+                args: other_args,
+                ..
+            },
+        ) => {
+            module == other_module
+                && name == other_name
+                && args.len() == other_args.len()
+                && args
+                    .iter()
+                    .zip(other_args)
+                    .all(|(one, other)| could_unify(one, other))
+        }
+        (
+            Type::Fn { args, return_ },
+            Type::Fn {
+                args: other_args,
+                return_: other_return,
+            },
+        ) => {
+            args.len() == other_args.len()
+                && args
+                    .iter()
+                    .zip(other_args)
+                    .all(|(one, other)| could_unify(one, other))
+                && could_unify(return_, other_return)
+        }
+        (Type::Tuple { elements }, Type::Tuple { elements: other }) => {
+            elements.len() == other.len()
+                && elements
+                    .iter()
+                    .zip(other)
+                    .all(|(one, other)| could_unify(one, other))
+        }
+        (Type::Named { .. } | Type::Fn { .. } | Type::Tuple { .. }, _) => false,
+    }
+}
+
+/// When a `Result` is used where its `Ok` value is expected, the `let` that
+/// assigned it can be turned into a `use` with `result.try`:
+///
+/// ```gleam
+/// let number = int.parse(string)
+/// number + 1
+/// ```
+///
+/// Becomes:
+///
+/// ```gleam
+/// use number <- result.try(int.parse(string))
+/// number + 1
+/// ```
+///
+/// This is only possible if the function or block the `let` is in returns a
+/// `Result` with the same error type.
+///
+fn use_result_try(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    printer: &Printer<'_>,
+    location: SrcSpan,
+    expected: &Type,
+    given: &Type,
+) -> Option<Vec<TextEdit>> {
+    let (ok, error) = given.result_types()?;
+    if !could_unify(&ok, expected) {
+        return None;
+    }
+
+    let function = module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            ast::Definition::Function(function)
+                if function.full_location().contains(location.start) =>
+            {
+                Some(function)
+            }
+            _ => None,
+        })?;
+
+    // The mismatched value is either the value of the `let` itself, or a
+    // variable it defined. A variable that is part of an invalid expression
+    // might be missing from the typed AST, so it's looked up by name in the
+    // scope where it's used.
+    let name = module
+        .code
+        .get(location.start as usize..location.end as usize)?;
+    let (assignment, scope_type) = ResultAssignmentFinder::new(location, name).find(function)?;
+    let (_, scope_error) = scope_type?.result_types()?;
+    if !could_unify(&scope_error, &error) {
+        return None;
+    }
+    let Pattern::Variable { name, .. } = &assignment.pattern else {
+        return None;
+    };
+
+    let mut edits = TextEdits::new(line_numbers);
+    let result = printer.print_module(RESULT_MODULE);
+    edits.replace(
+        SrcSpan::new(assignment.location.start, assignment.value.location().start),
+        format!("use {name} <- {result}.try("),
+    );
+    edits.insert(assignment.value.location().end, ")".into());
+    maybe_import(&mut edits, module, RESULT_MODULE);
+    Some(edits.edits)
+}
+
 /// Builder for code action to add annotations to an assignment or function
 ///
 pub struct AddAnnotations<'a> {
@@ -3286,23 +3752,51 @@ impl<'ast> ast::visit::Visit<'ast> for ExtractConstant<'ast> {
     }
 }
 
-/// Builder for code action to apply the "expand function capture" action.
+/// Builder for code action to extract the selected statements into a new
+/// function.
 ///
-pub struct ExpandFunctionCapture<'a> {
+/// ```gleam
+/// pub fn main() {
+///   let x = 1
+///   let y = x + 1
+///   let z = y * 2
+///   z + x
+/// }
+/// ```
+///
+/// Selecting the second and third statements, this will become:
+///
+/// ```gleam
+/// pub fn main() {
+///   let x = 1
+///   let z = function(x)
+///   z + x
+/// }
+///
+/// fn function(x: Int) -> Int {
+///   let y = x + 1
+///   let z = y * 2
+///   z
+/// }
+/// ```
+///
+pub struct ExtractFunction<'a> {
     module: &'a Module,
     params: &'a CodeActionParams,
     edits: TextEdits<'a>,
-    function_capture_data: Option<FunctionCaptureData>,
+    function: Option<&'a ast::TypedFunction>,
+    selected_statements: Option<SelectedStatements<'a>>,
 }
 
-pub struct FunctionCaptureData {
-    function_span: SrcSpan,
-    hole_span: SrcSpan,
-    hole_type: Arc<Type>,
-    reserved_names: VariablesNames,
+struct SelectedStatements<'a> {
+    statements: &'a [TypedStatement],
+    /// The statements coming after the selected ones in the same block, which
+    /// could be using variables defined by the selected statements.
+    following_statements: &'a [TypedStatement],
+    function: &'a ast::TypedFunction,
 }
 
-impl<'a> ExpandFunctionCapture<'a> {
+impl<'a> ExtractFunction<'a> {
     pub fn new(
         module: &'a Module,
         line_numbers: &'a LineNumbers,
@@ -3312,31 +3806,383 @@ impl<'a> ExpandFunctionCapture<'a> {
             module,
             params,
             edits: TextEdits::new(line_numbers),
-            function_capture_data: None,
+            function: None,
+            selected_statements: None,
         }
     }
 
     pub fn code_actions(mut self) -> Vec<CodeAction> {
         self.visit_typed_module(&self.module.ast);
 
-        let Some(FunctionCaptureData {
-            function_span,
-            hole_span,
-            hole_type,
-            reserved_names,
-        }) = self.function_capture_data
+        let Some(SelectedStatements {
+            statements,
+            following_statements,
+            function,
+        }) = self.selected_statements.take()
         else {
             return vec![];
         };
+        let (Some(first), Some(last)) = (statements.first(), statements.last()) else {
+            return vec![];
+        };
+        let location = SrcSpan::new(first.location().start, last.location().end);
 
-        let mut name_generator = NameGenerator::new();
-        name_generator.reserve_variable_names(reserved_names);
-        let name = name_generator.generate_name_from_type(&hole_type);
+        // Any variable used by the selected statements and defined outside of
+        // them becomes an argument of the new function.
+        let mut uses = LocalVariableUses::default();
+        statements
+            .iter()
+            .for_each(|statement| uses.visit_typed_statement(statement));
+        let arguments = uses
+            .uses
+            .into_iter()
+            .filter(|variable| !location.contains(variable.definition.start))
+            .unique_by(|variable| (variable.name.clone(), variable.definition.start))
+            .collect_vec();
+
+        // Any variable defined by the selected statements and used after them
+        // has to be returned by the new function.
+        let mut uses = LocalVariableUses::default();
+        following_statements
+            .iter()
+            .for_each(|statement| uses.visit_typed_statement(statement));
+        let returned = uses
+            .uses
+            .into_iter()
+            .filter(|variable| location.contains(variable.definition.start))
+            .unique_by(|variable| (variable.name.clone(), variable.definition.start))
+            .sorted_by_key(|variable| variable.definition.start)
+            .collect_vec();
+
+        let name = self.function_name(function);
+        let mut printer = Printer::new(&self.module.ast.names);
+        let parameters = arguments
+            .iter()
+            .map(|argument| {
+                let type_ = printer.print_type(&argument.type_);
+                format!("{}: {type_}", argument.name)
+            })
+            .join(", ");
+        let call = format!(
+            "{name}({})",
+            arguments.iter().map(|argument| &argument.name).join(", ")
+        );
 
-        self.edits.replace(hole_span, name.clone().into());
-        self.edits.insert(function_span.end, " }".into());
-        self.edits
-            .insert(function_span.start, format!("fn({name}) {{ "));
+        let (return_type, returned_value, call) = match returned.as_slice() {
+            [] => (printer.print_type(&last.type_()), None, call),
+            [variable] => (
+                printer.print_type(&variable.type_),
+                Some(variable.name.to_string()),
+                format!("let {} = {call}", variable.name),
+            ),
+            variables => {
+                let names = variables.iter().map(|variable| &variable.name).join(", ");
+                let types = variables
+                    .iter()
+                    .map(|variable| printer.print_type(&variable.type_))
+                    .join(", ");
+                (
+                    eco_format!("#({types})"),
+                    Some(format!("#({names})")),
+                    format!("let #({names}) = {call}"),
+                )
+            }
+        };
+
+        let Some(code) = self
+            .module
+            .code
+            .get(location.start as usize..location.end as usize)
+        else {
+            return vec![];
+        };
+        let indentation = self.edits.src_span_to_lsp_range(location).start.character as usize;
+        let mut body = code
+            .lines()
+            .enumerate()
+            .map(|(index, line)| match index {
+                0 => line,
+                _ => remove_indentation(line, indentation),
+            })
+            .map(|line| match line {
+                "" => String::new(),
+                _ => format!("  {line}"),
+            })
+            .join("\n");
+        if let Some(returned_value) = returned_value {
+            body.push_str(&format!("\n  {returned_value}"));
+        }
+
+        self.edits.replace(location, call);
+        self.edits.insert(
+            function.end_position,
+            format!("\n\nfn {name}({parameters}) -> {return_type} {{\n{body}\n}}"),
+        );
+
+        let mut action = Vec::with_capacity(1);
+        CodeActionBuilder::new("Extract function")
+            .kind(CodeActionKind::REFACTOR_EXTRACT)
+            .changes(self.params.text_document.uri.clone(), self.edits.edits)
+            .preferred(false)
+            .push_to(&mut action);
+        action
+    }
+
+    /// Picks a name for the new function that doesn't clash with any of the
+    /// module's values or the variables used by the function it's extracted
+    /// from.
+    ///
+    fn function_name(&self, function: &ast::TypedFunction) -> EcoString {
+        let mut name_generator = NameGenerator::new();
+        name_generator.reserve_variable_names(VariablesNames::from_statements(&function.body));
+        for definition in &self.module.ast.definitions {
+            match definition {
+                ast::Definition::Function(ast::Function {
+                    name: Some((_, name)),
+                    ..
+                })
+                | ast::Definition::ModuleConstant(ast::ModuleConstant { name, .. }) => {
+                    name_generator.add_used_name(name.clone())
+                }
+                ast::Definition::Import(import) => import
+                    .unqualified_values
+                    .iter()
+                    .for_each(|value| name_generator.add_used_name(value.used_name().clone())),
+                ast::Definition::Function(_)
+                | ast::Definition::TypeAlias(_)
+                | ast::Definition::CustomType(_) => (),
+            }
+        }
+        name_generator.rename_to_avoid_shadowing("function".into())
+    }
+
+    fn select_statements(&mut self, statements: &'a [TypedStatement]) {
+        let Some(function) = self.function else {
+            return;
+        };
+        let selection = self.edits.lsp_range_to_src_span(self.params.range);
+        if selection.start == selection.end {
+            return;
+        }
+
+        let overlaps_selection = |statement: &TypedStatement| {
+            let location = statement.location();
+            location.start < selection.end && selection.start < location.end
+        };
+        let (Some(first), Some(last)) = (
+            statements.iter().position(overlaps_selection),
+            statements.iter().rposition(overlaps_selection),
+        ) else {
+            return;
+        };
+        let (Some(selected), Some(following_statements)) =
+            (statements.get(first..=last), statements.get(last + 1..))
+        else {
+            return;
+        };
+
+        // The selected statements must be selected entirely, with nothing but
+        // whitespace or comments around them.
+        let (Some(first), Some(last)) = (selected.first(), selected.last()) else {
+            return;
+        };
+        let start = first.location().start;
+        let end = last.location().end;
+        if selection.start > start || selection.end < end {
+            return;
+        }
+        let only_whitespace_or_comments = |range: std::ops::Range<u32>| {
+            self.module
+                .code
+                .get(range.start as usize..range.end as usize)
+                .is_some_and(|code| {
+                    code.lines().all(|line| {
+                        let line = line.trim();
+                        line.is_empty() || line.starts_with("//")
+                    })
+                })
+        };
+        if !only_whitespace_or_comments(selection.start..start)
+            || !only_whitespace_or_comments(end..selection.end)
+        {
+            return;
+        }
+
+        // A `use` takes all the statements following it as its callback, so
+        // it can't be moved to another function on its own.
+        if selected
+            .iter()
+            .any(|statement| matches!(statement, ast::Statement::Use(_)))
+        {
+            return;
+        }
+
+        self.selected_statements = Some(SelectedStatements {
+            statements: selected,
+            following_statements,
+            function,
+        });
+    }
+}
+
+/// Removes up to the given number of spaces from the start of a line.
+///
+fn remove_indentation(line: &str, indentation: usize) -> &str {
+    let spaces = line
+        .chars()
+        .take(indentation)
+        .take_while(|char| *char == ' ')
+        .count();
+    line.get(spaces..).unwrap_or(line)
+}
+
+impl<'ast> ast::visit::Visit<'ast> for ExtractFunction<'ast> {
+    fn visit_typed_function(&mut self, fun: &'ast ast::TypedFunction) {
+        self.function = Some(fun);
+        self.select_statements(&fun.body);
+        ast::visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_expr_block(
+        &mut self,
+        location: &'ast SrcSpan,
+        statements: &'ast [TypedStatement],
+    ) {
+        self.select_statements(statements);
+        ast::visit::visit_typed_expr_block(self, location, statements);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast FunctionLiteralKind,
+        args: &'ast [TypedArg],
+        body: &'ast Vec1<TypedStatement>,
+        return_annotation: &'ast Option<ast::TypeAst>,
+    ) {
+        self.select_statements(body);
+        ast::visit::visit_typed_expr_fn(self, location, type_, kind, args, body, return_annotation);
+    }
+}
+
+struct LocalVariableUse {
+    name: EcoString,
+    definition: SrcSpan,
+    type_: Arc<Type>,
+}
+
+/// Finds all the uses of local variables, along with the location where each
+/// variable is defined.
+///
+#[derive(Default)]
+struct LocalVariableUses {
+    uses: Vec<LocalVariableUse>,
+}
+
+impl<'ast> ast::visit::Visit<'ast> for LocalVariableUses {
+    fn visit_typed_expr_var(
+        &mut self,
+        _location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        if let type_::ValueConstructorVariant::LocalVariable { location, .. } = &constructor.variant
+        {
+            self.uses.push(LocalVariableUse {
+                name: name.clone(),
+                definition: *location,
+                type_: constructor.type_.clone(),
+            });
+        }
+    }
+
+    fn visit_typed_clause_guard_var(
+        &mut self,
+        _location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        type_: &'ast Arc<Type>,
+        definition_location: &'ast SrcSpan,
+    ) {
+        self.uses.push(LocalVariableUse {
+            name: name.clone(),
+            definition: *definition_location,
+            type_: type_.clone(),
+        });
+    }
+
+    fn visit_typed_pattern_var_usage(
+        &mut self,
+        _location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        constructor: &'ast Option<ValueConstructor>,
+        type_: &'ast Arc<Type>,
+    ) {
+        if let Some(ValueConstructor {
+            variant: type_::ValueConstructorVariant::LocalVariable { location, .. },
+            ..
+        }) = constructor
+        {
+            self.uses.push(LocalVariableUse {
+                name: name.clone(),
+                definition: *location,
+                type_: type_.clone(),
+            });
+        }
+    }
+}
+
+/// Builder for code action to apply the "expand function capture" action.
+///
+pub struct ExpandFunctionCapture<'a> {
+    module: &'a Module,
+    params: &'a CodeActionParams,
+    edits: TextEdits<'a>,
+    function_capture_data: Option<FunctionCaptureData>,
+}
+
+pub struct FunctionCaptureData {
+    function_span: SrcSpan,
+    hole_span: SrcSpan,
+    hole_type: Arc<Type>,
+    reserved_names: VariablesNames,
+}
+
+impl<'a> ExpandFunctionCapture<'a> {
+    pub fn new(
+        module: &'a Module,
+        line_numbers: &'a LineNumbers,
+        params: &'a CodeActionParams,
+    ) -> Self {
+        Self {
+            module,
+            params,
+            edits: TextEdits::new(line_numbers),
+            function_capture_data: None,
+        }
+    }
+
+    pub fn code_actions(mut self) -> Vec<CodeAction> {
+        self.visit_typed_module(&self.module.ast);
+
+        let Some(FunctionCaptureData {
+            function_span,
+            hole_span,
+            hole_type,
+            reserved_names,
+        }) = self.function_capture_data
+        else {
+            return vec![];
+        };
+
+        let mut name_generator = NameGenerator::new();
+        name_generator.reserve_variable_names(reserved_names);
+        let name = name_generator.generate_name_from_type(&hole_type);
+
+        self.edits.replace(hole_span, name.clone().into());
+        self.edits.insert(function_span.end, " }".into());
+        self.edits
+            .insert(function_span.start, format!("fn({name}) {{ "));
 
         let mut action = Vec::with_capacity(1);
         CodeActionBuilder::new("Expand function capture")
@@ -4842,68 +5688,419 @@ impl<'ast> ast::visit::Visit<'ast> for GenerateFunction<'ast> {
     }
 }
 
-#[must_use]
-/// Checks the labels in the given arguments are correct: that is there's no
-/// duplicate labels and all labelled arguments come after the unlabelled ones.
-fn labels_are_correct(args: &[TypedCallArg]) -> bool {
-    let mut labelled_arg_found = false;
-    let mut used_labels = HashSet::new();
-
-    for arg in args {
-        match &arg.label {
-            // Labels are invalid if there's duplicate ones or if an unlabelled
-            // argument comes after a labelled one.
-            Some(label) if used_labels.contains(label) => return false,
-            None if labelled_arg_found => return false,
-            // Otherwise we just add the label to the used ones.
-            Some(label) => {
-                labelled_arg_found = true;
-                let _ = used_labels.insert(label);
-            }
-            None => continue,
-        }
-    }
-
-    true
+/// Builds a code action to generate a custom type variant that is used but
+/// doesn't exist yet. For example:
+///
+/// ```gleam
+/// pub type Wibble {
+///   Wibble
+/// }
+///
+/// pub fn main() -> Wibble {
+///   Wobble(1, label: "a")
+///   // ^ If the cursor is here, the action will add `Wobble(Int, label: String)`
+///   //   to the variants of the `Wibble` type
+/// }
+/// ```
+///
+pub struct GenerateVariant<'a> {
+    module: &'a Module,
+    params: &'a CodeActionParams,
+    edits: TextEdits<'a>,
+    variant_to_generate: Option<VariantToGenerate<'a>>,
 }
 
-struct NameGenerator {
-    used_names: HashSet<EcoString>,
+struct VariantToGenerate<'a> {
+    name: &'a str,
+    arguments: Vec<(Option<EcoString>, Arc<Type>)>,
+    /// Where the new variant is going to be inserted: that is right after the
+    /// last variant of the custom type it belongs to.
+    insert_at: u32,
 }
 
-impl NameGenerator {
-    pub fn new() -> Self {
-        NameGenerator {
-            used_names: HashSet::new(),
+impl<'a> GenerateVariant<'a> {
+    pub fn new(
+        module: &'a Module,
+        line_numbers: &'a LineNumbers,
+        params: &'a CodeActionParams,
+    ) -> Self {
+        Self {
+            module,
+            params,
+            edits: TextEdits::new(line_numbers),
+            variant_to_generate: None,
         }
     }
 
-    pub fn rename_to_avoid_shadowing(&mut self, base: EcoString) -> EcoString {
-        let mut i = 1;
-        let mut candidate_name = base.clone();
+    pub fn code_actions(mut self) -> Vec<CodeAction> {
+        self.visit_typed_module(&self.module.ast);
 
-        loop {
-            if self.used_names.contains(&candidate_name) {
-                i += 1;
-                candidate_name = eco_format!("{base}_{i}");
-            } else {
-                let _ = self.used_names.insert(candidate_name.clone());
-                return candidate_name;
-            }
-        }
-    }
+        let Some(VariantToGenerate {
+            name,
+            arguments,
+            insert_at,
+        }) = self.variant_to_generate
+        else {
+            return vec![];
+        };
 
-    pub fn generate_name_from_type(&mut self, type_: &Arc<Type>) -> EcoString {
-        let type_to_base_name = |type_: &Arc<Type>| {
-            type_
-                .named_type_name()
-                .map(|(_type_module, type_name)| EcoString::from(type_name.to_snake_case()))
-                .filter(|name| is_valid_lowercase_name(name))
-                .unwrap_or(EcoString::from("value"))
+        let mut printer = Printer::new(&self.module.ast.names);
+        let variant = if arguments.is_empty() {
+            name.to_string()
+        } else {
+            let arguments = arguments
+                .iter()
+                .map(|(label, type_)| match label {
+                    Some(label) => format!("{label}: {}", printer.print_type(type_)),
+                    None => printer.print_type(type_).to_string(),
+                })
+                .join(", ");
+            format!("{name}({arguments})")
         };
 
-        let base_name = match type_.list_type() {
-            None => type_to_base_name(type_),
+        self.edits.insert(insert_at, format!("\n  {variant}"));
+
+        let mut action = Vec::with_capacity(1);
+        CodeActionBuilder::new("Generate variant")
+            .kind(CodeActionKind::QUICKFIX)
+            .changes(self.params.text_document.uri.clone(), self.edits.edits)
+            .preferred(true)
+            .push_to(&mut action);
+        action
+    }
+
+    fn try_save_variant_to_generate(
+        &mut self,
+        variant_name_location: SrcSpan,
+        arguments_types: Vec<Arc<Type>>,
+        custom_type: &Arc<Type>,
+        labels: HashMap<usize, EcoString>,
+    ) {
+        let name_range = variant_name_location.start as usize..variant_name_location.end as usize;
+        let Some(name) = self.module.code.get(name_range) else {
+            return;
+        };
+        if !is_valid_uppercase_name(name) {
+            return;
+        }
+
+        // We can't come up with a sensible annotation for a field whose type
+        // we know nothing about.
+        if arguments_types.iter().any(|type_| type_.is_variable()) {
+            return;
+        }
+
+        let Some((type_module, type_name)) = custom_type.named_type_name() else {
+            return;
+        };
+        if type_module != self.module.name {
+            return;
+        }
+
+        let insert_at = self
+            .module
+            .ast
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                ast::Definition::CustomType(custom_type) if custom_type.name == type_name => {
+                    custom_type.constructors.last()
+                }
+                _ => None,
+            })
+            .map(|last_variant| last_variant.location.end);
+        let Some(insert_at) = insert_at else {
+            return;
+        };
+
+        let arguments = arguments_types
+            .into_iter()
+            .enumerate()
+            .map(|(i, type_)| (labels.get(&i).cloned(), type_))
+            .collect_vec();
+
+        self.variant_to_generate = Some(VariantToGenerate {
+            name,
+            arguments,
+            insert_at,
+        });
+    }
+}
+
+impl<'ast> ast::visit::Visit<'ast> for GenerateVariant<'ast> {
+    fn visit_typed_expr_invalid(&mut self, location: &'ast SrcSpan, type_: &'ast Arc<Type>) {
+        let invalid_range = self.edits.src_span_to_lsp_range(*location);
+        if within(self.params.range, invalid_range) {
+            self.try_save_variant_to_generate(*location, vec![], type_, HashMap::new());
+        }
+
+        ast::visit::visit_typed_expr_invalid(self, location, type_);
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        let fun_range = self.edits.src_span_to_lsp_range(fun.location());
+
+        if within(self.params.range, fun_range) && fun.is_invalid() {
+            if let (true, Some((arguments_types, custom_type))) =
+                (labels_are_correct(args), fun.type_().fn_types())
+            {
+                let labels = args
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, arg)| arg.label.as_ref().map(|label| (i, label.clone())))
+                    .collect();
+
+                self.try_save_variant_to_generate(
+                    fun.location(),
+                    arguments_types,
+                    &custom_type,
+                    labels,
+                );
+            }
+        } else {
+            ast::visit::visit_typed_expr_call(self, location, type_, fun, args);
+        }
+    }
+}
+
+/// Builds code actions to generate a type that is referenced in an annotation
+/// but is not defined anywhere. The type can either be generated as a new
+/// custom type or as a type alias:
+///
+/// ```gleam
+/// pub fn main(wibble: Wibble) { todo }
+/// //                  ^^^^^^ Unknown type!
+/// ```
+///
+/// Will add one of the following definitions right after `main`, public
+/// because `main` is:
+///
+/// ```gleam
+/// pub type Wibble {
+///   Wibble
+/// }
+///
+/// pub type Wibble =
+///   Nil
+/// ```
+///
+pub fn code_action_generate_type(
+    params: &CodeActionParams,
+    error: &Option<Error>,
+    actions: &mut Vec<CodeAction>,
+) {
+    let uri = &params.text_document.uri;
+    let Some(Error::Type {
+        errors, src, path, ..
+    }) = error
+    else {
+        return;
+    };
+
+    // An unknown type in the signature of a definition stops the analysis of
+    // the entire module, so we can't rely on its typed AST being available
+    // and work with its source code instead.
+    let line_numbers = LineNumbers::new(src);
+    let unknown_type = errors.iter().find_map(|error| match error {
+        type_::Error::UnknownType { location, name, .. } => {
+            let range = src_span_to_lsp_range(*location, &line_numbers);
+            overlaps(params.range, range).then_some((*location, name))
+        }
+        _ => None,
+    });
+    let Some((location, name)) = unknown_type else {
+        return;
+    };
+
+    // We only want to generate types that are referenced with their
+    // unqualified name, anything coming from another module is not ours to
+    // define.
+    let Some(arguments) = src
+        .get(location.start as usize..location.end as usize)
+        .and_then(|annotation| annotation.strip_prefix(name.as_str()))
+        .and_then(count_type_arguments)
+    else {
+        return;
+    };
+
+    // The new type is placed right after the definition that is referencing
+    // it, or at the end of the module if the definition couldn't be found.
+    let definition = crate::parse::parse_module(path.clone(), src, &WarningEmitter::null())
+        .ok()
+        .and_then(|parsed| {
+            parsed
+                .module
+                .definitions
+                .iter()
+                .map(|definition| {
+                    (
+                        definition_full_location(&definition.definition),
+                        definition_publicity(&definition.definition),
+                    )
+                })
+                .find(|(definition, _)| definition.contains(location.start))
+        });
+    let insert_at = definition.map_or(src.len() as u32, |(definition, _)| definition.end);
+
+    // A type referenced by a definition that is not private must be public
+    // too, or it would be leaked.
+    let publicity = match definition {
+        Some((_, publicity)) if !publicity.is_private() => "pub ",
+        Some(_) | None => "",
+    };
+
+    let parameters = if arguments == 0 {
+        String::new()
+    } else {
+        format!("({})", ('a'..='z').take(arguments).join(", "))
+    };
+
+    let mut edits = TextEdits::new(&line_numbers);
+    edits.insert(
+        insert_at,
+        format!("\n\n{publicity}type {name}{parameters} {{\n  {name}\n}}"),
+    );
+    CodeActionBuilder::new("Generate type")
+        .kind(CodeActionKind::QUICKFIX)
+        .changes(uri.clone(), edits.edits)
+        .preferred(true)
+        .push_to(actions);
+
+    // A type alias can't have parameters it doesn't use, so we can only
+    // suggest one if the type has no arguments.
+    if arguments != 0 {
+        return;
+    }
+
+    let mut edits = TextEdits::new(&line_numbers);
+    edits.insert(insert_at, format!("\n\n{publicity}type {name} =\n  Nil"));
+    CodeActionBuilder::new("Generate type alias")
+        .kind(CodeActionKind::QUICKFIX)
+        .changes(uri.clone(), edits.edits)
+        .preferred(false)
+        .push_to(actions);
+}
+
+/// Given the source code following the name of a type in an annotation,
+/// returns the number of type arguments it is applied to. For example
+/// `(Int, List(a))` has two arguments, while the empty string has none.
+///
+/// Returns `None` if the code is not just a list of type arguments.
+///
+fn count_type_arguments(code: &str) -> Option<usize> {
+    if code.is_empty() {
+        return Some(0);
+    }
+
+    let arguments = code.strip_prefix('(')?.strip_suffix(')')?.trim();
+    if arguments.is_empty() {
+        return Some(0);
+    }
+
+    let mut depth = 0;
+    let mut count = 1;
+    for (i, char) in arguments.char_indices() {
+        match char {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            // A trailing comma doesn't start a new argument.
+            ',' if depth == 0 && !arguments.get(i + 1..)?.trim().is_empty() => count += 1,
+            _ => (),
+        }
+    }
+    Some(count)
+}
+
+fn definition_full_location(definition: &ast::UntypedDefinition) -> SrcSpan {
+    match definition {
+        ast::Definition::Function(function) => function.full_location(),
+        ast::Definition::CustomType(custom_type) => custom_type.full_location(),
+        ast::Definition::ModuleConstant(constant) => {
+            SrcSpan::new(constant.location.start, constant.value.location().end)
+        }
+        ast::Definition::TypeAlias(alias) => alias.location,
+        ast::Definition::Import(import) => import.location,
+    }
+}
+
+fn definition_publicity(definition: &ast::UntypedDefinition) -> ast::Publicity {
+    match definition {
+        ast::Definition::Function(function) => function.publicity,
+        ast::Definition::CustomType(custom_type) => custom_type.publicity,
+        ast::Definition::ModuleConstant(constant) => constant.publicity,
+        ast::Definition::TypeAlias(alias) => alias.publicity,
+        ast::Definition::Import(_) => ast::Publicity::Private,
+    }
+}
+
+#[must_use]
+/// Checks the labels in the given arguments are correct: that is there's no
+/// duplicate labels and all labelled arguments come after the unlabelled ones.
+fn labels_are_correct(args: &[TypedCallArg]) -> bool {
+    let mut labelled_arg_found = false;
+    let mut used_labels = HashSet::new();
+
+    for arg in args {
+        match &arg.label {
+            // Labels are invalid if there's duplicate ones or if an unlabelled
+            // argument comes after a labelled one.
+            Some(label) if used_labels.contains(label) => return false,
+            None if labelled_arg_found => return false,
+            // Otherwise we just add the label to the used ones.
+            Some(label) => {
+                labelled_arg_found = true;
+                let _ = used_labels.insert(label);
+            }
+            None => continue,
+        }
+    }
+
+    true
+}
+
+struct NameGenerator {
+    used_names: HashSet<EcoString>,
+}
+
+impl NameGenerator {
+    pub fn new() -> Self {
+        NameGenerator {
+            used_names: HashSet::new(),
+        }
+    }
+
+    pub fn rename_to_avoid_shadowing(&mut self, base: EcoString) -> EcoString {
+        let mut i = 1;
+        let mut candidate_name = base.clone();
+
+        loop {
+            if self.used_names.contains(&candidate_name) {
+                i += 1;
+                candidate_name = eco_format!("{base}_{i}");
+            } else {
+                let _ = self.used_names.insert(candidate_name.clone());
+                return candidate_name;
+            }
+        }
+    }
+
+    pub fn generate_name_from_type(&mut self, type_: &Arc<Type>) -> EcoString {
+        let type_to_base_name = |type_: &Arc<Type>| {
+            type_
+                .named_type_name()
+                .map(|(_type_module, type_name)| EcoString::from(type_name.to_snake_case()))
+                .filter(|name| is_valid_lowercase_name(name))
+                .unwrap_or(EcoString::from("value"))
+        };
+
+        let base_name = match type_.list_type() {
+            None => type_to_base_name(type_),
             // If we're coming up with a name for a list we want to use the
             // plural form for the name of the inner type. For example:
             // `List(Pokemon)` should generate `pokemons`.
@@ -4942,7 +6139,7 @@ impl NameGenerator {
 }
 
 #[must_use]
-fn is_valid_lowercase_name(name: &str) -> bool {
+pub fn is_valid_lowercase_name(name: &str) -> bool {
     if !name.starts_with(|char: char| char.is_ascii_lowercase()) {
         return false;
     }
@@ -4957,6 +6154,12 @@ fn is_valid_lowercase_name(name: &str) -> bool {
     str_to_keyword(name).is_none()
 }
 
+#[must_use]
+fn is_valid_uppercase_name(name: &str) -> bool {
+    name.starts_with(|char: char| char.is_ascii_uppercase())
+        && name.chars().all(|char| char.is_ascii_alphanumeric())
+}
+
 /// Code action to rewrite a single-step pipeline into a regular function call.
 /// For example: `a |> b(c, _)` would be rewritten as `b(c, a)`.
 ///
@@ -5258,11 +6461,1019 @@ impl<'ast> ast::visit::Visit<'ast> for InlineVariable<'ast> {
 
         let range = self.edits.src_span_to_lsp_range(*location);
 
-        if !overlaps(self.params.range, range) {
-            return;
+        if !overlaps(self.params.range, range) {
+            return;
+        }
+
+        self.maybe_inline(*location);
+    }
+}
+
+/// Builder for code action to inline a call to a function.
+///
+/// ```gleam
+/// pub fn main() {
+///   double(21)
+/// //  ^ [inline function]
+/// }
+///
+/// fn double(x: Int) -> Int {
+///   x * 2
+/// }
+/// ```
+///
+/// Will turn the call into:
+///
+/// ```gleam
+/// pub fn main() {
+///   21 * 2
+/// }
+/// ```
+///
+/// If that was the only call to a private function, another action also
+/// removes its definition.
+///
+pub struct InlineFunction<'a> {
+    module: &'a Module,
+    modules: &'a std::collections::HashMap<EcoString, Module>,
+    params: &'a CodeActionParams,
+    edits: TextEdits<'a>,
+    current_function: Option<&'a ast::TypedFunction>,
+    /// The locations of expressions used as operands of a binary operator,
+    /// or accessed with a `.`. Those need to be wrapped in a block if they're
+    /// replaced with a binary operation.
+    operands: Vec<SrcSpan>,
+    call: Option<CallToInline<'a>>,
+}
+
+struct CallToInline<'a> {
+    location: SrcSpan,
+    arguments: &'a [TypedCallArg],
+    caller: &'a ast::TypedFunction,
+    /// The module the called function is defined in.
+    module: &'a Module,
+    function: &'a ast::TypedFunction,
+}
+
+/// The maximum number of statements a function can have to be inlined.
+///
+const MAX_INLINED_STATEMENTS: usize = 5;
+
+impl<'a> InlineFunction<'a> {
+    pub fn new(
+        module: &'a Module,
+        line_numbers: &'a LineNumbers,
+        params: &'a CodeActionParams,
+        modules: &'a std::collections::HashMap<EcoString, Module>,
+    ) -> Self {
+        Self {
+            module,
+            modules,
+            params,
+            edits: TextEdits::new(line_numbers),
+            current_function: None,
+            operands: vec![],
+            call: None,
+        }
+    }
+
+    pub fn code_actions(mut self) -> Vec<CodeAction> {
+        self.visit_typed_module(&self.module.ast);
+
+        let Some(call) = self.call.take() else {
+            return vec![];
+        };
+        let Some(inlined) = self.inline(&call) else {
+            return vec![];
+        };
+
+        let mut actions = vec![];
+        self.edits.replace(call.location, inlined.clone());
+        CodeActionBuilder::new("Inline function")
+            .kind(CodeActionKind::REFACTOR_INLINE)
+            .changes(
+                self.params.text_document.uri.clone(),
+                std::mem::take(&mut self.edits.edits),
+            )
+            .preferred(false)
+            .push_to(&mut actions);
+
+        if let Some(definition) = self.removable_definition(&call) {
+            self.edits.replace(call.location, inlined);
+            self.edits.delete(definition);
+            CodeActionBuilder::new("Inline function and remove its definition")
+                .kind(CodeActionKind::REFACTOR_INLINE)
+                .changes(
+                    self.params.text_document.uri.clone(),
+                    std::mem::take(&mut self.edits.edits),
+                )
+                .preferred(false)
+                .push_to(&mut actions);
+        }
+
+        actions
+    }
+
+    fn called_function(&self, fun: &'a TypedExpr) -> Option<(&'a Module, &'a ast::TypedFunction)> {
+        let (module_name, name) = match fun {
+            TypedExpr::Var {
+                constructor:
+                    ValueConstructor {
+                        variant: type_::ValueConstructorVariant::ModuleFn { module, name, .. },
+                        ..
+                    },
+                ..
+            }
+            | TypedExpr::ModuleSelect {
+                constructor: ModuleValueConstructor::Fn { module, name, .. },
+                ..
+            } => (module, name),
+            _ => return None,
+        };
+
+        // Only functions from the same package can be inlined, as their code
+        // is available.
+        let module = if *module_name == self.module.name {
+            self.module
+        } else {
+            self.modules.get(module_name)?
+        };
+
+        let function = module
+            .ast
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                ast::Definition::Function(function)
+                    if function
+                        .name
+                        .as_ref()
+                        .is_some_and(|(_, function_name)| function_name == name) =>
+                {
+                    Some(function)
+                }
+                _ => None,
+            })?;
+        Some((module, function))
+    }
+
+    /// Returns the code replacing the call, if the function can be inlined.
+    ///
+    fn inline(&self, call: &CallToInline<'a>) -> Option<String> {
+        let CallToInline {
+            location,
+            arguments,
+            caller,
+            module,
+            function,
+        } = call;
+
+        // Functions implemented in another language can't be inlined, and
+        // bigger functions are better left as they are.
+        if function.external_erlang.is_some()
+            || function.external_javascript.is_some()
+            || function.body.len() > MAX_INLINED_STATEMENTS
+            || arguments.len() != function.arguments.len()
+            || arguments.iter().any(|argument| argument.is_implicit())
+        {
+            return None;
+        }
+
+        let mut body = InlinedBody::new(function);
+        function
+            .body
+            .iter()
+            .for_each(|statement| body.visit_typed_statement(statement));
+
+        // The function's code will now be in this module: it can only reference
+        // values from its own module if it's in the same module.
+        let same_module = module.name == self.module.name;
+        if !same_module && !body.only_references_locals {
+            return None;
+        }
+
+        // Any value from the module that would be shadowed by a variable where
+        // the function is called would be referring to a different value.
+        let caller_variables = LocalVariableDefinitions::of_function(caller).names;
+        if body
+            .module_values
+            .iter()
+            .any(|name| caller_variables.contains(name))
+        {
+            return None;
+        }
+
+        // The variables referenced by the arguments could be shadowed by a
+        // variable defined in the function, so those are renamed.
+        let mut argument_variables = VariablesNames {
+            names: HashSet::new(),
+        };
+        for argument in arguments.iter() {
+            argument_variables.visit_typed_call_arg(argument);
+        }
+        let argument_variables = argument_variables.names;
+
+        let mut name_generator = NameGenerator::new();
+        caller_variables
+            .iter()
+            .chain(argument_variables.iter())
+            .chain(body.module_values.iter())
+            .chain(body.locals.iter().map(|local| &local.name))
+            .for_each(|name| name_generator.add_used_name(name.clone()));
+
+        let mut edits = vec![];
+        for local in body.locals.iter() {
+            if !argument_variables.contains(&local.name) {
+                continue;
+            }
+            // Shorthand labels and `as` patterns can't be simply renamed.
+            if !local.renamable {
+                return None;
+            }
+            let new_name = name_generator.rename_to_avoid_shadowing(local.name.clone());
+            edits.push((local.definition, new_name.to_string()));
+            for use_ in body.local_uses.iter() {
+                if use_.definition == local.definition {
+                    edits.push((use_.location, labelled(&use_.label, &new_name)));
+                }
+            }
+        }
+
+        // Each argument is either replaced where the parameter is used or, if
+        // it would be evaluated a different number of times or in a different
+        // order, assigned to a variable first.
+        let impure_arguments = arguments
+            .iter()
+            .filter(|argument| has_side_effects(&argument.value))
+            .count();
+        let mut assignments = vec![];
+        for (argument, parameter) in arguments.iter().zip(function.arguments.iter()) {
+            let value = match &argument.value {
+                _ if argument.uses_label_shorthand() => argument.label.as_ref()?.to_string(),
+                value => self.code(value.location())?.to_string(),
+            };
+            let uses = body
+                .parameter_uses
+                .iter()
+                .filter(|use_| parameter.location.contains(use_.definition.start))
+                .collect_vec();
+
+            let is_simple = matches!(
+                argument.value,
+                TypedExpr::Var { .. }
+                    | TypedExpr::Int { .. }
+                    | TypedExpr::Float { .. }
+                    | TypedExpr::String { .. }
+            );
+            let replace_uses = is_simple
+                || match uses.as_slice() {
+                    // Inside an anonymous function or a case clause the
+                    // argument could be evaluated any number of times.
+                    [use_] if use_.is_nested => false,
+                    [_] if !has_side_effects(&argument.value) => true,
+                    // An argument with side effects must also be evaluated
+                    // before anything else the function does, and the other
+                    // arguments can't have any.
+                    [use_] => impure_arguments == 1 && body.is_evaluated_first(use_),
+                    _ => false,
+                };
+            if replace_uses {
+                for use_ in uses {
+                    let value = match &argument.value {
+                        TypedExpr::BinOp { .. } | TypedExpr::Pipeline { .. } if use_.is_operand => {
+                            format!("{{ {value} }}")
+                        }
+                        _ => value.clone(),
+                    };
+                    edits.push((use_.location, labelled(&use_.label, &value)));
+                }
+                continue;
+            }
+
+            let name = match parameter.names.get_variable_name() {
+                None => "_".into(),
+                Some(_) if uses.is_empty() => "_".into(),
+                Some(name) if argument_variables.contains(name) => {
+                    let new_name = name_generator.rename_to_avoid_shadowing(name.clone());
+                    for use_ in uses {
+                        edits.push((use_.location, labelled(&use_.label, &new_name)));
+                    }
+                    new_name
+                }
+                Some(name) => name.clone(),
+            };
+            assignments.push(format!("let {name} = {value}"));
+        }
+
+        let body_location = SrcSpan::new(
+            function.body.first().location().start,
+            function.body.last().location().end,
+        );
+        let mut code = module
+            .code
+            .get(body_location.start as usize..body_location.end as usize)?
+            .to_string();
+        for (location, new_text) in edits
+            .into_iter()
+            .sorted_by_key(|(location, _)| std::cmp::Reverse(location.start))
+        {
+            let start = location.start.checked_sub(body_location.start)? as usize;
+            let end = location.end.checked_sub(body_location.start)? as usize;
+            code.replace_range(start..end, &new_text);
+        }
+
+        let body_indentation = indentation_at(&module.code, body_location.start);
+        let call_indentation = " ".repeat(indentation_at(&self.module.code, location.start));
+        let lines = code.lines().enumerate().map(|(index, line)| match index {
+            0 => line,
+            _ => remove_indentation(line, body_indentation),
+        });
+
+        let is_single_expression = match function.body.as_slice() {
+            [ast::Statement::Expression(_)] => assignments.is_empty(),
+            _ => false,
+        };
+        if is_single_expression {
+            let code = lines
+                .enumerate()
+                .map(|(index, line)| match (index, line) {
+                    (0, line) | (_, line @ "") => line.to_string(),
+                    (_, line) => format!("{call_indentation}{line}"),
+                })
+                .join("\n");
+            let is_operation = matches!(
+                function.body.first(),
+                ast::Statement::Expression(TypedExpr::BinOp { .. } | TypedExpr::Pipeline { .. })
+            );
+            return Some(if is_operation && self.operands.contains(location) {
+                format!("{{ {code} }}")
+            } else {
+                code
+            });
+        }
+
+        let statements = assignments
+            .iter()
+            .map(String::as_str)
+            .chain(lines)
+            .map(|line| match line {
+                "" => String::new(),
+                _ => format!("{call_indentation}  {line}"),
+            })
+            .join("\n");
+        Some(format!("{{\n{statements}\n{call_indentation}}}"))
+    }
+
+    /// If the function is private and only called once, its definition can be
+    /// removed after inlining it.
+    ///
+    fn removable_definition(&self, call: &CallToInline<'a>) -> Option<SrcSpan> {
+        let function = call.function;
+        if call.module.name != self.module.name || function.publicity.is_public() {
+            return None;
+        }
+
+        let (_, name) = function.name.as_ref()?;
+        let references = self
+            .module
+            .ast
+            .type_info
+            .references
+            .value_references
+            .get(&(self.module.name.clone(), name.clone()))?;
+        let calls = references
+            .iter()
+            .filter(|reference| reference.kind != ReferenceKind::Definition)
+            .count();
+        if calls != 1 {
+            return None;
+        }
+
+        // The documentation and attributes of the function are removed too,
+        // as well as any whitespace after it.
+        let start = documentation_start(&self.module.code, function.location.start);
+        let end = trailing_whitespace_end(&self.module.code, function.end_position);
+        Some(SrcSpan::new(start, end))
+    }
+
+    fn code(&self, location: SrcSpan) -> Option<&'a str> {
+        self.module
+            .code
+            .get(location.start as usize..location.end as usize)
+    }
+}
+
+/// The code replacing a variable, keeping the label if it was passed using the
+/// shorthand label syntax.
+///
+fn labelled(label: &Option<EcoString>, value: &str) -> String {
+    match label {
+        Some(label) if label == value => format!("{label}:"),
+        Some(label) => format!("{label}: {value}"),
+        None => value.to_string(),
+    }
+}
+
+/// Returns the number of spaces at the start of the line containing the
+/// given byte index.
+///
+fn indentation_at(code: &str, byte_index: u32) -> usize {
+    let line_start = code
+        .get(..byte_index as usize)
+        .and_then(|before| before.rfind('\n'))
+        .map_or(0, |index| index + 1);
+    code.get(line_start..).map_or(0, |line| {
+        line.chars().take_while(|char| *char == ' ').count()
+    })
+}
+
+impl<'ast> ast::visit::Visit<'ast> for InlineFunction<'ast> {
+    fn visit_typed_function(&mut self, fun: &'ast ast::TypedFunction) {
+        self.current_function = Some(fun);
+        ast::visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        let fun_range = self.edits.src_span_to_lsp_range(fun.location());
+        let called_function = if within(self.params.range, fun_range) {
+            self.called_function(fun)
+        } else {
+            None
+        };
+        if let (Some((module, function)), Some(caller)) = (called_function, self.current_function) {
+            self.call = Some(CallToInline {
+                location: *location,
+                arguments: args,
+                caller,
+                module,
+                function,
+            });
+        }
+
+        ast::visit::visit_typed_expr_call(self, location, type_, fun, args);
+    }
+
+    fn visit_typed_expr_bin_op(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        name: &'ast ast::BinOp,
+        left: &'ast TypedExpr,
+        right: &'ast TypedExpr,
+    ) {
+        self.operands.push(left.location());
+        self.operands.push(right.location());
+        ast::visit::visit_typed_expr_bin_op(self, location, type_, name, left, right);
+    }
+
+    fn visit_typed_expr_record_access(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        index: &'ast u64,
+        record: &'ast TypedExpr,
+    ) {
+        self.operands.push(record.location());
+        ast::visit::visit_typed_expr_record_access(self, location, type_, label, index, record);
+    }
+
+    fn visit_typed_expr_tuple_index(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        index: &'ast u64,
+        tuple: &'ast TypedExpr,
+    ) {
+        self.operands.push(tuple.location());
+        ast::visit::visit_typed_expr_tuple_index(self, location, type_, index, tuple);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast FunctionLiteralKind,
+        args: &'ast [TypedArg],
+        body: &'ast Vec1<TypedStatement>,
+        return_annotation: &'ast Option<ast::TypeAst>,
+    ) {
+        // The arguments of a function capture have no code of their own, so
+        // the call can't be inlined.
+        match kind {
+            FunctionLiteralKind::Capture { .. } => (),
+            FunctionLiteralKind::Anonymous { .. } | FunctionLiteralKind::Use { .. } => {
+                ast::visit::visit_typed_expr_fn(
+                    self,
+                    location,
+                    type_,
+                    kind,
+                    args,
+                    body,
+                    return_annotation,
+                );
+            }
+        }
+    }
+}
+
+struct ParameterUse {
+    location: SrcSpan,
+    definition: SrcSpan,
+    is_operand: bool,
+    /// Whether the use is inside an anonymous function or a case clause, so
+    /// it could be evaluated any number of times.
+    is_nested: bool,
+    /// The label of the argument, if the parameter is passed with the
+    /// shorthand label syntax.
+    label: Option<EcoString>,
+}
+
+struct LocalDefinition {
+    name: EcoString,
+    definition: SrcSpan,
+    renamable: bool,
+}
+
+struct LocalUse {
+    location: SrcSpan,
+    definition: SrcSpan,
+    label: Option<EcoString>,
+}
+
+/// Everything needed to move the body of a function where it's called.
+///
+struct InlinedBody<'a> {
+    function: &'a ast::TypedFunction,
+    parameter_uses: Vec<ParameterUse>,
+    /// The variables defined in the body of the function.
+    locals: Vec<LocalDefinition>,
+    local_uses: Vec<LocalUse>,
+    /// The names of the values of the function's module referenced without a
+    /// qualifier.
+    module_values: Vec<EcoString>,
+    /// Whether the body only references its own variables, so that it can be
+    /// moved to another module.
+    only_references_locals: bool,
+    operands: Vec<SrcSpan>,
+    /// How many anonymous functions and case clauses the visitor is in.
+    nesting: usize,
+    /// The end of each expression that could have side effects, which is
+    /// where those effects happen.
+    effects: Vec<u32>,
+}
+
+impl<'a> InlinedBody<'a> {
+    fn new(function: &'a ast::TypedFunction) -> Self {
+        Self {
+            function,
+            parameter_uses: vec![],
+            locals: vec![],
+            local_uses: vec![],
+            module_values: vec![],
+            only_references_locals: true,
+            operands: vec![],
+            nesting: 0,
+            effects: vec![],
+        }
+    }
+
+    /// Whether the given use is evaluated before any side effect of the body
+    /// could happen.
+    ///
+    fn is_evaluated_first(&self, use_: &ParameterUse) -> bool {
+        self.effects
+            .iter()
+            .all(|effect| *effect > use_.location.start)
+    }
+
+    fn use_local(&mut self, location: SrcSpan, definition: SrcSpan, label: Option<EcoString>) {
+        let is_parameter = self
+            .function
+            .arguments
+            .iter()
+            .any(|argument| argument.location.contains(definition.start));
+        if is_parameter {
+            self.parameter_uses.push(ParameterUse {
+                location,
+                definition,
+                is_operand: self.operands.contains(&location),
+                is_nested: self.nesting > 0,
+                label,
+            });
+        } else {
+            self.local_uses.push(LocalUse {
+                location,
+                definition,
+                label,
+            });
+        }
+    }
+
+    fn define_local(&mut self, name: &EcoString, definition: SrcSpan, renamable: bool) {
+        self.locals.push(LocalDefinition {
+            name: name.clone(),
+            definition,
+            renamable,
+        });
+    }
+}
+
+impl<'ast> ast::visit::Visit<'ast> for InlinedBody<'ast> {
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        match &constructor.variant {
+            type_::ValueConstructorVariant::LocalVariable {
+                location: definition,
+                ..
+            } => self.use_local(*location, *definition, None),
+            type_::ValueConstructorVariant::ModuleConstant { .. }
+            | type_::ValueConstructorVariant::LocalConstant { .. }
+            | type_::ValueConstructorVariant::ModuleFn { .. }
+            | type_::ValueConstructorVariant::Record { .. } => {
+                self.only_references_locals = false;
+                self.module_values.push(name.clone());
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_expr_module_select(
+        &mut self,
+        _location: &'ast SrcSpan,
+        _field_start: &'ast u32,
+        _type_: &'ast Arc<Type>,
+        _label: &'ast EcoString,
+        _module_name: &'ast EcoString,
+        _module_alias: &'ast EcoString,
+        _constructor: &'ast ModuleValueConstructor,
+    ) {
+        self.only_references_locals = false;
+    }
+
+    fn visit_typed_expr_bin_op(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        name: &'ast ast::BinOp,
+        left: &'ast TypedExpr,
+        right: &'ast TypedExpr,
+    ) {
+        self.operands.push(left.location());
+        self.operands.push(right.location());
+        ast::visit::visit_typed_expr_bin_op(self, location, type_, name, left, right);
+    }
+
+    fn visit_typed_expr_record_access(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        index: &'ast u64,
+        record: &'ast TypedExpr,
+    ) {
+        self.operands.push(record.location());
+        ast::visit::visit_typed_expr_record_access(self, location, type_, label, index, record);
+    }
+
+    fn visit_typed_expr_tuple_index(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        index: &'ast u64,
+        tuple: &'ast TypedExpr,
+    ) {
+        self.operands.push(tuple.location());
+        ast::visit::visit_typed_expr_tuple_index(self, location, type_, index, tuple);
+    }
+
+    fn visit_typed_call_arg(&mut self, arg: &'ast TypedCallArg) {
+        // A variable passed with a shorthand label can't be replaced without
+        // also writing the label.
+        if let (
+            true,
+            Some(label),
+            TypedExpr::Var {
+                constructor:
+                    ValueConstructor {
+                        variant: type_::ValueConstructorVariant::LocalVariable { location, .. },
+                        ..
+                    },
+                ..
+            },
+        ) = (arg.uses_label_shorthand(), &arg.label, &arg.value)
+        {
+            self.use_local(arg.location, *location, Some(label.clone()));
+            return;
+        }
+        visit_typed_call_arg(self, arg);
+    }
+
+    fn visit_typed_clause_guard_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        _name: &'ast EcoString,
+        _type_: &'ast Arc<Type>,
+        definition_location: &'ast SrcSpan,
+    ) {
+        self.use_local(*location, *definition_location, None);
+    }
+
+    fn visit_typed_clause_guard_module_select(
+        &mut self,
+        _location: &'ast SrcSpan,
+        _type_: &'ast Arc<Type>,
+        _label: &'ast EcoString,
+        _module_name: &'ast EcoString,
+        _module_alias: &'ast EcoString,
+        _literal: &'ast ast::TypedConstant,
+    ) {
+        self.only_references_locals = false;
+    }
+
+    fn visit_typed_pattern_variable(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        _type: &'ast Arc<Type>,
+        origin: &'ast VariableOrigin,
+    ) {
+        let renamable = match origin {
+            VariableOrigin::LabelShorthand(_) => false,
+            VariableOrigin::Variable(_)
+            | VariableOrigin::AssignmentPattern
+            | VariableOrigin::Generated => true,
+        };
+        self.define_local(name, *location, renamable);
+    }
+
+    fn visit_typed_pattern_assign(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        pattern: &'ast TypedPattern,
+    ) {
+        self.define_local(name, *location, false);
+        ast::visit::visit_typed_pattern_assign(self, location, name, pattern);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_pattern_constructor(
+        &mut self,
+        location: &'ast SrcSpan,
+        name_location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        arguments: &'ast Vec<CallArg<TypedPattern>>,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        constructor: &'ast crate::analyse::Inferred<type_::PatternConstructor>,
+        spread: &'ast Option<SrcSpan>,
+        type_: &'ast Arc<Type>,
+    ) {
+        self.only_references_locals = false;
+        ast::visit::visit_typed_pattern_constructor(
+            self,
+            location,
+            name_location,
+            name,
+            arguments,
+            module,
+            constructor,
+            spread,
+            type_,
+        );
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast FunctionLiteralKind,
+        args: &'ast [TypedArg],
+        body: &'ast Vec1<TypedStatement>,
+        return_annotation: &'ast Option<ast::TypeAst>,
+    ) {
+        for arg in args {
+            match &arg.names {
+                ast::ArgNames::Named { name, location } => self.define_local(name, *location, true),
+                ast::ArgNames::NamedLabelled {
+                    name,
+                    name_location,
+                    ..
+                } => self.define_local(name, *name_location, true),
+                ast::ArgNames::Discard { .. } | ast::ArgNames::LabelledDiscard { .. } => (),
+            }
+        }
+        self.nesting += 1;
+        ast::visit::visit_typed_expr_fn(self, location, type_, kind, args, body, return_annotation);
+        self.nesting -= 1;
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast ast::TypedClause) {
+        self.nesting += 1;
+        ast::visit::visit_typed_clause(self, clause);
+        self.nesting -= 1;
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        if !fun.is_record_builder() {
+            self.effects.push(location.end);
+        }
+        ast::visit::visit_typed_expr_call(self, location, type_, fun, args);
+    }
+
+    fn visit_typed_expr_todo(
+        &mut self,
+        location: &'ast SrcSpan,
+        message: &'ast Option<Box<TypedExpr>>,
+        kind: &'ast TodoKind,
+        type_: &'ast Arc<Type>,
+    ) {
+        self.effects.push(location.end);
+        ast::visit::visit_typed_expr_todo(self, location, message, kind, type_);
+    }
+
+    fn visit_typed_expr_panic(
+        &mut self,
+        location: &'ast SrcSpan,
+        message: &'ast Option<Box<TypedExpr>>,
+        type_: &'ast Arc<Type>,
+    ) {
+        self.effects.push(location.end);
+        ast::visit::visit_typed_expr_panic(self, location, message, type_);
+    }
+
+    fn visit_typed_expr_echo(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        expression: &'ast Option<Box<TypedExpr>>,
+    ) {
+        self.effects.push(location.end);
+        ast::visit::visit_typed_expr_echo(self, location, type_, expression);
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        if assignment.kind.is_assert() {
+            self.effects.push(assignment.location.end);
+        }
+        ast::visit::visit_typed_assignment(self, assignment);
+    }
+
+    fn visit_type_ast(&mut self, _node: &'ast ast::TypeAst) {
+        // Types might not be available in another module.
+        self.only_references_locals = false;
+    }
+}
+
+/// Returns true if evaluating the expression could have side effects, that
+/// is if it calls a function, panics or prints something.
+///
+fn has_side_effects(expression: &TypedExpr) -> bool {
+    let mut finder = SideEffectFinder { found: false };
+    finder.visit_typed_expr(expression);
+    finder.found
+}
+
+struct SideEffectFinder {
+    found: bool,
+}
+
+impl<'ast> ast::visit::Visit<'ast> for SideEffectFinder {
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        if !fun.is_record_builder() {
+            self.found = true;
+        }
+        ast::visit::visit_typed_expr_call(self, location, type_, fun, args);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        _location: &'ast SrcSpan,
+        _type_: &'ast Arc<Type>,
+        _kind: &'ast FunctionLiteralKind,
+        _args: &'ast [TypedArg],
+        _body: &'ast Vec1<TypedStatement>,
+        _return_annotation: &'ast Option<ast::TypeAst>,
+    ) {
+        // The body of an anonymous function is not evaluated where it's
+        // defined.
+    }
+
+    fn visit_typed_expr_todo(
+        &mut self,
+        _location: &'ast SrcSpan,
+        _message: &'ast Option<Box<TypedExpr>>,
+        _kind: &'ast TodoKind,
+        _type_: &'ast Arc<Type>,
+    ) {
+        self.found = true;
+    }
+
+    fn visit_typed_expr_panic(
+        &mut self,
+        _location: &'ast SrcSpan,
+        _message: &'ast Option<Box<TypedExpr>>,
+        _type_: &'ast Arc<Type>,
+    ) {
+        self.found = true;
+    }
+
+    fn visit_typed_expr_echo(
+        &mut self,
+        _location: &'ast SrcSpan,
+        _type_: &'ast Arc<Type>,
+        _expression: &'ast Option<Box<TypedExpr>>,
+    ) {
+        self.found = true;
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        if assignment.kind.is_assert() {
+            self.found = true;
+        }
+        ast::visit::visit_typed_assignment(self, assignment);
+    }
+}
+
+/// Finds the names of all the variables defined in a function.
+///
+struct LocalVariableDefinitions {
+    names: HashSet<EcoString>,
+}
+
+impl LocalVariableDefinitions {
+    fn of_function(function: &ast::TypedFunction) -> Self {
+        let mut definitions = Self {
+            names: HashSet::new(),
+        };
+        for argument in &function.arguments {
+            if let Some(name) = argument.names.get_variable_name() {
+                let _ = definitions.names.insert(name.clone());
+            }
+        }
+        definitions.visit_typed_function(function);
+        definitions
+    }
+}
+
+impl<'ast> ast::visit::Visit<'ast> for LocalVariableDefinitions {
+    fn visit_typed_pattern_variable(
+        &mut self,
+        _location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        _type: &'ast Arc<Type>,
+        _origin: &'ast VariableOrigin,
+    ) {
+        let _ = self.names.insert(name.clone());
+    }
+
+    fn visit_typed_pattern_assign(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        pattern: &'ast TypedPattern,
+    ) {
+        let _ = self.names.insert(name.clone());
+        ast::visit::visit_typed_pattern_assign(self, location, name, pattern);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast FunctionLiteralKind,
+        args: &'ast [TypedArg],
+        body: &'ast Vec1<TypedStatement>,
+        return_annotation: &'ast Option<ast::TypeAst>,
+    ) {
+        for arg in args {
+            if let Some(name) = arg.names.get_variable_name() {
+                let _ = self.names.insert(name.clone());
+            }
         }
-
-        self.maybe_inline(*location);
+        ast::visit::visit_typed_expr_fn(self, location, type_, kind, args, body, return_annotation);
     }
 }
 
@@ -5969,3 +8180,627 @@ impl<'ast> ast::visit::Visit<'ast> for RemoveEchos<'ast> {
         ast::visit::visit_typed_pipeline_assignment(self, assignment);
     }
 }
+
+/// An import as it's left by the "Organize imports" action.
+///
+#[derive(Debug)]
+struct OrganizedImport<'a> {
+    import: &'a ast::Import<EcoString>,
+    /// The code of the unqualified items that are still used.
+    items: Vec<&'a str>,
+    /// Whether the module is used with a qualified name, like `wibble.wobble`.
+    is_used_qualified: bool,
+    is_removed: bool,
+}
+
+impl OrganizedImport<'_> {
+    fn code(&self) -> String {
+        let mut code = format!("import {}", self.import.module);
+        if !self.items.is_empty() {
+            code.push_str(&format!(".{{{}}}", self.items.iter().join(", ")));
+        }
+        match &self.import.as_name {
+            Some((AssignName::Variable(name) | AssignName::Discard(name), _)) => {
+                code.push_str(&format!(" as {name}"));
+            }
+            None => {}
+        }
+        code
+    }
+}
+
+/// Code action to organize the imports of a module: unused imports and
+/// unqualified items are removed, imports of the same module are merged, and
+/// the imports are formatted and sorted like `gleam format` would.
+///
+pub struct OrganizeImports<'a> {
+    module: &'a Module,
+    params: &'a CodeActionParams,
+    edits: TextEdits<'a>,
+}
+
+impl<'a> OrganizeImports<'a> {
+    pub fn new(
+        module: &'a Module,
+        line_numbers: &'a LineNumbers,
+        params: &'a CodeActionParams,
+    ) -> Self {
+        Self {
+            module,
+            params,
+            edits: TextEdits::new(line_numbers),
+        }
+    }
+
+    pub fn code_actions(mut self) -> Vec<CodeAction> {
+        let definitions = self
+            .module
+            .ast
+            .definitions
+            .iter()
+            .sorted_by_key(|definition| definition.location().start)
+            .collect_vec();
+        let imports = definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                ast::Definition::Import(import) => Some(import),
+                _ => None,
+            })
+            .collect_vec();
+        let organized = self.organized_imports(&imports);
+
+        // Each group of consecutive imports is organized on its own, as there
+        // might be other definitions between them.
+        let mut organized = organized.iter();
+        for (is_import_group, group) in &definitions
+            .iter()
+            .chunk_by(|definition| definition.is_import())
+        {
+            let group = group.count();
+            if !is_import_group {
+                continue;
+            }
+            let imports = organized.by_ref().take(group).collect_vec();
+            self.organize_group(&imports);
+        }
+
+        if self.edits.edits.is_empty() {
+            return vec![];
+        }
+
+        let mut action = Vec::with_capacity(1);
+        CodeActionBuilder::new("Organize imports")
+            .kind(CodeActionKind::SOURCE_ORGANIZE_IMPORTS)
+            .changes(self.params.text_document.uri.clone(), self.edits.edits)
+            .preferred(false)
+            .push_to(&mut action);
+        action
+    }
+
+    fn organized_imports(
+        &self,
+        imports: &[&'a ast::Import<EcoString>],
+    ) -> Vec<OrganizedImport<'a>> {
+        let warnings = &self.module.ast.type_info.warnings;
+        let unused_items = warnings
+            .iter()
+            .filter_map(|warning| match warning {
+                type_::Warning::UnusedImportedValue { location, .. }
+                | type_::Warning::UnusedType {
+                    location,
+                    imported: true,
+                    ..
+                }
+                | type_::Warning::UnusedConstructor {
+                    location,
+                    imported: true,
+                    ..
+                } => Some(*location),
+                _ => None,
+            })
+            .collect_vec();
+        let is_unused_module = |location: SrcSpan| {
+            warnings.iter().any(|warning| match warning {
+                type_::Warning::UnusedImportedModule {
+                    location: unused, ..
+                }
+                | type_::Warning::UnusedImportedModuleAlias {
+                    location: unused, ..
+                } => *unused == location,
+                _ => false,
+            })
+        };
+
+        let mut organized = imports
+            .iter()
+            .map(|import| {
+                let items = import
+                    .unqualified_values
+                    .iter()
+                    .chain(&import.unqualified_types)
+                    .sorted_by_key(|item| item.location.start)
+                    .collect_vec();
+                let is_used_qualified = match &import.as_name {
+                    _ if items.is_empty() => !is_unused_module(import.location),
+                    Some((AssignName::Variable(_), alias_location)) => {
+                        !is_unused_module(*alias_location)
+                    }
+                    Some((AssignName::Discard(_), _)) => false,
+                    // The type checker doesn't track if a module whose
+                    // items are imported unqualified is also used with a
+                    // qualified name, so we have to look for any reference
+                    // to it.
+                    None => self.is_referenced_qualified(&import.module),
+                };
+                let items = items
+                    .into_iter()
+                    .filter(|item| !unused_items.contains(&item.location))
+                    .map(|item| self.code_at(item.location))
+                    .collect_vec();
+
+                OrganizedImport {
+                    import,
+                    is_removed: items.is_empty() && !is_used_qualified,
+                    items,
+                    is_used_qualified,
+                }
+            })
+            .collect_vec();
+
+        // Imports of the same module are merged into one, unless more than
+        // one of them is used with a qualified name.
+        let duplicates = organized
+            .iter()
+            .enumerate()
+            .filter(|(_, organized)| !organized.is_removed)
+            .map(|(index, organized)| (organized.import.module.clone(), index))
+            .into_group_map();
+        for indices in duplicates.into_values() {
+            let qualified = indices
+                .iter()
+                .filter(|index| organized.get(**index).is_some_and(|i| i.is_used_qualified))
+                .collect_vec();
+            let target = match (qualified.as_slice(), indices.first()) {
+                ([], Some(first)) => *first,
+                ([index], _) => **index,
+                _ => continue,
+            };
+
+            let mut items = vec![];
+            for index in &indices {
+                let Some(duplicate) = organized.get_mut(*index) else {
+                    continue;
+                };
+                for item in &duplicate.items {
+                    if !items.contains(item) {
+                        items.push(*item);
+                    }
+                }
+                duplicate.is_removed = *index != target;
+            }
+            if let Some(target) = organized.get_mut(target) {
+                target.items = items;
+            }
+        }
+
+        organized
+    }
+
+    /// Replaces a group of consecutive imports with their organized and
+    /// formatted version, keeping any comment between them.
+    ///
+    fn organize_group(&mut self, imports: &[&OrganizedImport<'_>]) {
+        let (Some(first), Some(last)) = (imports.first(), imports.last()) else {
+            return;
+        };
+        let group = SrcSpan::new(first.import.location.start, last.import.location.end);
+
+        let mut code = String::new();
+        let mut previous_end = group.start;
+        for import in imports {
+            let gap = self.code_at(SrcSpan::new(previous_end, import.import.location.start));
+            previous_end = import.import.location.end;
+            if !import.is_removed {
+                code.push_str(gap);
+                code.push_str(&import.code());
+            } else if !gap.trim().is_empty() {
+                // The comments before a removed import are kept.
+                code.push_str(gap);
+            }
+        }
+
+        let mut formatted = String::new();
+        let path = self.module.input_path.as_path();
+        if crate::format::pretty(&mut formatted, &code.into(), path).is_err() {
+            return;
+        }
+        let formatted = formatted.trim_end();
+        if formatted == self.code_at(group) {
+            return;
+        }
+
+        let end = if formatted.is_empty() {
+            trailing_whitespace_end(&self.module.code, group.end)
+        } else {
+            group.end
+        };
+        self.edits
+            .replace(SrcSpan::new(group.start, end), formatted.to_string());
+    }
+
+    fn is_referenced_qualified(&self, module: &EcoString) -> bool {
+        let references = &self.module.ast.type_info.references;
+        references
+            .value_references
+            .iter()
+            .chain(&references.type_references)
+            .any(|((referenced_module, _), references)| {
+                referenced_module == module
+                    && references
+                        .iter()
+                        .any(|reference| reference.kind == ReferenceKind::Qualified)
+            })
+    }
+
+    fn code_at(&self, location: SrcSpan) -> &'a str {
+        self.module
+            .code
+            .get(location.start as usize..location.end as usize)
+            .unwrap_or_default()
+    }
+}
+
+/// Code actions to change the visibility of a top level function, constant,
+/// type or type alias: making it public, private or `@internal`.
+///
+/// A visibility change is not offered if it would break the code, that is if
+/// a definition that is used by other modules would be made private, or if it
+/// would result in a private or internal type being leaked by a public value.
+///
+pub struct ChangeVisibility<'a> {
+    module: &'a Module,
+    modules: &'a std::collections::HashMap<EcoString, Module>,
+    line_numbers: &'a LineNumbers,
+    params: &'a CodeActionParams,
+}
+
+/// A top level definition whose visibility can be changed.
+///
+struct VisibilityChange<'a> {
+    layer: ast::Layer,
+    name: &'a EcoString,
+    /// The start of the definition, where the `pub` keyword goes.
+    start: u32,
+    publicity: ast::Publicity,
+    /// If this is an opaque type, the `opaque` keyword has to be removed when
+    /// making it private.
+    opaque: bool,
+    /// The constructors of a custom type, which have the same visibility as
+    /// the type itself.
+    constructors: Vec<&'a EcoString>,
+}
+
+impl<'a> ChangeVisibility<'a> {
+    pub fn new(
+        module: &'a Module,
+        line_numbers: &'a LineNumbers,
+        params: &'a CodeActionParams,
+        modules: &'a std::collections::HashMap<EcoString, Module>,
+    ) -> Self {
+        Self {
+            module,
+            modules,
+            line_numbers,
+            params,
+        }
+    }
+
+    pub fn code_actions(self) -> Vec<CodeAction> {
+        let Some(change) = self.definition_under_cursor() else {
+            return vec![];
+        };
+
+        let mut actions = vec![];
+        match change.publicity {
+            ast::Publicity::Private => {
+                self.push_action("Make public", &change, ast::Publicity::Public, &mut actions);
+                self.push_action(
+                    "Make internal",
+                    &change,
+                    ast::Publicity::Internal {
+                        attribute_location: None,
+                    },
+                    &mut actions,
+                );
+            }
+            ast::Publicity::Public => {
+                self.push_action(
+                    "Make private",
+                    &change,
+                    ast::Publicity::Private,
+                    &mut actions,
+                );
+                self.push_action(
+                    "Make internal",
+                    &change,
+                    ast::Publicity::Internal {
+                        attribute_location: None,
+                    },
+                    &mut actions,
+                );
+            }
+            ast::Publicity::Internal {
+                attribute_location: Some(_),
+            } => {
+                self.push_action("Make public", &change, ast::Publicity::Public, &mut actions);
+                self.push_action(
+                    "Make private",
+                    &change,
+                    ast::Publicity::Private,
+                    &mut actions,
+                );
+            }
+            // A definition in an internal module is internal without needing
+            // the `@internal` attribute, so it can only be made private.
+            ast::Publicity::Internal {
+                attribute_location: None,
+            } => {
+                self.push_action(
+                    "Make private",
+                    &change,
+                    ast::Publicity::Private,
+                    &mut actions,
+                );
+            }
+        }
+        actions
+    }
+
+    fn definition_under_cursor(&self) -> Option<VisibilityChange<'a>> {
+        self.module.ast.definitions.iter().find_map(|definition| {
+            let change = match definition {
+                ast::Definition::Function(function) => VisibilityChange {
+                    layer: ast::Layer::Value,
+                    name: &function.name.as_ref()?.1,
+                    start: function.location.start,
+                    publicity: function.publicity,
+                    opaque: false,
+                    constructors: vec![],
+                },
+                ast::Definition::ModuleConstant(constant) => VisibilityChange {
+                    layer: ast::Layer::Value,
+                    name: &constant.name,
+                    start: constant.location.start,
+                    publicity: constant.publicity,
+                    opaque: false,
+                    constructors: vec![],
+                },
+                ast::Definition::TypeAlias(alias) => VisibilityChange {
+                    layer: ast::Layer::Type,
+                    name: &alias.alias,
+                    start: alias.location.start,
+                    publicity: alias.publicity,
+                    opaque: false,
+                    constructors: vec![],
+                },
+                ast::Definition::CustomType(custom_type) => VisibilityChange {
+                    layer: ast::Layer::Type,
+                    name: &custom_type.name,
+                    start: custom_type.location.start,
+                    publicity: custom_type.publicity,
+                    opaque: custom_type.opaque,
+                    // The constructors of an opaque type are always
+                    // private, no matter the visibility of the type.
+                    constructors: if custom_type.opaque {
+                        vec![]
+                    } else {
+                        custom_type
+                            .constructors
+                            .iter()
+                            .map(|constructor| &constructor.name)
+                            .collect()
+                    },
+                },
+                ast::Definition::Import(_) => return None,
+            };
+
+            let range = src_span_to_lsp_range(definition.location(), self.line_numbers);
+            overlaps(self.params.range, range).then_some(change)
+        })
+    }
+
+    fn push_action(
+        &self,
+        title: &str,
+        change: &VisibilityChange<'_>,
+        publicity: ast::Publicity,
+        actions: &mut Vec<CodeAction>,
+    ) {
+        if publicity.is_private() && self.is_used_by_other_modules(change) {
+            return;
+        }
+        if self.leaks_types(change, publicity) {
+            return;
+        }
+
+        let mut edits = TextEdits::new(self.line_numbers);
+        let start = change.start;
+        let code = &self.module.code;
+
+        // First we get rid of the `@internal` attribute, if there is one.
+        if let ast::Publicity::Internal {
+            attribute_location: Some(attribute),
+        } = change.publicity
+        {
+            edits.delete(SrcSpan::new(
+                attribute.start,
+                trailing_whitespace_end(code, attribute.end),
+            ));
+        }
+
+        match (change.publicity.is_private(), publicity) {
+            (true, ast::Publicity::Public) => edits.insert(start, "pub ".into()),
+            (true, ast::Publicity::Internal { .. }) => {
+                edits.insert(start, "@internal\npub ".into())
+            }
+            (false, ast::Publicity::Internal { .. }) => edits.insert(start, "@internal\n".into()),
+            (false, ast::Publicity::Private) => {
+                let mut end = trailing_whitespace_end(code, start + "pub".len() as u32);
+                if change.opaque {
+                    end = trailing_whitespace_end(code, end + "opaque".len() as u32);
+                }
+                edits.delete(SrcSpan::new(start, end));
+            }
+            (true, ast::Publicity::Private) | (false, ast::Publicity::Public) => (),
+        }
+
+        CodeActionBuilder::new(title)
+            .kind(CodeActionKind::REFACTOR_REWRITE)
+            .changes(self.params.text_document.uri.clone(), edits.edits)
+            .preferred(false)
+            .push_to(actions);
+    }
+
+    /// Returns true if the definition, or any of its constructors, is
+    /// referenced by a module other than the one it's defined in.
+    ///
+    fn is_used_by_other_modules(&self, change: &VisibilityChange<'_>) -> bool {
+        let referenced = |layer: ast::Layer, name: &EcoString| {
+            let key = (self.module.name.clone(), name.clone());
+            self.modules
+                .values()
+                .filter(|module| module.name != self.module.name)
+                .any(|module| {
+                    let references = &module.ast.type_info.references;
+                    let references = match layer {
+                        ast::Layer::Value => &references.value_references,
+                        ast::Layer::Type => &references.type_references,
+                    };
+                    references
+                        .get(&key)
+                        .is_some_and(|references| !references.is_empty())
+                })
+        };
+
+        referenced(change.layer, change.name)
+            || change
+                .constructors
+                .iter()
+                .any(|constructor| referenced(ast::Layer::Value, constructor))
+    }
+
+    /// Returns true if giving the definition the new publicity would result in
+    /// a non private value, type alias or custom type exposing a private type,
+    /// or a public one exposing an internal type.
+    ///
+    fn leaks_types(&self, change: &VisibilityChange<'_>, publicity: ast::Publicity) -> bool {
+        let module_name = &self.module.name;
+        let is_changed_type = |type_module: &EcoString, type_name: &EcoString| {
+            change.layer == ast::Layer::Type
+                && type_module == module_name
+                && type_name == change.name
+        };
+
+        // Whether a definition with the given publicity leaks any of the types
+        // in its signature. `is_changed` tells if it's the definition whose
+        // publicity is being changed.
+        let leaks = |is_changed: bool, definition_publicity: ast::Publicity, type_: &Type| {
+            let definition_publicity = if is_changed {
+                publicity
+            } else {
+                definition_publicity
+            };
+
+            // A private definition can't leak anything.
+            if definition_publicity.is_private() {
+                return false;
+            }
+
+            exposes_type(type_, &|type_module, type_name, type_publicity| {
+                let type_is_changed = is_changed_type(type_module, type_name);
+                if !is_changed && !type_is_changed {
+                    return false;
+                }
+
+                let type_publicity = if type_is_changed {
+                    publicity
+                } else {
+                    type_publicity
+                };
+                type_publicity.is_private()
+                    || (definition_publicity.is_public() && type_publicity.is_internal())
+            })
+        };
+
+        let leaking_value = self
+            .module
+            .ast
+            .type_info
+            .values
+            .iter()
+            .any(|(value_name, value)| {
+                let value_is_changed = match change.layer {
+                    ast::Layer::Value => value_name == change.name,
+                    ast::Layer::Type => change.constructors.contains(&value_name),
+                };
+                leaks(value_is_changed, value.publicity, &value.type_)
+            });
+
+        let leaking_type = self
+            .module
+            .ast
+            .definitions
+            .iter()
+            .any(|definition| match definition {
+                ast::Definition::TypeAlias(alias) => {
+                    let alias_is_changed = is_changed_type(module_name, &alias.alias);
+                    leaks(alias_is_changed, alias.publicity, &alias.type_)
+                }
+                // The fields of an opaque type are not exposed by it.
+                ast::Definition::CustomType(custom_type) if !custom_type.opaque => {
+                    let type_is_changed = is_changed_type(module_name, &custom_type.name);
+                    custom_type
+                        .constructors
+                        .iter()
+                        .flat_map(|constructor| &constructor.arguments)
+                        .any(|field| leaks(type_is_changed, custom_type.publicity, &field.type_))
+                }
+                ast::Definition::CustomType(_)
+                | ast::Definition::Function(_)
+                | ast::Definition::ModuleConstant(_)
+                | ast::Definition::Import(_) => false,
+            });
+
+        leaking_value || leaking_type
+    }
+}
+
+/// Returns true if any of the named types appearing in the given type
+/// satisfies the predicate.
+///
+fn exposes_type(
+    type_: &Type,
+    predicate: &impl Fn(&EcoString, &EcoString, ast::Publicity) -> bool,
+) -> bool {
+    match type_ {
+        Type::Named {
+            module,
+            name,
+            publicity,
+            args,
+            ..
+        } => {
+            predicate(module, name, *publicity)
+                || args.iter().any(|type_| exposes_type(type_, predicate))
+        }
+        Type::Tuple { elements } => elements.iter().any(|type_| exposes_type(type_, predicate)),
+        Type::Fn { args, return_ } => {
+            exposes_type(return_, predicate)
+                || args.iter().any(|type_| exposes_type(type_, predicate))
+        }
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => false,
+            TypeVar::Link { type_ } => exposes_type(type_, predicate),
+        },
+    }
+}
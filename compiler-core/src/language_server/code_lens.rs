@@ -0,0 +1,107 @@
+use ecow::EcoString;
+use lsp_types::{CodeLens, Command};
+use serde::Serialize;
+
+use crate::{
+    ast::{Definition, TypedFunction},
+    build::{Module, Target},
+    line_numbers::LineNumbers,
+};
+
+use super::src_span_to_lsp_range;
+
+/// The command clients run for the lens on a `main` function, equivalent to
+/// `gleam run --module <module>`.
+pub const RUN_COMMAND: &str = "gleam.run";
+
+/// The command clients run for the lens on a test function, equivalent to
+/// running `gleam test` for that function.
+pub const RUN_TEST_COMMAND: &str = "gleam.runTest";
+
+/// The arguments of the commands of the lenses, enough for a client to know
+/// what `gleam` command to run.
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RunArguments {
+    module: EcoString,
+    function: EcoString,
+    target: Target,
+}
+
+/// Returns the lenses to run the `main` function of a module and, for test
+/// modules, each of its test functions.
+///
+/// Running a module that is internal or that has a deprecated `main`
+/// function would result in a warning, so no lenses are shown for those.
+///
+pub fn code_lenses(module: &Module, line_numbers: &LineNumbers, target: Target) -> Vec<CodeLens> {
+    if module.ast.type_info.is_internal {
+        return vec![];
+    }
+
+    match module.ast.type_info.get_main_function(target) {
+        Ok(main) if main.deprecation.is_deprecated() => return vec![],
+        Ok(_) | Err(_) => {}
+    }
+
+    let lens = |function: &TypedFunction, title: &str, command: &str| {
+        let (_, name) = function.name.as_ref()?;
+        let arguments = RunArguments {
+            module: module.name.clone(),
+            function: name.clone(),
+            target,
+        };
+        Some(CodeLens {
+            range: src_span_to_lsp_range(function.location, line_numbers),
+            command: Some(Command {
+                title: title.into(),
+                command: command.into(),
+                arguments: Some(vec![serde_json::to_value(arguments).ok()?]),
+            }),
+            data: None,
+        })
+    };
+
+    module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Function(function) if is_main_function(function, target) => {
+                lens(function, "Run", RUN_COMMAND)
+            }
+            Definition::Function(function)
+                if module.is_test() && is_test_function(function, target) =>
+            {
+                lens(function, "Run test", RUN_TEST_COMMAND)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_main_function(function: &TypedFunction, target: Target) -> bool {
+    is_runnable(function, target)
+        && function
+            .name
+            .as_ref()
+            .is_some_and(|(_, name)| name == "main")
+}
+
+fn is_test_function(function: &TypedFunction, target: Target) -> bool {
+    is_runnable(function, target)
+        && function
+            .name
+            .as_ref()
+            .is_some_and(|(_, name)| name.ends_with("_test"))
+}
+
+/// A function can be run if it is public, takes no arguments, and can be
+/// compiled for the current target.
+///
+fn is_runnable(function: &TypedFunction, target: Target) -> bool {
+    function.publicity.is_public()
+        && function.arguments.is_empty()
+        && function.implementations.supports(target)
+}
@@ -5,7 +5,7 @@ use itertools::Itertools;
 use crate::{
     Error, Result, Warning,
     analyse::TargetSupport,
-    build::{self, Mode, Module, NullTelemetry, Outcome, ProjectCompiler},
+    build::{self, Mode, Module, Outcome, ProjectCompiler, Telemetry},
     config::PackageConfig,
     io::{BeamCompiler, CommandExecutor, FileSystemReader, FileSystemWriter, Stdio},
     language_server::Locker,
@@ -51,6 +51,7 @@ where
         paths: ProjectPaths,
         io: IO,
         locker: Box<dyn Locker>,
+        telemetry: Box<dyn Telemetry>,
     ) -> Result<Self> {
         let target = config.target;
         let name = config.name.clone();
@@ -79,7 +80,7 @@ where
             config,
             options,
             manifest.packages,
-            &NullTelemetry,
+            telemetry,
             warnings.clone(),
             paths,
             io,
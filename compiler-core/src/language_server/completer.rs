@@ -12,6 +12,7 @@ use vec1::Vec1;
 
 use crate::{
     Result,
+    analyse::Inferred,
     ast::{
         self, Arg, CallArg, Definition, Function, FunctionLiteralKind, Pattern, Publicity,
         TypedExpr,
@@ -794,33 +795,127 @@ where
         fun: &TypedExpr,
         existing_args: &[CallArg<TypedExpr>],
     ) -> Vec<CompletionItem> {
+        let Some(field_map) =
+            self.callable_field_map(fun, self.compiler.project_compiler.get_importable_modules())
+        else {
+            return vec![];
+        };
         let fun_type = fun.type_().fn_types().map(|(args, _)| args);
         let already_included_labels = existing_args
             .iter()
-            .filter_map(|a| a.label.clone())
+            .filter_map(|a| a.label.as_ref())
             .collect_vec();
-        let Some(field_map) =
-            self.callable_field_map(fun, self.compiler.project_compiler.get_importable_modules())
+
+        label_completions(field_map, fun_type, &already_included_labels)
+    }
+
+    /// Provides completions for the fields that can still be updated when the
+    /// context being editted is a record update: `Wibble(..wibble, |)`
+    pub fn completion_record_update_labels(
+        &'a self,
+        constructor: &TypedExpr,
+        existing_args: &[CallArg<TypedExpr>],
+    ) -> Vec<CompletionItem> {
+        // The fields that are not explicitly updated are still part of the
+        // arguments, so we only look at the ones actually written down.
+        let explicit_args = existing_args
+            .iter()
+            .filter(|arg| arg.implicit.is_none())
+            .cloned()
+            .collect_vec();
+        self.completion_labels(constructor, &explicit_args)
+    }
+
+    /// Provides completions for labels when the context being editted is a
+    /// constructor pattern: `Wibble(wibble:, |)`
+    pub fn completion_pattern_labels(
+        &'a self,
+        pattern: &Pattern<Arc<Type>>,
+    ) -> Vec<CompletionItem> {
+        let Pattern::Constructor {
+            arguments,
+            constructor: Inferred::Known(constructor),
+            ..
+        } = pattern
         else {
             return vec![];
         };
+        let Some(field_map) = &constructor.field_map else {
+            return vec![];
+        };
 
-        field_map
-            .fields
+        let constructor_type = self
+            .compiler
+            .project_compiler
+            .get_importable_modules()
+            .get(&constructor.module)
+            .and_then(|module| module.values.get(&constructor.name))
+            .and_then(|value| value.type_.fn_types())
+            .map(|(args, _)| args);
+        // The fields ignored by a spread are added as implicit arguments, we
+        // only want to skip the ones that were actually written down.
+        let already_included_labels = arguments
             .iter()
-            .filter(|field| !already_included_labels.contains(field.0))
-            .map(|(label, arg_index)| {
-                let detail = fun_type.as_ref().and_then(|args| {
-                    args.get(*arg_index as usize)
-                        .map(|a| Printer::new().pretty_print(a, 0))
-                });
-                let label = format!("{label}:");
-                let sort_text = Some(sort_text(CompletionKind::Label, &label));
+            .filter(|argument| argument.implicit.is_none())
+            .filter_map(|argument| argument.label.as_ref())
+            .collect_vec();
+
+        label_completions(field_map, constructor_type, &already_included_labels)
+    }
+
+    /// Provides completions for the type variables that are in scope when the
+    /// context being editted is an annotation: those are the type parameters
+    /// of a type definition, or the type variables used in a function's
+    /// signature.
+    pub fn completion_type_variables(&'a self, byte_index: u32) -> Vec<CompletionItem> {
+        let (insert_range, module_select) = self.get_phrase_surrounding_completion();
+        if module_select.is_some() {
+            return vec![];
+        }
+
+        let mut names = vec![];
+        for definition in &self.module.ast.definitions {
+            match definition {
+                Definition::Function(function) if function.full_location().contains(byte_index) => {
+                    let annotations = function
+                        .arguments
+                        .iter()
+                        .filter_map(|argument| argument.annotation.as_ref())
+                        .chain(function.return_annotation.as_ref());
+                    for annotation in annotations {
+                        type_variables(annotation, &mut names);
+                    }
+                }
+                Definition::CustomType(custom_type)
+                    if custom_type.full_location().contains(byte_index) =>
+                {
+                    names.extend(custom_type.parameters.iter().map(|(_, name)| name.clone()));
+                }
+                Definition::TypeAlias(alias) if alias.location.contains(byte_index) => {
+                    names.extend(alias.parameters.iter().map(|(_, name)| name.clone()));
+                }
+                Definition::Function(_)
+                | Definition::CustomType(_)
+                | Definition::TypeAlias(_)
+                | Definition::Import(_)
+                | Definition::ModuleConstant(_) => (),
+            }
+        }
+
+        names
+            .into_iter()
+            .unique()
+            .map(|name| {
+                let label = name.to_string();
                 CompletionItem {
-                    label,
-                    detail,
-                    kind: Some(CompletionItemKind::FIELD),
-                    sort_text,
+                    label: label.clone(),
+                    kind: Some(CompletionItemKind::TYPE_PARAMETER),
+                    detail: Some("Type variable".into()),
+                    sort_text: Some(sort_text(CompletionKind::LocallyDefined, &label)),
+                    text_edit: Some(CompletionTextEdit::Edit(TextEdit {
+                        range: insert_range,
+                        new_text: label,
+                    })),
                     ..Default::default()
                 }
             })
@@ -847,6 +942,56 @@ where
     }
 }
 
+/// Completions for the labels of a field map that haven't been written yet.
+fn label_completions(
+    field_map: &FieldMap,
+    argument_types: Option<Vec<Arc<Type>>>,
+    already_included_labels: &[&EcoString],
+) -> Vec<CompletionItem> {
+    field_map
+        .fields
+        .iter()
+        .filter(|field| !already_included_labels.contains(&field.0))
+        .map(|(label, arg_index)| {
+            let detail = argument_types.as_ref().and_then(|args| {
+                args.get(*arg_index as usize)
+                    .map(|a| Printer::new().pretty_print(a, 0))
+            });
+            let label = format!("{label}:");
+            let sort_text = Some(sort_text(CompletionKind::Label, &label));
+            CompletionItem {
+                label,
+                detail,
+                kind: Some(CompletionItemKind::FIELD),
+                sort_text,
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// Collects the names of all the type variables used in an annotation.
+fn type_variables(type_: &ast::TypeAst, names: &mut Vec<EcoString>) {
+    match type_ {
+        ast::TypeAst::Var(var) => names.push(var.name.clone()),
+        ast::TypeAst::Constructor(constructor) => constructor
+            .arguments
+            .iter()
+            .for_each(|argument| type_variables(argument, names)),
+        ast::TypeAst::Fn(fun) => {
+            fun.arguments
+                .iter()
+                .for_each(|argument| type_variables(argument, names));
+            type_variables(&fun.return_, names);
+        }
+        ast::TypeAst::Tuple(tuple) => tuple
+            .elements
+            .iter()
+            .for_each(|element| type_variables(element, names)),
+        ast::TypeAst::Hole(_) => (),
+    }
+}
+
 fn add_import_to_completion(
     item: &mut CompletionItem,
     import_location: Position,
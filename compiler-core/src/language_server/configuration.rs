@@ -0,0 +1,42 @@
+use serde::Deserialize;
+
+/// The settings a client can send in the `initializationOptions` of the
+/// initialise request to customise the behaviour of the language server.
+/// Unknown or invalid settings are ignored and the defaults are used instead.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Configuration {
+    pub inlay_hints: InlayHintsConfig,
+}
+
+impl Configuration {
+    pub fn from_initialisation_options(options: Option<&serde_json::Value>) -> Self {
+        options
+            .and_then(|options| serde_json::from_value(options.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Which kinds of inlay hints should be shown.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InlayHintsConfig {
+    /// Show the type of `let` bindings without a type annotation.
+    pub let_bindings: bool,
+    /// Show the type of each step of a pipeline spanning multiple lines.
+    pub pipelines: bool,
+    /// Show the return type of functions without a return annotation.
+    pub function_returns: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        Self {
+            let_bindings: true,
+            pipelines: true,
+            function_returns: true,
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use std::sync::Arc;
+
+use ecow::EcoString;
+use lsp_types::{DocumentHighlight, DocumentHighlightKind};
+
+use crate::{
+    analyse::Inferred,
+    ast::{
+        ArgNames, CallArg, Definition, SrcSpan, TypedAssignment, TypedExpr, TypedPattern,
+        visit::{self, Visit},
+    },
+    build::{Located, Module},
+    line_numbers::LineNumbers,
+    reference::ReferenceKind,
+    type_::{
+        ModuleValueConstructor, PatternConstructor, Type, TypedCallArg, ValueConstructorVariant,
+        error::VariableOrigin,
+    },
+};
+
+use super::{
+    reference::{Referenced, find_variable_references, reference_for_ast_node},
+    src_span_to_lsp_range,
+};
+
+/// Returns all the occurrences in a module of the variable, value, type or
+/// label under the cursor. Places where a name is bound are highlighted as
+/// writes, while its uses are highlighted as reads.
+///
+pub fn document_highlights(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    found: Located<'_>,
+    byte_index: u32,
+) -> Option<Vec<DocumentHighlight>> {
+    let highlights = match label_at(module, &found, byte_index) {
+        Some((callee, label)) => label_highlights(module, callee, label),
+        None => reference_highlights(module, found, byte_index)?,
+    };
+
+    Some(
+        highlights
+            .into_iter()
+            .map(|(location, kind)| DocumentHighlight {
+                range: src_span_to_lsp_range(location, line_numbers),
+                kind: Some(kind),
+            })
+            .collect(),
+    )
+}
+
+fn reference_highlights(
+    module: &Module,
+    found: Located<'_>,
+    byte_index: u32,
+) -> Option<Vec<(SrcSpan, DocumentHighlightKind)>> {
+    match reference_for_ast_node(found, &module.name)? {
+        Referenced::LocalVariable {
+            origin,
+            definition_location,
+            location,
+        } if location.contains(byte_index) => match origin {
+            Some(VariableOrigin::Generated) => None,
+            Some(
+                VariableOrigin::LabelShorthand(_)
+                | VariableOrigin::AssignmentPattern
+                | VariableOrigin::Variable(_),
+            )
+            | None => Some(
+                std::iter::once((definition_location, DocumentHighlightKind::WRITE))
+                    .chain(
+                        find_variable_references(&module.ast, definition_location)
+                            .into_iter()
+                            .map(|location| (location, DocumentHighlightKind::READ)),
+                    )
+                    .collect(),
+            ),
+        },
+        Referenced::ModuleValue {
+            module: value_module,
+            name,
+            location,
+            ..
+        } if location.contains(byte_index) => Some(module_references(
+            &module.ast.type_info.references.value_references,
+            value_module,
+            name,
+        )),
+        Referenced::ModuleType {
+            module: type_module,
+            name,
+            location,
+            ..
+        } if location.contains(byte_index) => Some(module_references(
+            &module.ast.type_info.references.type_references,
+            type_module,
+            name,
+        )),
+        Referenced::LocalVariable { .. }
+        | Referenced::ModuleValue { .. }
+        | Referenced::ModuleType { .. } => None,
+    }
+}
+
+fn module_references(
+    references: &crate::reference::ReferenceMap,
+    module: EcoString,
+    name: EcoString,
+) -> Vec<(SrcSpan, DocumentHighlightKind)> {
+    let Some(references) = references.get(&(module, name)) else {
+        return vec![];
+    };
+
+    references
+        .iter()
+        .map(|reference| {
+            let kind = match reference.kind {
+                ReferenceKind::Definition | ReferenceKind::Import => DocumentHighlightKind::WRITE,
+                ReferenceKind::Qualified | ReferenceKind::Unqualified | ReferenceKind::Alias => {
+                    DocumentHighlightKind::READ
+                }
+            };
+            (reference.location, kind)
+        })
+        .collect()
+}
+
+/// The module and name of the function or record constructor a label
+/// belongs to.
+///
+pub type Callee = (EcoString, EcoString);
+
+/// If the cursor is on a label, returns it along with the function or record
+/// constructor it belongs to.
+///
+fn label_at(module: &Module, found: &Located<'_>, byte_index: u32) -> Option<(Callee, EcoString)> {
+    match found {
+        // The label of a call argument or pattern: the call it belongs to
+        // has to be found to know what is being called.
+        Located::Label(location, _) => labelled_argument_callee(module, *location),
+
+        Located::Arg(arg) => match &arg.names {
+            ArgNames::NamedLabelled {
+                label,
+                label_location,
+                ..
+            }
+            | ArgNames::LabelledDiscard {
+                label,
+                label_location,
+                ..
+            } if label_location.contains(byte_index) => {
+                let function = function_name_at(module, byte_index)?;
+                Some(((module.name.clone(), function.clone()), label.clone()))
+            }
+            _ => None,
+        },
+
+        Located::VariantConstructorDefinition(constructor) => constructor
+            .arguments
+            .iter()
+            .find_map(|argument| match &argument.label {
+                Some((location, label)) if location.contains(byte_index) => Some((
+                    (module.name.clone(), constructor.name.clone()),
+                    label.clone(),
+                )),
+                _ => None,
+            }),
+
+        _ => None,
+    }
+}
+
+/// Returns the label of the call argument or pattern at the given location,
+/// along with the function or record constructor it belongs to.
+///
+pub fn labelled_argument_callee(module: &Module, location: SrcSpan) -> Option<(Callee, EcoString)> {
+    let mut finder = FindLabelledArgument {
+        location,
+        found: None,
+    };
+    finder.visit_typed_module(&module.ast);
+    finder.found
+}
+
+fn function_name_at(module: &Module, byte_index: u32) -> Option<&EcoString> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Function(function) if function.full_location().contains(byte_index) => {
+                function.name.as_ref().map(|(_, name)| name)
+            }
+            _ => None,
+        })
+}
+
+/// Returns the occurrences of a label in the definition of its function or
+/// record constructor, and in all the calls and patterns using it.
+///
+fn label_highlights(
+    module: &Module,
+    callee: Callee,
+    label: EcoString,
+) -> Vec<(SrcSpan, DocumentHighlightKind)> {
+    let mut highlights = vec![];
+
+    if callee.0 == module.name {
+        for definition in &module.ast.definitions {
+            match definition {
+                Definition::Function(function)
+                    if function.name.as_ref().map(|(_, name)| name) == Some(&callee.1) =>
+                {
+                    highlights.extend(function.arguments.iter().filter_map(|argument| {
+                        match &argument.names {
+                            ArgNames::NamedLabelled {
+                                label: argument_label,
+                                label_location,
+                                ..
+                            }
+                            | ArgNames::LabelledDiscard {
+                                label: argument_label,
+                                label_location,
+                                ..
+                            } if *argument_label == label => Some(*label_location),
+                            _ => None,
+                        }
+                    }));
+                }
+                Definition::CustomType(custom_type) => {
+                    let constructors = custom_type
+                        .constructors
+                        .iter()
+                        .filter(|constructor| constructor.name == callee.1);
+                    highlights.extend(
+                        constructors
+                            .flat_map(|constructor| &constructor.arguments)
+                            .filter_map(|argument| match &argument.label {
+                                Some((location, argument_label)) if *argument_label == label => {
+                                    Some(*location)
+                                }
+                                _ => None,
+                            }),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut finder = FindLabelUses {
+        callee,
+        label,
+        uses: vec![],
+    };
+    finder.visit_typed_module(&module.ast);
+
+    highlights
+        .into_iter()
+        .map(|location| (location, DocumentHighlightKind::WRITE))
+        .chain(
+            finder
+                .uses
+                .into_iter()
+                .map(|location| (location, DocumentHighlightKind::READ)),
+        )
+        .collect()
+}
+
+fn expression_callee(expression: &TypedExpr) -> Option<Callee> {
+    match expression {
+        TypedExpr::Var { constructor, .. } => match &constructor.variant {
+            ValueConstructorVariant::ModuleFn { module, name, .. }
+            | ValueConstructorVariant::Record { module, name, .. } => {
+                Some((module.clone(), name.clone()))
+            }
+            _ => None,
+        },
+        TypedExpr::ModuleSelect {
+            constructor,
+            label,
+            module_name,
+            ..
+        } => match constructor {
+            ModuleValueConstructor::Fn { module, name, .. } => Some((module.clone(), name.clone())),
+            ModuleValueConstructor::Record { .. } => Some((module_name.clone(), label.clone())),
+            ModuleValueConstructor::Constant { .. } => None,
+        },
+        _ => None,
+    }
+}
+
+fn pattern_callee(constructor: &Inferred<PatternConstructor>) -> Option<Callee> {
+    match constructor {
+        Inferred::Known(constructor) => {
+            Some((constructor.module.clone(), constructor.name.clone()))
+        }
+        Inferred::Unknown => None,
+    }
+}
+
+/// The location of the label at the start of a labelled argument.
+///
+fn label_location<T>(argument: &CallArg<T>) -> Option<(SrcSpan, &EcoString)> {
+    let label = argument.label.as_ref()?;
+    let start = argument.location.start;
+    Some((SrcSpan::new(start, start + label.len() as u32), label))
+}
+
+/// Finds the function or record constructor called with the labelled argument
+/// at the given location.
+///
+struct FindLabelledArgument {
+    location: SrcSpan,
+    found: Option<(Callee, EcoString)>,
+}
+
+impl FindLabelledArgument {
+    fn check<T>(&mut self, callee: Option<Callee>, arguments: &[CallArg<T>]) {
+        let argument = arguments
+            .iter()
+            .find(|argument| argument.location == self.location);
+        if let (Some(callee), Some(label)) = (
+            callee,
+            argument.and_then(|argument| argument.label.as_ref()),
+        ) {
+            self.found = Some((callee, label.clone()));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for FindLabelledArgument {
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        self.check(expression_callee(fun), args);
+        visit::visit_typed_expr_call(self, location, type_, fun, args);
+    }
+
+    fn visit_typed_expr_record_update(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        record: &'ast TypedAssignment,
+        constructor: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        self.check(expression_callee(constructor), args);
+        visit::visit_typed_expr_record_update(self, location, type_, record, constructor, args);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_pattern_constructor(
+        &mut self,
+        location: &'ast SrcSpan,
+        name_location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        arguments: &'ast Vec<CallArg<TypedPattern>>,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        constructor: &'ast Inferred<PatternConstructor>,
+        spread: &'ast Option<SrcSpan>,
+        type_: &'ast Arc<Type>,
+    ) {
+        self.check(pattern_callee(constructor), arguments);
+        visit::visit_typed_pattern_constructor(
+            self,
+            location,
+            name_location,
+            name,
+            arguments,
+            module,
+            constructor,
+            spread,
+            type_,
+        );
+    }
+}
+
+/// Finds all the places where a label of the given function or record
+/// constructor is used.
+///
+struct FindLabelUses {
+    callee: Callee,
+    label: EcoString,
+    uses: Vec<SrcSpan>,
+}
+
+impl FindLabelUses {
+    fn check<T>(&mut self, callee: Option<Callee>, arguments: &[CallArg<T>]) {
+        if callee.as_ref() != Some(&self.callee) {
+            return;
+        }
+        self.uses.extend(
+            arguments
+                .iter()
+                .filter_map(label_location)
+                .filter(|(_, label)| **label == self.label)
+                .map(|(location, _)| location),
+        );
+    }
+}
+
+impl<'ast> Visit<'ast> for FindLabelUses {
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        self.check(expression_callee(fun), args);
+        visit::visit_typed_expr_call(self, location, type_, fun, args);
+    }
+
+    fn visit_typed_expr_record_update(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        record: &'ast TypedAssignment,
+        constructor: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        self.check(expression_callee(constructor), args);
+        visit::visit_typed_expr_record_update(self, location, type_, record, constructor, args);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_pattern_constructor(
+        &mut self,
+        location: &'ast SrcSpan,
+        name_location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        arguments: &'ast Vec<CallArg<TypedPattern>>,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        constructor: &'ast Inferred<PatternConstructor>,
+        spread: &'ast Option<SrcSpan>,
+        type_: &'ast Arc<Type>,
+    ) {
+        self.check(pattern_callee(constructor), arguments);
+        visit::visit_typed_pattern_constructor(
+            self,
+            location,
+            name_location,
+            name,
+            arguments,
+            module,
+            constructor,
+            spread,
+            type_,
+        );
+    }
+}
@@ -76,3 +76,24 @@ pub fn get_import_edit(
         new_text: ["import ", module_full_name, new_lines].concat(),
     }
 }
+
+/// Returns the start of the documentation and attributes preceding a
+/// definition that starts at `start`, so that they can be edited together
+/// with it.
+pub fn documentation_start(code: &str, start: u32) -> u32 {
+    let mut start = start as usize;
+    while let Some(before) = code.get(..start) {
+        let line_start = before.trim_end().rfind('\n').map_or(0, |index| index + 1);
+        match before.get(line_start..).map(str::trim_start) {
+            Some(line) if line.starts_with("///") || line.starts_with('@') => start = line_start,
+            _ => break,
+        }
+    }
+    start as u32
+}
+
+/// Returns the end of any whitespace following `end`.
+pub fn trailing_whitespace_end(code: &str, end: u32) -> u32 {
+    let rest = code.get(end as usize..).unwrap_or_default();
+    end + (rest.len() - rest.trim_start().len()) as u32
+}
@@ -9,7 +9,9 @@ use crate::{
     config::PackageConfig,
     io::{BeamCompiler, CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
-        compiler::LspProjectCompiler, files::FileSystemProxy, progress::ProgressReporter,
+        compiler::LspProjectCompiler,
+        files::FileSystemProxy,
+        progress::{CompilerProgress, ProgressReporter},
     },
     line_numbers::LineNumbers,
     paths::ProjectPaths,
@@ -29,28 +31,48 @@ use lsp_types::{
     PrepareRenameResponse, Range, SignatureHelp, SymbolKind, SymbolTag, TextEdit, Url,
     WorkspaceEdit,
 };
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use super::{
     DownloadDependencies, MakeLocker,
+    call_hierarchy::CallHierarchy,
+    change_signature::{self, ChangeSignatureArguments, ChangeSignatureOutcome},
     code_action::{
-        AddAnnotations, CodeActionBuilder, ConvertFromUse, ConvertToFunctionCall, ConvertToPipe,
-        ConvertToUse, ExpandFunctionCapture, ExtractConstant, ExtractVariable,
-        FillInMissingLabelledArgs, FillUnusedFields, GenerateDynamicDecoder, GenerateFunction,
-        GenerateJsonEncoder, InlineVariable, InterpolateString, LetAssertToCase,
-        PatternMatchOnValue, RedundantTupleInCaseSubject, RemoveEchos, UseLabelShorthandSyntax,
+        AddAnnotations, ChangeVisibility, CodeActionBuilder, ConvertFromUse, ConvertToFunctionCall,
+        ConvertToPipe, ConvertToUse, ExpandFunctionCapture, ExtractConstant, ExtractFunction,
+        ExtractVariable, FillInMissingLabelledArgs, FillUnusedFields, GenerateDynamicDecoder,
+        GenerateFunction, GenerateJsonEncoder, GenerateVariant, InlineFunction, InlineVariable,
+        InterpolateString, LetAssertToCase, OrganizeImports, PatternMatchOnValue,
+        RedundantTupleInCaseSubject, RemoveEchos, UseLabelShorthandSyntax,
         code_action_add_missing_patterns, code_action_convert_qualified_constructor_to_unqualified,
-        code_action_convert_unqualified_constructor_to_qualified, code_action_import_module,
-        code_action_inexhaustive_let_to_case,
+        code_action_convert_unqualified_constructor_to_qualified, code_action_fix_type_mismatch,
+        code_action_generate_type, code_action_import_module, code_action_inexhaustive_let_to_case,
     },
+    code_lens::code_lenses,
     completer::Completer,
+    configuration::InlayHintsConfig,
+    document_highlight::{document_highlights, labelled_argument_callee},
+    folding_range::folding_ranges,
+    generated_code::{GeneratedCode, GeneratedCodeParams, generated_code},
+    implementation::{NativeFiles, external_location, externals},
+    inlay_hints::get_inlay_hints,
+    move_definition::{self, MoveDefinitionArguments, MoveOutcome},
     reference::{
-        Referenced, find_module_references, find_variable_references, reference_for_ast_node,
+        ModuleItem, Referenced, find_module_references, find_variable_references,
+        reference_for_ast_node,
     },
     rename::{
         RenameTarget, Renamed, VariableRenameKind, rename_local_variable, rename_module_entity,
+        rename_references,
     },
-    signature_help, src_span_to_lsp_range,
+    rename_module::{self, RenameModuleArguments, RenameModuleOutcome},
+    selection_range::selection_range,
+    semantic_tokens::{semantic_tokens, semantic_tokens_edits},
+    signature_help, src_span_to_lsp_range, url_from_path,
+    workspace_symbols::find_workspace_symbols,
 };
 
 #[derive(Debug, PartialEq, Eq)]
@@ -89,6 +111,11 @@ pub struct LanguageServerEngine<IO, Reporter> {
     /// Used to know if to show the "View on HexDocs" link
     /// when hovering on an imported value
     hex_deps: HashSet<EcoString>,
+
+    /// The last semantic tokens sent to the client for each document, so that
+    /// following requests can be answered with just the changes.
+    semantic_tokens: HashMap<Url, lsp::SemanticTokens>,
+    semantic_tokens_result_id: u64,
 }
 
 impl<'a, IO, Reporter> LanguageServerEngine<IO, Reporter>
@@ -102,7 +129,7 @@ where
         + MakeLocker
         + Clone,
     // IO to be supplied from inside of gleam-core
-    Reporter: ProgressReporter + Clone + 'a,
+    Reporter: ProgressReporter + Clone + 'static,
 {
     pub fn new(
         config: PackageConfig,
@@ -121,8 +148,15 @@ where
         // NOTE: This must come after the progress reporter has finished!
         let manifest = manifest?;
 
-        let compiler: LspProjectCompiler<FileSystemProxy<IO>> =
-            LspProjectCompiler::new(manifest, config, paths.clone(), io.clone(), locker)?;
+        let progress = Box::new(CompilerProgress::new(progress_reporter.clone()));
+        let compiler: LspProjectCompiler<FileSystemProxy<IO>> = LspProjectCompiler::new(
+            manifest,
+            config,
+            paths.clone(),
+            io.clone(),
+            locker,
+            progress,
+        )?;
 
         let hex_deps = compiler
             .project_compiler
@@ -145,6 +179,8 @@ where
             paths,
             error: None,
             hex_deps,
+            semantic_tokens: HashMap::new(),
+            semantic_tokens_result_id: 0,
         })
     }
 
@@ -152,6 +188,22 @@ where
         self.respond(Self::compile)
     }
 
+    /// The name of the package this engine is compiling.
+    pub fn package_name(&self) -> &EcoString {
+        &self.compiler.project_compiler.config.name
+    }
+
+    /// Whether this project depends on the given package through its path
+    /// rather than by downloading it. Path dependencies are compiled from their
+    /// source, so any change to their modules affects this project too.
+    pub fn has_path_dependency(&self, package: &str) -> bool {
+        self.compiler
+            .project_compiler
+            .packages
+            .get(package)
+            .is_some_and(|package| package.is_local())
+    }
+
     /// Compile the project if we are in one. Otherwise do nothing.
     fn compile(&mut self) -> Result<(), Error> {
         self.compiled_since_last_feedback = true;
@@ -229,6 +281,73 @@ where
         })
     }
 
+    pub(crate) fn goto_implementation(
+        &mut self,
+        params: lsp_types::GotoDefinitionParams,
+    ) -> Response<Vec<lsp::Location>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let Some(current_module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let line_numbers = LineNumbers::new(&current_module.code);
+            let byte_index =
+                line_numbers.byte_index(params.position.line, params.position.character);
+
+            // The body of an external function without a Gleam implementation
+            // covers its head, so we look for the definition first.
+            let node = current_module
+                .ast
+                .definitions
+                .iter()
+                .find(|definition| match definition {
+                    Definition::Function(function) => function.location.contains(byte_index),
+                    _ => false,
+                })
+                .map(Located::ModuleStatement)
+                .or_else(|| current_module.find_node(byte_index));
+            let Some(node) = node else {
+                return Ok(vec![]);
+            };
+
+            let (module, mut externals) = externals(&node, &current_module.name);
+            let Some(source) = this.compiler.get_source(&module) else {
+                return Ok(vec![]);
+            };
+            let gleam_module_path = Utf8PathBuf::from(&source.path);
+
+            // Native files of the root package can be in both `src` and
+            // `test`, while for dependencies only `src` is compiled. The path
+            // of the Gleam module tells us where the dependency's `src`
+            // directory is: for Hex packages this is in `build/packages`.
+            let directories = if this.compiler.modules.contains_key(&module) {
+                vec![this.paths.src_directory(), this.paths.test_directory()]
+            } else {
+                let package_src = gleam_module_path
+                    .as_str()
+                    .strip_suffix(&format!("{module}.gleam"))
+                    .map(Utf8PathBuf::from);
+                package_src.into_iter().collect()
+            };
+            let native_files = NativeFiles {
+                gleam_module_path: &gleam_module_path,
+                directories,
+            };
+
+            // The implementation for the target the project is compiled to
+            // comes first.
+            let target = this.compiler.project_compiler.target();
+            externals.sort_by_key(|external| external.target != target);
+
+            Ok(externals
+                .iter()
+                .filter_map(|external| {
+                    external_location(external, &native_files, &this.compiler.project_compiler.io)
+                })
+                .collect())
+        })
+    }
+
     fn definition_location_to_lsp_location(
         &self,
         line_numbers: &LineNumbers,
@@ -283,6 +402,9 @@ where
 
             let completions = match found {
                 Located::PatternSpread { .. } => None,
+                Located::Pattern(pattern @ ast::Pattern::Constructor { .. }) => {
+                    Some(completer.completion_pattern_labels(pattern))
+                }
                 Located::Pattern(_pattern) => None,
                 // Do not show completions when typing inside a string.
                 Located::Expression(TypedExpr::String { .. }) => None,
@@ -298,17 +420,34 @@ where
                     completions.append(&mut completer.completion_field_accessors(record.type_()));
                     Some(completions)
                 }
+                Located::Expression(TypedExpr::RecordUpdate {
+                    constructor, args, ..
+                }) => {
+                    let mut completions = vec![];
+                    completions.append(&mut completer.completion_values());
+                    completions
+                        .append(&mut completer.completion_record_update_labels(constructor, args));
+                    Some(completions)
+                }
                 Located::Statement(_) | Located::Expression(_) => {
                     Some(completer.completion_values())
                 }
                 Located::ModuleStatement(Definition::Function(_)) => {
-                    Some(completer.completion_types())
+                    let mut completions = vec![];
+                    completions.append(&mut completer.completion_types());
+                    completions.append(&mut completer.completion_type_variables(byte_index));
+                    Some(completions)
                 }
 
                 Located::FunctionBody(_) => Some(completer.completion_values()),
 
                 Located::ModuleStatement(Definition::TypeAlias(_) | Definition::CustomType(_))
-                | Located::VariantConstructorDefinition(_) => Some(completer.completion_types()),
+                | Located::VariantConstructorDefinition(_) => {
+                    let mut completions = vec![];
+                    completions.append(&mut completer.completion_types());
+                    completions.append(&mut completer.completion_type_variables(byte_index));
+                    Some(completions)
+                }
 
                 // If the import completions returned no results and we are in an import then
                 // we should try to provide completions for unqualified values
@@ -325,7 +464,12 @@ where
 
                 Located::Arg(_) => None,
 
-                Located::Annotation { .. } => Some(completer.completion_types()),
+                Located::Annotation { .. } => {
+                    let mut completions = vec![];
+                    completions.append(&mut completer.completion_types());
+                    completions.append(&mut completer.completion_type_variables(byte_index));
+                    Some(completions)
+                }
 
                 Located::Label(_, _) => None,
 
@@ -349,14 +493,21 @@ where
     ) -> Response<Option<Vec<CodeAction>>> {
         self.respond(|this| {
             let mut actions = vec![];
+            code_action_generate_type(&params, &this.error, &mut actions);
+
             let Some(module) = this.module_for_uri(&params.text_document.uri) else {
-                return Ok(None);
+                return Ok(if actions.is_empty() {
+                    None
+                } else {
+                    Some(actions)
+                });
             };
 
             let lines = LineNumbers::new(&module.code);
 
             code_action_unused_values(module, &lines, &params, &mut actions);
             code_action_unused_imports(module, &lines, &params, &mut actions);
+            actions.extend(OrganizeImports::new(module, &lines, &params).code_actions());
             code_action_convert_qualified_constructor_to_unqualified(
                 module,
                 &lines,
@@ -372,6 +523,14 @@ where
             code_action_fix_names(&lines, &params, &this.error, &mut actions);
             code_action_import_module(module, &lines, &params, &this.error, &mut actions);
             code_action_add_missing_patterns(module, &lines, &params, &this.error, &mut actions);
+            code_action_fix_type_mismatch(
+                module,
+                &lines,
+                &params,
+                &this.error,
+                |name| this.can_import(module, name),
+                &mut actions,
+            );
             code_action_inexhaustive_let_to_case(
                 module,
                 &lines,
@@ -392,13 +551,24 @@ where
             actions.extend(InterpolateString::new(module, &lines, &params).code_actions());
             actions.extend(ExtractVariable::new(module, &lines, &params).code_actions());
             actions.extend(ExtractConstant::new(module, &lines, &params).code_actions());
+            actions.extend(ExtractFunction::new(module, &lines, &params).code_actions());
             actions.extend(GenerateFunction::new(module, &lines, &params).code_actions());
+            actions.extend(GenerateVariant::new(module, &lines, &params).code_actions());
             actions.extend(ConvertToPipe::new(module, &lines, &params).code_actions());
             actions.extend(ConvertToFunctionCall::new(module, &lines, &params).code_actions());
             actions.extend(
                 PatternMatchOnValue::new(module, &lines, &params, &this.compiler).code_actions(),
             );
             actions.extend(InlineVariable::new(module, &lines, &params).code_actions());
+            actions.extend(
+                InlineFunction::new(module, &lines, &params, &this.compiler.modules).code_actions(),
+            );
+            actions.extend(change_signature::code_actions(module, &lines, &params));
+            actions.extend(
+                ChangeVisibility::new(module, &lines, &params, &this.compiler.modules)
+                    .code_actions(),
+            );
+            actions.extend(move_definition::code_actions(module, &lines, &params));
             GenerateDynamicDecoder::new(module, &lines, &params, &mut actions).code_actions();
             GenerateJsonEncoder::new(module, &lines, &params, &mut actions).code_actions();
             AddAnnotations::new(module, &lines, &params).code_action(&mut actions);
@@ -555,6 +725,133 @@ where
         })
     }
 
+    pub fn workspace_symbol(
+        &mut self,
+        params: &lsp::WorkspaceSymbolParams,
+    ) -> Response<Vec<lsp::SymbolInformation>> {
+        self.respond(|this| {
+            Ok(find_workspace_symbols(
+                &params.query,
+                &this.compiler.project_compiler.config.name,
+                this.compiler.project_compiler.get_importable_modules(),
+                &this.compiler.sources,
+            ))
+        })
+    }
+
+    pub fn inlay_hints(
+        &mut self,
+        params: lsp::InlayHintParams,
+        config: InlayHintsConfig,
+    ) -> Response<Vec<lsp::InlayHint>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            Ok(get_inlay_hints(
+                &module.ast,
+                &line_numbers,
+                params.range,
+                config,
+            ))
+        })
+    }
+
+    pub fn folding_range(
+        &mut self,
+        params: lsp::FoldingRangeParams,
+    ) -> Response<Vec<lsp::FoldingRange>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            Ok(folding_ranges(module))
+        })
+    }
+
+    pub fn selection_range(
+        &mut self,
+        params: lsp::SelectionRangeParams,
+    ) -> Response<Vec<lsp::SelectionRange>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            Ok(params
+                .positions
+                .into_iter()
+                .map(|position| selection_range(module, &line_numbers, position))
+                .collect())
+        })
+    }
+
+    pub fn semantic_tokens_full(
+        &mut self,
+        params: lsp::SemanticTokensParams,
+    ) -> Response<Option<lsp::SemanticTokensResult>> {
+        self.respond(|this| {
+            let uri = params.text_document.uri;
+            let Some(tokens) = this.semantic_tokens_for_uri(uri) else {
+                return Ok(None);
+            };
+            Ok(Some(lsp::SemanticTokensResult::Tokens(tokens)))
+        })
+    }
+
+    pub fn semantic_tokens_full_delta(
+        &mut self,
+        params: lsp::SemanticTokensDeltaParams,
+    ) -> Response<Option<lsp::SemanticTokensFullDeltaResult>> {
+        self.respond(|this| {
+            let uri = params.text_document.uri;
+            let previous = this.semantic_tokens.get(&uri).cloned();
+            let Some(tokens) = this.semantic_tokens_for_uri(uri) else {
+                return Ok(None);
+            };
+
+            // If we don't know about the tokens the client is referring to we
+            // have no choice but to send all of them again.
+            let result = match previous {
+                Some(previous) if previous.result_id == Some(params.previous_result_id) => {
+                    lsp::SemanticTokensFullDeltaResult::TokensDelta(lsp::SemanticTokensDelta {
+                        result_id: tokens.result_id,
+                        edits: semantic_tokens_edits(&previous.data, &tokens.data),
+                    })
+                }
+                Some(_) | None => lsp::SemanticTokensFullDeltaResult::Tokens(tokens),
+            };
+            Ok(Some(result))
+        })
+    }
+
+    /// Forgets the semantic tokens computed for a document that has been
+    /// closed, as the client won't ask for a delta of those.
+    ///
+    pub fn document_closed(&mut self, uri: &Url) {
+        let _ = self.semantic_tokens.remove(uri);
+    }
+
+    /// Computes the semantic tokens of a module, remembering them so that
+    /// future requests can be answered with a delta.
+    ///
+    fn semantic_tokens_for_uri(&mut self, uri: Url) -> Option<lsp::SemanticTokens> {
+        let module = self.module_for_uri(&uri)?;
+        let data = semantic_tokens(
+            module,
+            self.compiler.project_compiler.get_importable_modules(),
+        );
+
+        self.semantic_tokens_result_id += 1;
+        let tokens = lsp::SemanticTokens {
+            result_id: Some(self.semantic_tokens_result_id.to_string()),
+            data,
+        };
+        let _ = self.semantic_tokens.insert(uri, tokens.clone());
+        Some(tokens)
+    }
+
     /// Check whether a particular module is in the same package as this one
     fn is_same_package(&self, current_module: &Module, module_name: &str) -> bool {
         let other_module = self
@@ -704,6 +1001,73 @@ where
         })
     }
 
+    pub fn move_definition(
+        &mut self,
+        arguments: MoveDefinitionArguments,
+    ) -> Response<Option<MoveOutcome>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&arguments.uri) else {
+                return Ok(None);
+            };
+            Ok(move_definition::move_definition(
+                &this.compiler.modules,
+                &this.paths,
+                module,
+                arguments.position,
+                arguments.module.as_ref(),
+            ))
+        })
+    }
+
+    pub fn rename_module(
+        &mut self,
+        arguments: RenameModuleArguments,
+    ) -> Response<Option<RenameModuleOutcome>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&arguments.uri) else {
+                return Ok(None);
+            };
+            Ok(rename_module::rename_module(
+                &this.compiler.modules,
+                &this.paths,
+                &this.compiler.project_compiler.io,
+                module,
+                &arguments.module,
+            ))
+        })
+    }
+
+    pub fn will_rename_files(
+        &mut self,
+        params: lsp::RenameFilesParams,
+    ) -> Response<Option<WorkspaceEdit>> {
+        self.respond(|this| {
+            Ok(rename_module::will_rename_files(
+                &this.compiler.modules,
+                &this.paths,
+                &this.compiler.project_compiler.io,
+                &params.files,
+            ))
+        })
+    }
+
+    pub fn change_signature(
+        &mut self,
+        arguments: ChangeSignatureArguments,
+    ) -> Response<Option<ChangeSignatureOutcome>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&arguments.uri) else {
+                return Ok(None);
+            };
+            Ok(change_signature::change_signature(
+                &this.compiler.modules,
+                module,
+                arguments.position,
+                arguments.parameters,
+            ))
+        })
+    }
+
     pub fn find_references(
         &mut self,
         params: lsp::ReferenceParams,
@@ -776,6 +1140,264 @@ where
         })
     }
 
+    /// The value or type that a find references request at this position is
+    /// about, so that the projects depending on this one can be asked for its
+    /// references too.
+    pub fn referenced_module_item(
+        &mut self,
+        position: &lsp::TextDocumentPositionParams,
+    ) -> Response<Option<ModuleItem>> {
+        self.respond(|this| {
+            let Some((lines, found)) = this.node_at_position(position) else {
+                return Ok(None);
+            };
+
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+
+            let byte_index = lines.byte_index(position.position.line, position.position.character);
+
+            Ok(match reference_for_ast_node(found, &module.name) {
+                Some(Referenced::ModuleValue {
+                    module,
+                    name,
+                    location,
+                    ..
+                }) if location.contains(byte_index) => Some(ModuleItem {
+                    module,
+                    name,
+                    layer: ast::Layer::Value,
+                }),
+                Some(Referenced::ModuleType {
+                    module,
+                    name,
+                    location,
+                    ..
+                }) if location.contains(byte_index) => Some(ModuleItem {
+                    module,
+                    name,
+                    layer: ast::Layer::Type,
+                }),
+                _ => None,
+            })
+        })
+    }
+
+    /// The value or type that a rename at this position changes the name of,
+    /// so that the projects depending on this one can update their references
+    /// too. Renaming an unqualified import only introduces an alias in the
+    /// module importing it, so there is nothing to update elsewhere.
+    pub fn renamed_module_item(
+        &mut self,
+        position: &lsp::TextDocumentPositionParams,
+    ) -> Response<Option<ModuleItem>> {
+        self.respond(|this| {
+            let Some((_, found)) = this.node_at_position(position) else {
+                return Ok(None);
+            };
+
+            let Some(current_module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+
+            Ok(match reference_for_ast_node(found, &current_module.name) {
+                Some(
+                    Referenced::ModuleValue {
+                        module,
+                        target_kind: RenameTarget::Unqualified,
+                        ..
+                    }
+                    | Referenced::ModuleType {
+                        module,
+                        target_kind: RenameTarget::Unqualified,
+                        ..
+                    },
+                ) if module != current_module.name => None,
+                Some(Referenced::ModuleValue { module, name, .. }) => Some(ModuleItem {
+                    module,
+                    name,
+                    layer: ast::Layer::Value,
+                }),
+                Some(Referenced::ModuleType { module, name, .. }) => Some(ModuleItem {
+                    module,
+                    name,
+                    layer: ast::Layer::Type,
+                }),
+                Some(Referenced::LocalVariable { .. }) | None => None,
+            })
+        })
+    }
+
+    /// Finds the references to a value or type of a path dependency in the
+    /// modules of this project. The dependency's own modules are left to its
+    /// own engine.
+    pub fn find_dependency_references(
+        &mut self,
+        item: &ModuleItem,
+    ) -> Response<Vec<lsp::Location>> {
+        self.respond(|this| {
+            let uris = this.root_package_uris();
+            Ok(find_module_references(
+                item.module.clone(),
+                item.name.clone(),
+                this.compiler.project_compiler.get_importable_modules(),
+                &this.compiler.sources,
+                item.layer,
+            )
+            .into_iter()
+            .filter(|location| uris.contains(&location.uri))
+            .collect())
+        })
+    }
+
+    /// Renames the references to a value or type of a path dependency in the
+    /// modules of this project. The dependency's own modules are left to its
+    /// own engine.
+    pub fn rename_dependency_references(
+        &mut self,
+        item: &ModuleItem,
+        new_name: &str,
+    ) -> Response<WorkspaceEdit> {
+        self.respond(|this| {
+            let uris = this.root_package_uris();
+            let mut edit = rename_references(
+                this.compiler.project_compiler.get_importable_modules(),
+                &this.compiler.sources,
+                &item.module,
+                &item.name,
+                new_name,
+                item.layer,
+            );
+            if let Some(changes) = edit.changes.as_mut() {
+                changes.retain(|uri, _| uris.contains(uri));
+            }
+            Ok(edit)
+        })
+    }
+
+    /// The URIs of the modules of the root package, leaving out those of its
+    /// dependencies.
+    fn root_package_uris(&self) -> HashSet<Url> {
+        self.compiler
+            .modules
+            .keys()
+            .filter_map(|name| self.compiler.sources.get(name))
+            .filter_map(|source| url_from_path(&source.path))
+            .collect()
+    }
+
+    pub fn code_lens(&mut self, params: lsp::CodeLensParams) -> Response<Vec<lsp::CodeLens>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(vec![]);
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let target = this.compiler.project_compiler.target();
+            Ok(code_lenses(module, &line_numbers, target))
+        })
+    }
+
+    pub fn generated_code(
+        &mut self,
+        params: GeneratedCodeParams,
+    ) -> Response<Option<GeneratedCode>> {
+        self.respond(|this| {
+            let Some(module) = this.module_for_uri(&params.text_document.uri) else {
+                return Ok(None);
+            };
+
+            // A module with type errors can't be compiled to Erlang or
+            // JavaScript.
+            if let Some(Error::Type { path, .. }) = &this.error
+                && path == &module.input_path
+            {
+                return Ok(None);
+            }
+
+            Ok(generated_code(
+                module,
+                this.paths.root(),
+                &this.compiler.project_compiler.config,
+                this.compiler
+                    .project_compiler
+                    .get_importable_modules()
+                    .values(),
+                params.target,
+                params.position,
+            ))
+        })
+    }
+
+    pub fn document_highlight(
+        &mut self,
+        params: lsp::DocumentHighlightParams,
+    ) -> Response<Option<Vec<lsp::DocumentHighlight>>> {
+        self.respond(|this| {
+            let position = &params.text_document_position_params;
+            let Some((lines, found)) = this.node_at_position(position) else {
+                return Ok(None);
+            };
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+            let byte_index = lines.byte_index(position.position.line, position.position.character);
+            Ok(document_highlights(module, &lines, found, byte_index))
+        })
+    }
+
+    pub fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyItem>>> {
+        self.respond(|this| {
+            let position = &params.text_document_position_params;
+            let Some((lines, found)) = this.node_at_position(position) else {
+                return Ok(None);
+            };
+            let Some(module) = this.module_for_uri(&position.text_document.uri) else {
+                return Ok(None);
+            };
+            let byte_index = lines.byte_index(position.position.line, position.position.character);
+
+            Ok(match reference_for_ast_node(found, &module.name) {
+                Some(Referenced::ModuleValue {
+                    module,
+                    name,
+                    location,
+                    name_kind: Named::Function,
+                    ..
+                }) if location.contains(byte_index) => this
+                    .call_hierarchy()
+                    .item(&module, &name)
+                    .map(|item| vec![item]),
+                _ => None,
+            })
+        })
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyIncomingCall>>> {
+        self.respond(|this| Ok(Some(this.call_hierarchy().incoming_calls(&params.item))))
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyOutgoingCall>>> {
+        self.respond(|this| Ok(Some(this.call_hierarchy().outgoing_calls(&params.item))))
+    }
+
+    fn call_hierarchy(&self) -> CallHierarchy<'_> {
+        CallHierarchy {
+            modules: &self.compiler.modules,
+            importable_modules: self.compiler.project_compiler.get_importable_modules(),
+            sources: &self.compiler.sources,
+        }
+    }
+
     fn respond<T>(&mut self, handler: impl FnOnce(&mut Self) -> Result<T>) -> Response<T> {
         let result = handler(self);
         let warnings = self.take_warnings();
@@ -922,7 +1544,24 @@ Unused labelled fields:
                     ))
                 }
                 Located::Label(location, type_) => {
-                    Some(hover_for_label(location, type_, lines, module))
+                    let documentation = labelled_argument_callee(module, location).and_then(
+                        |((callee_module, callee), label)| {
+                            this.compiler
+                                .get_module_interface(&callee_module)?
+                                .values
+                                .get(&callee)?
+                                .field_map()?
+                                .documentation
+                                .get(&label)
+                        },
+                    );
+                    Some(hover_for_label(
+                        location,
+                        type_,
+                        documentation,
+                        lines,
+                        module,
+                    ))
                 }
                 Located::ModuleName { location, name, .. } => {
                     let Some(module) = this.compiler.get_module_interface(name) else {
@@ -990,6 +1629,22 @@ Unused labelled fields:
 
         self.compiler.modules.get(&module_name)
     }
+
+    /// Whether the given module can be imported by another one without relying
+    /// on a transitive dependency.
+    ///
+    fn can_import(&self, module: &Module, name: &str) -> bool {
+        let project_compiler = &self.compiler.project_compiler;
+        let Some(imported) = project_compiler.get_importable_modules().get(name) else {
+            return false;
+        };
+        let config = &project_compiler.config;
+        imported.package.is_empty()
+            || imported.package == config.name
+            || config.dependencies.contains_key(&imported.package)
+            // Test modules can also import dev dependencies.
+            || (!module.origin.is_src() && config.dev_dependencies.contains_key(&imported.package))
+    }
 }
 
 fn custom_type_symbol(
@@ -1202,11 +1857,15 @@ fn hover_for_annotation(
 fn hover_for_label(
     location: SrcSpan,
     type_: Arc<Type>,
+    documentation: Option<&EcoString>,
     line_numbers: LineNumbers,
     module: &Module,
 ) -> Hover {
     let type_ = Printer::new(&module.ast.names).print_type(&type_);
-    let contents = format!("```gleam\n{type_}\n```");
+    let contents = match documentation {
+        Some(documentation) => format!("```gleam\n{type_}\n```\n{documentation}"),
+        None => format!("```gleam\n{type_}\n```"),
+    };
     Hover {
         contents: HoverContents::Scalar(MarkedString::String(contents)),
         range: Some(src_span_to_lsp_range(location, &line_numbers)),
@@ -1585,7 +2244,7 @@ fn get_doc_marker_pos(content_pos: u32) -> u32 {
     content_pos.saturating_sub(3)
 }
 
-fn make_deprecated_symbol_tag(deprecation: &Deprecation) -> Option<Vec<SymbolTag>> {
+pub fn make_deprecated_symbol_tag(deprecation: &Deprecation) -> Option<Vec<SymbolTag>> {
     deprecation
         .is_deprecated()
         .then(|| vec![SymbolTag::DEPRECATED])
@@ -40,7 +40,7 @@ impl Feedback {
         }
     }
 
-    fn append_message(&mut self, diagnostic: Diagnostic) {
+    pub fn append_message(&mut self, diagnostic: Diagnostic) {
         self.messages.push(diagnostic);
     }
 }
@@ -462,6 +462,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -470,6 +471,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                tags: vec![],
             }],
         };
         feedback.append_feedback(Feedback {
@@ -481,6 +483,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 }],
             )]),
             messages: vec![],
@@ -497,6 +500,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            tags: vec![],
                         }],
                     ),
                     (
@@ -507,6 +511,7 @@ mod tests {
                             text: "Error 3".to_string(),
                             title: "Error 3".to_string(),
                             level: Level::Error,
+                            tags: vec![],
                         }],
                     ),
                 ]),
@@ -516,6 +521,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 },],
             }
         );
@@ -532,6 +538,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -540,6 +547,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                tags: vec![],
             }],
         };
         feedback.append_feedback(Feedback {
@@ -551,6 +559,7 @@ mod tests {
                     text: "Error 3".to_string(),
                     title: "Error 3".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 }],
             )]),
             messages: vec![],
@@ -566,6 +575,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        tags: vec![],
                     }],
                 ),]),
                 messages: vec![Diagnostic {
@@ -574,6 +584,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 },],
             }
         );
@@ -590,6 +601,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -598,6 +610,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                tags: vec![],
             }],
         };
         feedback.append_feedback(Feedback {
@@ -608,6 +621,7 @@ mod tests {
                 text: "Error 3".to_string(),
                 title: "Error 3".to_string(),
                 level: Level::Error,
+                tags: vec![],
             }],
         });
         assert_eq!(
@@ -621,6 +635,7 @@ mod tests {
                         text: "Error 1".to_string(),
                         title: "Error 1".to_string(),
                         level: Level::Error,
+                        tags: vec![],
                     },],
                 ),]),
                 messages: vec![
@@ -630,6 +645,7 @@ mod tests {
                         text: "Error 2".to_string(),
                         title: "Error 2".to_string(),
                         level: Level::Error,
+                        tags: vec![],
                     },
                     Diagnostic {
                         location: None,
@@ -637,6 +653,7 @@ mod tests {
                         text: "Error 3".to_string(),
                         title: "Error 3".to_string(),
                         level: Level::Error,
+                        tags: vec![],
                     }
                 ],
             }
@@ -654,6 +671,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -662,6 +680,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                tags: vec![],
             }],
         };
         feedback.append_feedback(Feedback {
@@ -680,6 +699,7 @@ mod tests {
                             text: "Error 1".to_string(),
                             title: "Error 1".to_string(),
                             level: Level::Error,
+                            tags: vec![],
                         },],
                     ),
                     (Utf8PathBuf::from("src/file2.gleam"), vec![],),
@@ -690,6 +710,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 },],
             }
         );
@@ -706,6 +727,7 @@ mod tests {
                     text: "Error 1".to_string(),
                     title: "Error 1".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 }],
             )]),
             messages: vec![Diagnostic {
@@ -714,6 +736,7 @@ mod tests {
                 text: "Error 2".to_string(),
                 title: "Error 2".to_string(),
                 level: Level::Error,
+                tags: vec![],
             }],
         };
         feedback.append_feedback(Feedback {
@@ -730,6 +753,7 @@ mod tests {
                     text: "Error 2".to_string(),
                     title: "Error 2".to_string(),
                     level: Level::Error,
+                    tags: vec![],
                 },],
             }
         );
@@ -50,6 +50,11 @@ where
         write_result
     }
 
+    /// The files whose unsaved contents are cached.
+    pub fn cached_files(&self) -> Vec<Utf8PathBuf> {
+        self.edit_cache.files()
+    }
+
     pub fn delete_mem_cache(&self, path: &Utf8Path) -> Result<()> {
         if self.edit_cache.is_directory(path) {
             self.edit_cache.delete_directory(path)
@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use itertools::Itertools;
+use lsp_types::{FoldingRange, FoldingRangeKind};
+use vec1::Vec1;
+
+use crate::{
+    ast::{
+        Definition, FunctionLiteralKind, SrcSpan, TypeAst, TypedArg, TypedAssignment, TypedClause,
+        TypedDefinition, TypedExpr, TypedFunction, TypedStatement,
+        visit::{self, Visit},
+    },
+    build::Module,
+    line_numbers::LineNumbers,
+    type_::{ModuleValueConstructor, Type, TypedCallArg, ValueConstructorVariant},
+};
+
+/// Returns the ranges of a module that can be folded: blocks of imports,
+/// functions, custom types, case clauses, groups of doc comments, and lists
+/// and records spanning multiple lines.
+///
+pub fn folding_ranges(module: &Module) -> Vec<FoldingRange> {
+    let line_numbers = LineNumbers::new(&module.code);
+    let mut collector = FoldingRangeCollector {
+        code: &module.code,
+        line_numbers: &line_numbers,
+        ranges: vec![],
+    };
+
+    collector.imports(&module.ast.definitions);
+    collector.comments(&module.extra.module_comments);
+    collector.comments(&module.extra.doc_comments);
+    for definition in &module.ast.definitions {
+        match definition {
+            Definition::Function(function) => collector.visit_typed_function(function),
+            Definition::CustomType(custom_type) => {
+                collector.fold(custom_type.full_location(), Some(FoldingRangeKind::Region));
+            }
+            Definition::TypeAlias(_) | Definition::Import(_) | Definition::ModuleConstant(_) => {}
+        }
+    }
+
+    collector
+        .ranges
+        .into_iter()
+        .sorted_by_key(|range| (range.start_line, range.end_line))
+        .collect()
+}
+
+struct FoldingRangeCollector<'a> {
+    code: &'a str,
+    line_numbers: &'a LineNumbers,
+    ranges: Vec<FoldingRange>,
+}
+
+impl FoldingRangeCollector<'_> {
+    /// The zero based line a byte index is on.
+    fn line(&self, byte_index: u32) -> u32 {
+        self.line_numbers.line_number(byte_index) - 1
+    }
+
+    fn fold(&mut self, location: SrcSpan, kind: Option<FoldingRangeKind>) {
+        let start_line = self.line(location.start);
+        let last = location.end.saturating_sub(1);
+        let mut end_line = self.line(last);
+
+        // If the folded code ends with a closing bracket, that is kept
+        // visible along with the line where the code starts.
+        let ends_with_bracket = self
+            .code
+            .get(last as usize..location.end as usize)
+            .is_some_and(|last| matches!(last, "}" | "]" | ")"));
+        if ends_with_bracket {
+            end_line = end_line.saturating_sub(1);
+        }
+
+        if end_line <= start_line {
+            return;
+        }
+
+        self.ranges.push(FoldingRange {
+            start_line,
+            start_character: None,
+            end_line,
+            end_character: None,
+            kind,
+            collapsed_text: None,
+        });
+    }
+
+    /// Each run of imports with no other definition in between them is folded
+    /// together.
+    ///
+    fn imports(&mut self, definitions: &[TypedDefinition]) {
+        let definitions = definitions
+            .iter()
+            .sorted_by_key(|definition| definition.location().start);
+
+        let mut block: Option<SrcSpan> = None;
+        for definition in definitions {
+            match (definition, block) {
+                (Definition::Import(import), Some(current)) => {
+                    block = Some(current.merge(&import.location));
+                }
+                (Definition::Import(import), None) => block = Some(import.location),
+                (_, Some(current)) => {
+                    self.fold(current, Some(FoldingRangeKind::Imports));
+                    block = None;
+                }
+                (_, None) => {}
+            }
+        }
+
+        if let Some(block) = block {
+            self.fold(block, Some(FoldingRangeKind::Imports));
+        }
+    }
+
+    /// Comments on consecutive lines are folded together.
+    ///
+    fn comments(&mut self, comments: &[SrcSpan]) {
+        let mut group: Option<SrcSpan> = None;
+        for comment in comments {
+            match group {
+                Some(current) if self.line(current.end) + 1 == self.line(comment.start) => {
+                    group = Some(current.merge(comment));
+                }
+                Some(current) => {
+                    self.fold(current, Some(FoldingRangeKind::Comment));
+                    group = Some(*comment);
+                }
+                None => group = Some(*comment),
+            }
+        }
+
+        if let Some(group) = group {
+            self.fold(group, Some(FoldingRangeKind::Comment));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for FoldingRangeCollector<'_> {
+    fn visit_typed_function(&mut self, fun: &'ast TypedFunction) {
+        self.fold(fun.full_location(), Some(FoldingRangeKind::Region));
+        visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast FunctionLiteralKind,
+        args: &'ast [TypedArg],
+        body: &'ast Vec1<TypedStatement>,
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        // The callback of a `use` is the rest of the block, so there's no
+        // function to fold.
+        if let FunctionLiteralKind::Anonymous { .. } = kind {
+            self.fold(*location, Some(FoldingRangeKind::Region));
+        }
+        visit::visit_typed_expr_fn(self, location, type_, kind, args, body, return_annotation);
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.fold(clause.location(), Some(FoldingRangeKind::Region));
+        visit::visit_typed_clause(self, clause);
+    }
+
+    fn visit_typed_expr_list(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        elements: &'ast [TypedExpr],
+        tail: &'ast Option<Box<TypedExpr>>,
+    ) {
+        self.fold(*location, Some(FoldingRangeKind::Region));
+        visit::visit_typed_expr_list(self, location, type_, elements, tail);
+    }
+
+    fn visit_typed_expr_call(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        fun: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        let is_record = match fun {
+            TypedExpr::Var { constructor, .. } => {
+                matches!(constructor.variant, ValueConstructorVariant::Record { .. })
+            }
+            TypedExpr::ModuleSelect { constructor, .. } => {
+                matches!(constructor, ModuleValueConstructor::Record { .. })
+            }
+            _ => false,
+        };
+        if is_record {
+            self.fold(*location, Some(FoldingRangeKind::Region));
+        }
+        visit::visit_typed_expr_call(self, location, type_, fun, args);
+    }
+
+    fn visit_typed_expr_record_update(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        record: &'ast TypedAssignment,
+        constructor: &'ast TypedExpr,
+        args: &'ast [TypedCallArg],
+    ) {
+        self.fold(*location, Some(FoldingRangeKind::Region));
+        visit::visit_typed_expr_record_update(self, location, type_, record, constructor, args);
+    }
+}
@@ -0,0 +1,108 @@
+use camino::Utf8Path;
+use lsp_types::{Position, Range, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analyse::TargetSupport,
+    ast::Definition,
+    build::{Module, Target, package_compiler::StdlibPackage},
+    codegen::TypeScriptDeclarations,
+    config::PackageConfig,
+    erlang,
+    javascript::{self, ModuleConfig},
+    line_numbers::LineNumbers,
+    type_::ModuleInterface,
+};
+use itertools::Itertools;
+
+use super::src_span_to_lsp_range;
+
+/// A custom request clients can send to see the Erlang or JavaScript code the
+/// compiler generates for a module, without having to look for it in the
+/// build directory.
+///
+#[derive(Debug)]
+pub enum GeneratedCodeRequest {}
+
+impl lsp_types::request::Request for GeneratedCodeRequest {
+    type Params = GeneratedCodeParams;
+    type Result = Option<GeneratedCode>;
+    const METHOD: &'static str = "gleam/generatedCode";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedCodeParams {
+    pub text_document: TextDocumentIdentifier,
+    pub target: Target,
+    /// The position of the cursor in the module, used to find the generated
+    /// code corresponding to the function it is in.
+    #[serde(default)]
+    pub position: Option<Position>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedCode {
+    pub code: String,
+    /// The range of the generated code that corresponds to the function the
+    /// cursor is in, if it could be found.
+    pub range: Option<Range>,
+}
+
+/// Generates the code for a module, exactly as it would be written to the
+/// build directory.
+///
+pub fn generated_code<'a>(
+    module: &Module,
+    project_root: &Utf8Path,
+    config: &PackageConfig,
+    importable_modules: impl IntoIterator<Item = &'a ModuleInterface>,
+    target: Target,
+    position: Option<Position>,
+) -> Option<GeneratedCode> {
+    let stdlib_package = StdlibPackage::find(
+        config,
+        importable_modules
+            .into_iter()
+            .map(|module| module.package.as_str()),
+    );
+
+    let line_numbers = LineNumbers::new(&module.code);
+    let (code, definition_spans) = match target {
+        Target::Erlang => {
+            erlang::module_with_definition_spans(&module.ast, &line_numbers, project_root).ok()?
+        }
+        Target::JavaScript => javascript::module_with_definition_spans(ModuleConfig {
+            module: &module.ast,
+            line_numbers: &line_numbers,
+            path: &module.input_path,
+            project_root,
+            src: &module.code,
+            target_support: TargetSupport::NotEnforced,
+            typescript: TypeScriptDeclarations::None,
+            stdlib_package,
+        })
+        .ok()?,
+    };
+
+    let range = position
+        .and_then(|position| {
+            let byte_index = line_numbers.byte_index(position.line, position.character);
+            let (index, _) =
+                module
+                    .ast
+                    .definitions
+                    .iter()
+                    .find_position(|definition| match definition {
+                        Definition::Function(function) => {
+                            function.full_location().contains(byte_index)
+                        }
+                        _ => false,
+                    })?;
+            definition_spans.get(&index).copied()
+        })
+        .map(|span| src_span_to_lsp_range(span, &LineNumbers::new(&code)));
+
+    Some(GeneratedCode { code, range })
+}
@@ -0,0 +1,273 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use lsp_types::Location;
+
+use crate::{
+    ast::{Definition, SrcSpan, TypedExpr},
+    build::{Located, Target},
+    io::{DirWalker, FileSystemReader},
+    line_numbers::LineNumbers,
+    type_::{ModuleValueConstructor, ValueConstructor, ValueConstructorVariant},
+};
+
+use super::{src_span_to_lsp_range, url_from_path};
+
+/// An `@external` implementation of a Gleam function, as written in its
+/// attribute.
+///
+#[derive(Debug, Clone)]
+pub struct External {
+    pub target: Target,
+    pub module: EcoString,
+    pub function: EcoString,
+}
+
+/// Returns the module defining the function under the cursor, along with its
+/// external implementations.
+///
+pub fn externals(located: &Located<'_>, current_module: &EcoString) -> (EcoString, Vec<External>) {
+    let (module, external_erlang, external_javascript) = match located {
+        Located::Expression(
+            TypedExpr::Var {
+                constructor:
+                    ValueConstructor {
+                        variant:
+                            ValueConstructorVariant::ModuleFn {
+                                module,
+                                external_erlang,
+                                external_javascript,
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            }
+            | TypedExpr::ModuleSelect {
+                constructor:
+                    ModuleValueConstructor::Fn {
+                        module,
+                        external_erlang,
+                        external_javascript,
+                        ..
+                    },
+                ..
+            },
+        ) => (
+            module.clone(),
+            external_erlang.clone(),
+            external_javascript.clone(),
+        ),
+
+        Located::ModuleStatement(Definition::Function(function)) => (
+            current_module.clone(),
+            function
+                .external_erlang
+                .as_ref()
+                .map(|(module, function, _)| (module.clone(), function.clone())),
+            function
+                .external_javascript
+                .as_ref()
+                .map(|(module, function, _)| (module.clone(), function.clone())),
+        ),
+
+        _ => return (current_module.clone(), vec![]),
+    };
+
+    let externals = [
+        (Target::Erlang, external_erlang),
+        (Target::JavaScript, external_javascript),
+    ]
+    .into_iter()
+    .filter_map(|(target, external)| {
+        let (module, function) = external?;
+        Some(External {
+            target,
+            module,
+            function,
+        })
+    })
+    .collect();
+
+    (module, externals)
+}
+
+/// Where to look for the native files implementing the externals of a Gleam
+/// module.
+///
+#[derive(Debug)]
+pub struct NativeFiles<'a> {
+    /// The path of the Gleam module defining the external function.
+    pub gleam_module_path: &'a Utf8Path,
+    /// The directories that native files are copied from for the package the
+    /// Gleam module belongs to.
+    pub directories: Vec<Utf8PathBuf>,
+}
+
+/// Finds the location of the native implementation of an external function.
+/// Returns `None` if the file can't be found, as is the case for functions
+/// implemented by the Erlang standard library or by a JavaScript package.
+///
+pub fn external_location(
+    external: &External,
+    native_files: &NativeFiles<'_>,
+    io: &impl FileSystemReader,
+) -> Option<Location> {
+    let (path, find_definition): (_, fn(&str, &str) -> Option<SrcSpan>) = match external.target {
+        Target::Erlang => (
+            find_erlang_module(&external.module, native_files, io)?,
+            erlang_function_span,
+        ),
+        Target::JavaScript => (
+            find_javascript_module(&external.module, native_files, io)?,
+            javascript_function_span,
+        ),
+    };
+
+    let src = io.read(&path).ok()?;
+    // If the function can't be found we still take the programmer to the file
+    // it should be defined in.
+    let span = find_definition(&src, &external.function).unwrap_or_default();
+    let uri = url_from_path(path.as_str())?;
+    let range = src_span_to_lsp_range(span, &LineNumbers::new(&src));
+    Some(Location { uri, range })
+}
+
+/// Erlang modules can be anywhere in the native directories, all that matters
+/// is the name of the file.
+///
+fn find_erlang_module(
+    module: &str,
+    native_files: &NativeFiles<'_>,
+    io: &impl FileSystemReader,
+) -> Option<Utf8PathBuf> {
+    let file_name = format!("{module}.erl");
+    native_files.directories.iter().find_map(|directory| {
+        DirWalker::new(directory.clone())
+            .into_file_iter(io)
+            .filter_map(Result::ok)
+            .find(|path| path.file_name() == Some(file_name.as_str()))
+    })
+}
+
+/// JavaScript modules are imported relative to the Gleam module using them.
+///
+fn find_javascript_module(
+    module: &str,
+    native_files: &NativeFiles<'_>,
+    io: &impl FileSystemReader,
+) -> Option<Utf8PathBuf> {
+    let path = resolve_javascript_import(native_files.gleam_module_path.parent()?, module)?;
+    io.is_file(&path).then_some(path)
+}
+
+/// The path of a relative JavaScript import, like `./wibble_ffi.mjs`, made
+/// from a file in `directory`. Returns `None` for imports of packages.
+///
+pub fn resolve_javascript_import(directory: &Utf8Path, module: &str) -> Option<Utf8PathBuf> {
+    if !module.starts_with("./") && !module.starts_with("../") {
+        return None;
+    }
+
+    let mut path = directory.to_path_buf();
+    for component in Utf8Path::new(module).components() {
+        match component {
+            camino::Utf8Component::CurDir => (),
+            camino::Utf8Component::ParentDir => _ = path.pop(),
+            component => path.push(component),
+        }
+    }
+    Some(path)
+}
+
+/// Finds the name of the first clause of an Erlang function, falling back to
+/// the attribute exporting it.
+///
+fn erlang_function_span(src: &str, function: &str) -> Option<SrcSpan> {
+    let quoted = format!("'{function}'");
+    let mut line_start = 0;
+    let mut export = None;
+
+    for line in src.split_inclusive('\n') {
+        for name in [function, quoted.as_str()] {
+            if line
+                .strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with('('))
+            {
+                return Some(span_at(line_start, name));
+            }
+        }
+
+        if export.is_none() && line.starts_with("-export") {
+            export = [function, quoted.as_str()].into_iter().find_map(|name| {
+                let index = find_word(line, &format!("{name}/"))?;
+                Some(span_at(line_start + index, name))
+            });
+        }
+
+        line_start += line.len();
+    }
+
+    export
+}
+
+/// Finds the name of an exported JavaScript function, or of an exported
+/// variable holding one.
+///
+fn javascript_function_span(src: &str, function: &str) -> Option<SrcSpan> {
+    let mut line_start = 0;
+
+    for line in src.split_inclusive('\n') {
+        let indentation = line.len() - line.trim_start().len();
+        let declaration = line
+            .trim_start()
+            .strip_prefix("export")
+            .map(str::trim_start)
+            .map(|rest| rest.strip_prefix("async").map_or(rest, str::trim_start))
+            .and_then(|rest| {
+                ["function*", "function", "const", "let", "var", "class"]
+                    .into_iter()
+                    .find_map(|keyword| rest.strip_prefix(keyword))
+            });
+
+        if let Some(declaration) = declaration
+            && declaration.starts_with(char::is_whitespace)
+            && declaration.trim_start().starts_with(function)
+            && !declaration
+                .trim_start()
+                .get(function.len()..)
+                .is_some_and(starts_with_identifier_char)
+        {
+            let start =
+                line_start + indentation + line.trim_start().len() - declaration.trim_start().len();
+            return Some(span_at(start, function));
+        }
+
+        line_start += line.len();
+    }
+
+    None
+}
+
+fn span_at(start: usize, name: &str) -> SrcSpan {
+    SrcSpan::new(start as u32, (start + name.len()) as u32)
+}
+
+/// Finds `word` in `line`, making sure it's not the end of a longer name.
+///
+fn find_word(line: &str, word: &str) -> Option<usize> {
+    line.match_indices(word)
+        .map(|(index, _)| index)
+        .find(|index| {
+            !line
+                .get(..*index)
+                .and_then(|before| before.chars().next_back())
+                .is_some_and(|char| char.is_alphanumeric() || char == '_' || char == '@')
+        })
+}
+
+fn starts_with_identifier_char(string: &str) -> bool {
+    string
+        .chars()
+        .next()
+        .is_some_and(|char| char.is_alphanumeric() || char == '_' || char == '$')
+}
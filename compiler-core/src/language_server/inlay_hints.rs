@@ -0,0 +1,185 @@
+use std::sync::Arc;
+
+use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, Range, TextEdit};
+use vec1::Vec1;
+
+use crate::{
+    ast::{
+        FunctionLiteralKind, PipelineAssignmentKind, SrcSpan, TypeAst, TypedArg, TypedAssignment,
+        TypedExpr, TypedFunction, TypedModule, TypedPipelineAssignment, TypedStatement,
+        visit::{self, Visit},
+    },
+    line_numbers::LineNumbers,
+    type_::{
+        Type,
+        printer::{Names, Printer},
+    },
+};
+
+use super::{configuration::InlayHintsConfig, lsp_range_to_src_span, src_span_to_lsp_range};
+
+/// Returns the inlay hints showing the inferred types that are not written
+/// down in the given range of a module.
+///
+pub fn get_inlay_hints(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    range: Range,
+    config: InlayHintsConfig,
+) -> Vec<InlayHint> {
+    let mut collector = InlayHintCollector {
+        config,
+        line_numbers,
+        range: lsp_range_to_src_span(range, line_numbers),
+        names: &module.names,
+        printer: Printer::new(&module.names),
+        hints: vec![],
+    };
+    collector.visit_typed_module(module);
+    collector.hints
+}
+
+struct InlayHintCollector<'a> {
+    config: InlayHintsConfig,
+    line_numbers: &'a LineNumbers,
+    range: SrcSpan,
+    names: &'a Names,
+    /// The same printer is used for all the hints of a definition so that its
+    /// type variables are named consistently.
+    printer: Printer<'a>,
+    hints: Vec<InlayHint>,
+}
+
+impl InlayHintCollector<'_> {
+    fn push_type_hint(&mut self, position: u32, label: String, annotation: Option<String>) {
+        if position < self.range.start || position > self.range.end {
+            return;
+        }
+
+        let position = src_span_to_lsp_range(SrcSpan::new(position, position), self.line_numbers);
+        // Double clicking a hint for a missing annotation inserts it in the
+        // code.
+        let text_edits = annotation.map(|new_text| {
+            vec![TextEdit {
+                range: position,
+                new_text,
+            }]
+        });
+
+        self.hints.push(InlayHint {
+            position: position.start,
+            label: InlayHintLabel::String(label),
+            kind: Some(InlayHintKind::TYPE),
+            text_edits,
+            tooltip: None,
+            padding_left: Some(true),
+            padding_right: None,
+            data: None,
+        });
+    }
+
+    fn return_type_hint(&mut self, head_end: u32, return_type: &Type) {
+        let return_type = self.printer.print_type(return_type);
+        self.push_type_hint(
+            head_end,
+            format!("-> {return_type}"),
+            Some(format!(" -> {return_type}")),
+        );
+    }
+
+    fn line(&self, position: u32) -> u32 {
+        self.line_numbers.line_number(position)
+    }
+}
+
+impl<'ast> Visit<'ast> for InlayHintCollector<'ast> {
+    fn visit_typed_function(&mut self, fun: &'ast TypedFunction) {
+        // Type variables in different definitions are unrelated, so their
+        // names start over for each of them.
+        self.printer = Printer::new(self.names);
+        if self.config.function_returns && fun.return_annotation.is_none() {
+            self.return_type_hint(fun.location.end, &fun.return_type);
+        }
+        visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast FunctionLiteralKind,
+        args: &'ast [TypedArg],
+        body: &'ast Vec1<TypedStatement>,
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        // Captures and `use` callbacks don't have a head where a return
+        // annotation could be written.
+        match (kind, type_.as_ref()) {
+            (FunctionLiteralKind::Anonymous { head }, Type::Fn { return_, .. })
+                if self.config.function_returns && return_annotation.is_none() =>
+            {
+                self.return_type_hint(head.end, return_);
+            }
+            _ => {}
+        }
+        visit::visit_typed_expr_fn(self, location, type_, kind, args, body, return_annotation);
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        // Pipelines and `use` expressions generate assignments that can't be
+        // annotated.
+        if self.config.let_bindings
+            && assignment.annotation.is_none()
+            && !assignment.kind.is_generated()
+        {
+            let type_ = self.printer.print_type(&assignment.type_());
+            self.push_type_hint(
+                assignment.pattern.location().end,
+                format!(": {type_}"),
+                Some(format!(": {type_}")),
+            );
+        }
+        visit::visit_typed_assignment(self, assignment);
+    }
+
+    fn visit_typed_expr_pipeline(
+        &mut self,
+        location: &'ast SrcSpan,
+        first_value: &'ast TypedPipelineAssignment,
+        assignments: &'ast [(TypedPipelineAssignment, PipelineAssignmentKind)],
+        finally: &'ast TypedExpr,
+        finally_kind: &'ast PipelineAssignmentKind,
+    ) {
+        // A pipeline written on a single line is short enough not to need
+        // any hints.
+        if self.config.pipelines && self.line(location.start) != self.line(location.end) {
+            let steps = std::iter::once(first_value.value.as_ref())
+                .chain(assignments.iter().map(|(step, _)| step.value.as_ref()))
+                .chain(std::iter::once(finally))
+                .collect::<Vec<_>>();
+
+            // Only the last step on each line gets a hint, otherwise they
+            // would end up stacked one after the other.
+            let mut steps = steps.iter().peekable();
+            while let Some(step) = steps.next() {
+                let end = step.location().end;
+                let is_last_on_line = steps
+                    .peek()
+                    .is_none_or(|next| self.line(next.location().start) != self.line(end));
+                if is_last_on_line {
+                    let type_ = self.printer.print_type(&step.type_());
+                    self.push_type_hint(end, type_.to_string(), None);
+                }
+            }
+        }
+
+        visit::visit_typed_expr_pipeline(
+            self,
+            location,
+            first_value,
+            assignments,
+            finally,
+            finally_kind,
+        );
+    }
+}
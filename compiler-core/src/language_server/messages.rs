@@ -5,13 +5,26 @@ use lsp::{
 };
 use lsp_types::{
     self as lsp,
-    notification::{DidChangeTextDocument, DidCloseTextDocument, DidSaveTextDocument},
+    notification::{
+        Cancel, DidChangeTextDocument, DidCloseTextDocument, DidSaveTextDocument, Exit,
+        Notification as _,
+    },
     request::{
-        CodeActionRequest, Completion, DocumentSymbolRequest, Formatting, GotoTypeDefinition,
-        HoverRequest, PrepareRenameRequest, References, Rename, SignatureHelpRequest,
+        CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+        CodeActionRequest, CodeLensRequest, Completion, DocumentDiagnosticRequest,
+        DocumentHighlightRequest, DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest,
+        Formatting, GotoImplementation, GotoTypeDefinition, HoverRequest, InlayHintRequest,
+        OnTypeFormatting, PrepareRenameRequest, RangeFormatting, References, Rename, Request as _,
+        SelectionRangeRequest, SemanticTokensFullDeltaRequest, SemanticTokensFullRequest, Shutdown,
+        SignatureHelpRequest, WillRenameFiles, WorkspaceDiagnosticRequest, WorkspaceSymbolRequest,
     },
 };
-use std::time::Duration;
+use std::{
+    collections::{HashSet, VecDeque},
+    time::Duration,
+};
+
+use super::generated_code::{GeneratedCodeParams, GeneratedCodeRequest};
 
 #[derive(Debug)]
 pub enum Message {
@@ -22,9 +35,12 @@ pub enum Message {
 #[derive(Debug)]
 pub enum Request {
     Format(lsp::DocumentFormattingParams),
+    RangeFormat(lsp::DocumentRangeFormattingParams),
+    OnTypeFormat(lsp::DocumentOnTypeFormattingParams),
     Hover(lsp::HoverParams),
     GoToDefinition(lsp::GotoDefinitionParams),
     GoToTypeDefinition(lsp::GotoDefinitionParams),
+    GoToImplementation(lsp::GotoDefinitionParams),
     Completion(lsp::CompletionParams),
     CodeAction(lsp::CodeActionParams),
     SignatureHelp(lsp::SignatureHelpParams),
@@ -32,6 +48,22 @@ pub enum Request {
     PrepareRename(lsp::TextDocumentPositionParams),
     Rename(lsp::RenameParams),
     FindReferences(lsp::ReferenceParams),
+    WorkspaceSymbol(lsp::WorkspaceSymbolParams),
+    InlayHints(lsp::InlayHintParams),
+    SemanticTokensFull(lsp::SemanticTokensParams),
+    SemanticTokensFullDelta(lsp::SemanticTokensDeltaParams),
+    FoldingRange(lsp::FoldingRangeParams),
+    SelectionRange(lsp::SelectionRangeParams),
+    DocumentHighlight(lsp::DocumentHighlightParams),
+    CodeLens(lsp::CodeLensParams),
+    PrepareCallHierarchy(lsp::CallHierarchyPrepareParams),
+    IncomingCalls(Box<lsp::CallHierarchyIncomingCallsParams>),
+    OutgoingCalls(Box<lsp::CallHierarchyOutgoingCallsParams>),
+    DocumentDiagnostic(lsp::DocumentDiagnosticParams),
+    WorkspaceDiagnostic(lsp::WorkspaceDiagnosticParams),
+    ExecuteCommand(lsp::ExecuteCommandParams),
+    WillRenameFiles(lsp::RenameFilesParams),
+    GeneratedCode(GeneratedCodeParams),
 }
 
 impl Request {
@@ -42,6 +74,14 @@ impl Request {
                 let params = cast_request::<Formatting>(request);
                 Some(Message::Request(id, Request::Format(params)))
             }
+            "textDocument/rangeFormatting" => {
+                let params = cast_request::<RangeFormatting>(request);
+                Some(Message::Request(id, Request::RangeFormat(params)))
+            }
+            "textDocument/onTypeFormatting" => {
+                let params = cast_request::<OnTypeFormatting>(request);
+                Some(Message::Request(id, Request::OnTypeFormat(params)))
+            }
             "textDocument/hover" => {
                 let params = cast_request::<HoverRequest>(request);
                 Some(Message::Request(id, Request::Hover(params)))
@@ -78,10 +118,87 @@ impl Request {
                 let params = cast_request::<GotoTypeDefinition>(request);
                 Some(Message::Request(id, Request::GoToTypeDefinition(params)))
             }
+            "textDocument/implementation" => {
+                let params = cast_request::<GotoImplementation>(request);
+                Some(Message::Request(id, Request::GoToImplementation(params)))
+            }
             "textDocument/references" => {
                 let params = cast_request::<References>(request);
                 Some(Message::Request(id, Request::FindReferences(params)))
             }
+            "workspace/symbol" => {
+                let params = cast_request::<WorkspaceSymbolRequest>(request);
+                Some(Message::Request(id, Request::WorkspaceSymbol(params)))
+            }
+            "textDocument/inlayHint" => {
+                let params = cast_request::<InlayHintRequest>(request);
+                Some(Message::Request(id, Request::InlayHints(params)))
+            }
+            "textDocument/semanticTokens/full" => {
+                let params = cast_request::<SemanticTokensFullRequest>(request);
+                Some(Message::Request(id, Request::SemanticTokensFull(params)))
+            }
+            "textDocument/semanticTokens/full/delta" => {
+                let params = cast_request::<SemanticTokensFullDeltaRequest>(request);
+                Some(Message::Request(
+                    id,
+                    Request::SemanticTokensFullDelta(params),
+                ))
+            }
+            "textDocument/foldingRange" => {
+                let params = cast_request::<FoldingRangeRequest>(request);
+                Some(Message::Request(id, Request::FoldingRange(params)))
+            }
+            "textDocument/selectionRange" => {
+                let params = cast_request::<SelectionRangeRequest>(request);
+                Some(Message::Request(id, Request::SelectionRange(params)))
+            }
+            "textDocument/documentHighlight" => {
+                let params = cast_request::<DocumentHighlightRequest>(request);
+                Some(Message::Request(id, Request::DocumentHighlight(params)))
+            }
+            "textDocument/codeLens" => {
+                let params = cast_request::<CodeLensRequest>(request);
+                Some(Message::Request(id, Request::CodeLens(params)))
+            }
+            "textDocument/prepareCallHierarchy" => {
+                let params = cast_request::<CallHierarchyPrepare>(request);
+                Some(Message::Request(id, Request::PrepareCallHierarchy(params)))
+            }
+            "callHierarchy/incomingCalls" => {
+                let params = cast_request::<CallHierarchyIncomingCalls>(request);
+                Some(Message::Request(
+                    id,
+                    Request::IncomingCalls(Box::new(params)),
+                ))
+            }
+            "callHierarchy/outgoingCalls" => {
+                let params = cast_request::<CallHierarchyOutgoingCalls>(request);
+                Some(Message::Request(
+                    id,
+                    Request::OutgoingCalls(Box::new(params)),
+                ))
+            }
+            "textDocument/diagnostic" => {
+                let params = cast_request::<DocumentDiagnosticRequest>(request);
+                Some(Message::Request(id, Request::DocumentDiagnostic(params)))
+            }
+            "workspace/diagnostic" => {
+                let params = cast_request::<WorkspaceDiagnosticRequest>(request);
+                Some(Message::Request(id, Request::WorkspaceDiagnostic(params)))
+            }
+            "workspace/executeCommand" => {
+                let params = cast_request::<ExecuteCommand>(request);
+                Some(Message::Request(id, Request::ExecuteCommand(params)))
+            }
+            "workspace/willRenameFiles" => {
+                let params = cast_request::<WillRenameFiles>(request);
+                Some(Message::Request(id, Request::WillRenameFiles(params)))
+            }
+            "gleam/generatedCode" => {
+                let params = cast_request::<GeneratedCodeRequest>(request);
+                Some(Message::Request(id, Request::GeneratedCode(params)))
+            }
             _ => None,
         }
     }
@@ -91,8 +208,10 @@ impl Request {
 pub enum Notification {
     /// A Gleam file has been modified in memory, and the new text is provided.
     SourceFileChangedInMemory { path: Utf8PathBuf, text: String },
-    /// A Gleam file has been saved or closed in the editor.
+    /// A Gleam file has been saved in the editor.
     SourceFileMatchesDisc { path: Utf8PathBuf },
+    /// A Gleam file has been closed in the editor.
+    SourceFileClosed { path: Utf8PathBuf },
     /// gleam.toml has changed.
     ConfigFileChanged { path: Utf8PathBuf },
     /// It's time to compile all open projects.
@@ -128,7 +247,7 @@ impl Notification {
             }
             "textDocument/didClose" => {
                 let params = cast_notification::<DidCloseTextDocument>(notification);
-                let notification = Notification::SourceFileMatchesDisc {
+                let notification = Notification::SourceFileClosed {
                     path: super::path(&params.text_document.uri),
                 };
                 Some(Message::Notification(notification))
@@ -159,25 +278,46 @@ pub enum Next {
 ///   stopped typing for a moment and would benefit from feedback.
 /// - A request type message is received, which requires an immediate response.
 ///
+/// It also keeps track of the requests the client has cancelled, so they can
+/// be dropped rather than answered if they are still waiting to be handled.
+///
 pub struct MessageBuffer {
     messages: Vec<Message>,
+    /// Messages received while looking for cancellations, which are yet to be
+    /// processed.
+    received: VecDeque<lsp_server::Message>,
+    cancelled: HashSet<lsp_server::RequestId>,
 }
 
 impl MessageBuffer {
     pub fn new() -> Self {
         Self {
             messages: Vec::new(),
+            received: VecDeque::new(),
+            cancelled: HashSet::new(),
         }
     }
 
     pub fn receive(&mut self, conn: &lsp_server::Connection) -> Next {
         let pause = Duration::from_millis(100);
 
+        // Messages that were received while looking for cancellations come
+        // first, in the order they were sent.
         // If the buffer is empty, wait indefinitely for the first message.
         // If the buffer is not empty, wait for a short time to see if more messages are
         // coming before processing the ones we have.
-        let message = if self.messages.is_empty() {
-            Some(conn.receiver.recv().expect("Receiving LSP message"))
+        let message = if let Some(message) = self.received.pop_front() {
+            Some(message)
+        } else if self.messages.is_empty() {
+            // There are no requests waiting to be handled, so any cancellation
+            // left is for a request that has already been answered.
+            self.cancelled.clear();
+            match conn.receiver.recv() {
+                Ok(message) => Some(message),
+                // The client has gone away without asking the server to shut
+                // down, as happens when a socket connection is closed.
+                Err(_) => return Next::Stop,
+            }
         } else {
             conn.receiver.recv_timeout(pause).ok()
         };
@@ -216,7 +356,43 @@ impl MessageBuffer {
         Next::Handle(self.take_messages())
     }
 
+    /// Whether the client has cancelled a request that is yet to be handled,
+    /// for example because it was sent while the server was busy compiling.
+    ///
+    pub fn is_cancelled(
+        &mut self,
+        conn: &lsp_server::Connection,
+        id: &lsp_server::RequestId,
+    ) -> bool {
+        // Cancellations sent since the last message was received are still
+        // waiting in the connection, so we look through them. Any other
+        // messages are kept to be processed afterwards.
+        while let Ok(message) = conn.receiver.try_recv() {
+            match message {
+                lsp_server::Message::Notification(n) if n.method == Cancel::METHOD => {
+                    self.cancel(n)
+                }
+                message => self.received.push_back(message),
+            }
+        }
+        self.cancelled.remove(id)
+    }
+
+    fn cancel(&mut self, n: lsp_server::Notification) {
+        let params = cast_notification::<Cancel>(n);
+        let id = match params.id {
+            lsp::NumberOrString::Number(id) => id.into(),
+            lsp::NumberOrString::String(id) => id.into(),
+        };
+        _ = self.cancelled.insert(id);
+    }
+
     fn notification(&mut self, n: lsp_server::Notification) -> Next {
+        if n.method == Cancel::METHOD {
+            self.cancel(n);
+            return Next::MorePlease;
+        }
+
         // A new notification telling us that an edit has been made, or
         // something along those lines.
         if let Some(message) = Notification::extract(n) {
@@ -249,10 +425,24 @@ impl MessageBuffer {
         connection: &lsp_server::Connection,
         request: &lsp_server::Request,
     ) -> bool {
+        // The exit notification may already have been received while looking
+        // for cancellations, in which case there is nothing left to wait for.
+        if request.method == Shutdown::METHOD && self.received.iter().any(is_exit) {
+            let response = lsp_server::Response::new_ok(request.id.clone(), ());
+            connection
+                .sender
+                .send(response.into())
+                .expect("channel send LSP response");
+            return true;
+        }
         connection.handle_shutdown(request).expect("LSP shutdown")
     }
 }
 
+fn is_exit(message: &lsp_server::Message) -> bool {
+    matches!(message, lsp_server::Message::Notification(notification) if notification.method == Exit::METHOD)
+}
+
 fn cast_request<R>(request: lsp_server::Request) -> R::Params
 where
     R: lsp::request::Request,
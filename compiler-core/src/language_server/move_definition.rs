@@ -0,0 +1,961 @@
+use std::collections::{HashMap, HashSet};
+
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionParams, Command, CreateFile, DocumentChangeOperation,
+    DocumentChanges, OneOf, OptionalVersionedTextDocumentIdentifier, Position, ResourceOp,
+    TextDocumentEdit, TextEdit, Url, WorkspaceEdit,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{AssignName, Definition, Import, Layer, SrcSpan, UnqualifiedImport},
+    build::{Module, Origin},
+    line_numbers::LineNumbers,
+    paths::ProjectPaths,
+    reference::{Reference, ReferenceKind},
+};
+
+use super::{
+    code_action::CodeActionBuilder,
+    edits::{
+        Newlines, add_newlines_after_import, documentation_start,
+        position_of_first_definition_if_import, trailing_whitespace_end,
+    },
+    engine::overlaps,
+    src_span_to_lsp_range, url_from_path,
+};
+
+/// The command clients run to move a definition to another module.
+pub const MOVE_DEFINITION_COMMAND: &str = "gleam.moveDefinition";
+
+/// The command run by the "Move definition…" code action. It's implemented by
+/// the client rather than the language server, so it's not advertised: the
+/// client asks which module the definition should be moved to, then runs
+/// [`MOVE_DEFINITION_COMMAND`] with the same arguments and `module` set.
+pub const PROMPT_MOVE_DEFINITION_COMMAND: &str = "gleam.promptMoveDefinition";
+
+/// The arguments of the move definition command: the definition at
+/// `position` in the module at `uri` is moved to `module`, which is created
+/// if it doesn't exist yet.
+///
+/// The code action leaves `module` out, for the client to fill in once it
+/// knows the destination.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveDefinitionArguments {
+    pub uri: Url,
+    pub position: Position,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<EcoString>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveOutcome {
+    /// The definition is moved by applying the edit. The moved code still uses
+    /// the `private_items` of its original module, which need to be made
+    /// public for it to compile.
+    Moved {
+        edit: WorkspaceEdit,
+        private_items: Vec<EcoString>,
+    },
+    /// The definition can't be moved to the chosen module.
+    Refused { reason: String },
+}
+
+/// A top level function, constant or custom type that can be moved to
+/// another module.
+///
+#[derive(Debug)]
+struct MovedDefinition {
+    name: EcoString,
+    /// The location of the definition's head, like `pub fn wibble(a: Int)`.
+    head: SrcSpan,
+    /// The location of the entire definition, including its documentation
+    /// and attributes.
+    location: SrcSpan,
+    is_private: bool,
+    /// The values and types defined by the definition: a custom type also
+    /// defines its constructors.
+    names: Vec<(Layer, EcoString)>,
+}
+
+fn movable_definitions(module: &Module) -> impl Iterator<Item = MovedDefinition> + '_ {
+    module.ast.definitions.iter().filter_map(|definition| {
+        let (name, head, end, is_private, names) = match definition {
+            Definition::Function(function) => {
+                let (_, name) = function.name.as_ref()?;
+                (
+                    name,
+                    function.location,
+                    function.end_position,
+                    function.publicity.is_private(),
+                    vec![(Layer::Value, name.clone())],
+                )
+            }
+            Definition::ModuleConstant(constant) => (
+                &constant.name,
+                constant.location,
+                constant.value.location().end,
+                constant.publicity.is_private(),
+                vec![(Layer::Value, constant.name.clone())],
+            ),
+            Definition::CustomType(custom_type) => (
+                &custom_type.name,
+                custom_type.location,
+                custom_type.end_position,
+                custom_type.publicity.is_private(),
+                std::iter::once((Layer::Type, custom_type.name.clone()))
+                    .chain(
+                        custom_type
+                            .constructors
+                            .iter()
+                            .map(|constructor| (Layer::Value, constructor.name.clone())),
+                    )
+                    .collect(),
+            ),
+            Definition::TypeAlias(_) | Definition::Import(_) => return None,
+        };
+
+        Some(MovedDefinition {
+            name: name.clone(),
+            head,
+            location: SrcSpan::new(documentation_start(&module.code, head.start), end),
+            is_private,
+            names,
+        })
+    })
+}
+
+/// A code action to move the definition under the cursor to another module.
+/// The action runs the client's `gleam.promptMoveDefinition` command, which
+/// asks for the destination, an existing module or a new one, before running
+/// the `gleam.moveDefinition` command so that the edits are only computed for
+/// the chosen module.
+///
+pub fn code_actions(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    params: &CodeActionParams,
+) -> Vec<CodeAction> {
+    let Some(definition) = movable_definitions(module).find(|definition| {
+        overlaps(
+            params.range,
+            src_span_to_lsp_range(definition.head, line_numbers),
+        )
+    }) else {
+        return vec![];
+    };
+
+    let arguments = MoveDefinitionArguments {
+        uri: params.text_document.uri.clone(),
+        position: src_span_to_lsp_range(definition.head, line_numbers).start,
+        module: None,
+    };
+    let Ok(arguments) = serde_json::to_value(arguments) else {
+        return vec![];
+    };
+
+    let title = "Move definition…";
+    let mut actions = vec![];
+    CodeActionBuilder::new(title)
+        .kind(CodeActionKind::REFACTOR)
+        .command(Command {
+            title: title.into(),
+            command: PROMPT_MOVE_DEFINITION_COMMAND.into(),
+            arguments: Some(vec![arguments]),
+        })
+        .preferred(false)
+        .push_to(&mut actions);
+    actions
+}
+
+/// Moves the definition at `position` in `module` to the `destination`
+/// module, creating it if it doesn't exist.
+///
+/// Every module importing the definition gets its imports and qualified
+/// references updated, and the moved code gets the imports it needs in its
+/// new module.
+///
+pub fn move_definition(
+    modules: &HashMap<EcoString, Module>,
+    paths: &ProjectPaths,
+    module: &Module,
+    position: Position,
+    destination: Option<&EcoString>,
+) -> Option<MoveOutcome> {
+    let line_numbers = LineNumbers::new(&module.code);
+    let byte_index = line_numbers.byte_index(position.line, position.character);
+    let definition =
+        movable_definitions(module).find(|definition| definition.head.contains(byte_index))?;
+
+    let refuse = |reason: String| Some(MoveOutcome::Refused { reason });
+
+    let Some(destination) = destination else {
+        return refuse(format!(
+            "Choose the module to move `{}` to",
+            definition.name
+        ));
+    };
+    if destination == &module.name {
+        return refuse(format!(
+            "`{}` is already in `{destination}`",
+            definition.name
+        ));
+    }
+    if !is_valid_module_name(destination) {
+        return refuse(format!("`{destination}` is not a valid module name"));
+    }
+
+    let destination_module = modules.get(destination);
+    if let Some(destination_module) = destination_module {
+        if destination_module.origin != module.origin {
+            return refuse(format!(
+                "`{}` can't be moved between `src` and `test` modules",
+                definition.name
+            ));
+        }
+
+        let interface = &destination_module.ast.type_info;
+        let already_defined = definition.names.iter().find(|(layer, name)| match layer {
+            Layer::Value => interface.values.contains_key(name),
+            Layer::Type => interface.types.contains_key(name),
+        });
+        if let Some((_, name)) = already_defined {
+            return refuse(format!("`{destination}` already defines `{name}`"));
+        }
+    }
+
+    let mut mover = DefinitionMover {
+        modules,
+        source: module,
+        destination: destination_module,
+        destination_name: destination,
+        definition,
+        edits: HashMap::new(),
+        moved_code_edits: vec![],
+        imports: HashMap::new(),
+        private_items: HashSet::new(),
+        used_by_source: false,
+    };
+    mover.update_moved_code();
+    mover.update_references();
+
+    if mover.creates_import_cycle() {
+        return refuse(format!(
+            "Moving `{}` to `{destination}` would create an import cycle",
+            mover.definition.name
+        ));
+    }
+
+    let directory = match module.origin {
+        Origin::Src => paths.src_directory(),
+        Origin::Test => paths.test_directory(),
+    };
+    let new_module_path = directory.join(format!("{destination}.gleam"));
+    mover.edit(new_module_path.as_str())
+}
+
+pub fn is_valid_module_name(name: &str) -> bool {
+    name.split('/').all(|segment| {
+        segment.starts_with(|char: char| char.is_ascii_lowercase())
+            && segment
+                .chars()
+                .all(|char| char.is_ascii_lowercase() || char.is_ascii_digit() || char == '_')
+    })
+}
+
+/// The changes to the import of a module inside another one.
+///
+#[derive(Debug, Default)]
+struct ImportChanges {
+    /// Whether the module is referenced with qualified references, so it needs
+    /// to be imported even if there are no unqualified items.
+    qualified: bool,
+    /// The alias to use if the module ends up being imported by a new import.
+    alias: Option<EcoString>,
+    /// The locations of the unqualified items to remove from the import.
+    removed: Vec<SrcSpan>,
+    /// The unqualified items to add to the import, like `type Wibble` or
+    /// `wobble as w`, along with their position in the import they come from.
+    added: Vec<(u32, EcoString)>,
+}
+
+impl ImportChanges {
+    fn imports_module(&self) -> bool {
+        self.qualified || !self.added.is_empty()
+    }
+
+    /// Adds an unqualified item, keeping the order the items had in the
+    /// import they come from.
+    ///
+    fn add(&mut self, location: SrcSpan, item: EcoString) {
+        if self.added.iter().all(|(_, added)| *added != item) {
+            self.added.push((location.start, item));
+            self.added.sort();
+        }
+    }
+
+    fn added_items(&self) -> impl Iterator<Item = &EcoString> {
+        self.added.iter().map(|(_, item)| item)
+    }
+}
+
+struct DefinitionMover<'a> {
+    modules: &'a HashMap<EcoString, Module>,
+    source: &'a Module,
+    destination: Option<&'a Module>,
+    destination_name: &'a EcoString,
+    definition: MovedDefinition,
+    /// The edits to each existing module, by module name.
+    edits: HashMap<EcoString, Vec<(SrcSpan, String)>>,
+    /// The edits to the code of the moved definition, using locations of the
+    /// module it is moved from.
+    moved_code_edits: Vec<(SrcSpan, String)>,
+    /// The changes to the imports of each module, by the names of the
+    /// importing and imported module.
+    imports: HashMap<(EcoString, EcoString), ImportChanges>,
+    private_items: HashSet<EcoString>,
+    /// Whether the rest of the module the definition is moved from still uses
+    /// it.
+    used_by_source: bool,
+}
+
+impl DefinitionMover<'_> {
+    fn is_moved(&self, module: &str, layer: Layer, name: &EcoString) -> bool {
+        module == self.source.name
+            && self
+                .definition
+                .names
+                .iter()
+                .any(|(moved_layer, moved_name)| *moved_layer == layer && moved_name == name)
+    }
+
+    fn is_destination(&self, module: &str) -> bool {
+        module == self.destination_name
+    }
+
+    fn import_changes(&mut self, module: &EcoString, imported: &EcoString) -> &mut ImportChanges {
+        self.imports
+            .entry((module.clone(), imported.clone()))
+            .or_default()
+    }
+
+    /// Updates the references inside the moved definition, so that they still
+    /// point to the same values and types from the destination module.
+    ///
+    fn update_moved_code(&mut self) {
+        let source = self.source;
+        let location = self.definition.location;
+        let destination = self.destination_name;
+        let source_alias = self
+            .destination
+            .and_then(|destination| import_of(destination, &source.name))
+            .and_then(Import::used_name)
+            .unwrap_or_else(|| default_alias(&source.name));
+
+        for (layer, module, name, reference) in references(source) {
+            if !location.contains(reference.location.start)
+                || reference.kind == ReferenceKind::Definition
+            {
+                continue;
+            }
+
+            if module == &source.name {
+                // Recursive references keep working as they are.
+                if self.is_moved(module, layer, name) {
+                    continue;
+                }
+
+                // Anything else defined in the original module is now
+                // accessed through a qualified reference.
+                let start = reference.location.start;
+                self.moved_code_edits
+                    .push((SrcSpan::new(start, start), format!("{source_alias}.")));
+                self.import_changes(destination, &source.name).qualified = true;
+                if is_private(source, layer, name) {
+                    _ = self.private_items.insert(name.clone());
+                }
+            } else if self.is_destination(module) {
+                // Values of the destination module are now defined alongside
+                // the moved code.
+                match reference.kind {
+                    ReferenceKind::Qualified => self
+                        .moved_code_edits
+                        .push((qualifier(&source.code, reference.location), "".into())),
+                    ReferenceKind::Alias => self
+                        .moved_code_edits
+                        .push((reference.location, name.to_string())),
+                    ReferenceKind::Unqualified
+                    | ReferenceKind::Import
+                    | ReferenceKind::Definition => {}
+                }
+            } else {
+                // Values from other modules need the same imports in the
+                // destination module. Anything without an import comes from
+                // the prelude.
+                let Some(import) = import_of(source, module) else {
+                    continue;
+                };
+
+                match reference.kind {
+                    ReferenceKind::Qualified => {
+                        let destination_alias = self
+                            .destination
+                            .and_then(|destination| import_of(destination, module))
+                            .and_then(Import::used_name);
+                        match destination_alias {
+                            Some(alias) if Some(&alias) != import.used_name().as_ref() => {
+                                self.moved_code_edits.push((
+                                    qualifier(&source.code, reference.location),
+                                    format!("{alias}."),
+                                ))
+                            }
+                            Some(_) | None => {}
+                        }
+
+                        let changes = self.import_changes(destination, module);
+                        changes.qualified = true;
+                        changes.alias = match &import.as_name {
+                            Some((AssignName::Variable(alias), _)) => Some(alias.clone()),
+                            Some((AssignName::Discard(_), _)) | None => None,
+                        };
+                    }
+                    ReferenceKind::Unqualified | ReferenceKind::Alias => {
+                        let used_name = code_at(&source.code, reference.location);
+                        let item =
+                            unqualified_imports(import).find(|item| item.used_name() == used_name);
+                        if let Some(item) = item {
+                            let text = EcoString::from(code_at(&source.code, item.location));
+                            self.import_changes(destination, module)
+                                .add(item.location, text);
+                        }
+                    }
+                    ReferenceKind::Import | ReferenceKind::Definition => {}
+                }
+            }
+        }
+    }
+
+    /// Updates all references to the moved definition, so that they point to
+    /// the destination module.
+    ///
+    fn update_references(&mut self) {
+        let source = self.source;
+        let destination = self.destination_name;
+        let location = self.definition.location;
+
+        let modules = self.modules;
+        for module in modules.values() {
+            let destination_alias = import_of(module, destination)
+                .and_then(Import::used_name)
+                .unwrap_or_else(|| default_alias(destination));
+
+            for (layer, referenced_module, name, reference) in references(module) {
+                if !self.is_moved(referenced_module, layer, name)
+                    || reference.kind == ReferenceKind::Definition
+                {
+                    continue;
+                }
+
+                if module.name == source.name {
+                    if location.contains(reference.location.start) {
+                        continue;
+                    }
+                    let start = reference.location.start;
+                    self.edits
+                        .entry(module.name.clone())
+                        .or_default()
+                        .push((SrcSpan::new(start, start), format!("{destination_alias}.")));
+                    self.import_changes(&module.name, destination).qualified = true;
+                    self.used_by_source = true;
+                } else if self.is_destination(&module.name) {
+                    let edit = match reference.kind {
+                        ReferenceKind::Qualified => {
+                            (qualifier(&module.code, reference.location), "".into())
+                        }
+                        ReferenceKind::Alias => (reference.location, name.to_string()),
+                        ReferenceKind::Import => {
+                            if let Some(item) = imported_item(module, &source.name, reference) {
+                                self.import_changes(&module.name, &source.name)
+                                    .removed
+                                    .push(item.location);
+                            }
+                            continue;
+                        }
+                        ReferenceKind::Unqualified | ReferenceKind::Definition => continue,
+                    };
+                    self.edits
+                        .entry(module.name.clone())
+                        .or_default()
+                        .push(edit);
+                } else {
+                    match reference.kind {
+                        ReferenceKind::Qualified => {
+                            self.edits.entry(module.name.clone()).or_default().push((
+                                qualifier(&module.code, reference.location),
+                                format!("{destination_alias}."),
+                            ));
+                            self.import_changes(&module.name, destination).qualified = true;
+                        }
+                        ReferenceKind::Import => {
+                            let Some(item) = imported_item(module, &source.name, reference) else {
+                                continue;
+                            };
+                            let text = EcoString::from(code_at(&module.code, item.location));
+                            self.import_changes(&module.name, &source.name)
+                                .removed
+                                .push(item.location);
+                            self.import_changes(&module.name, destination)
+                                .add(item.location, text);
+                        }
+                        ReferenceKind::Unqualified
+                        | ReferenceKind::Alias
+                        | ReferenceKind::Definition => {}
+                    }
+                }
+            }
+        }
+
+        // A private definition used by the rest of its original module needs
+        // to become public.
+        if self.definition.is_private && self.used_by_source {
+            let start = self.definition.head.start;
+            self.moved_code_edits
+                .push((SrcSpan::new(start, start), "pub ".into()));
+        }
+    }
+
+    /// Whether the only things a module uses from the module the definition is
+    /// moved from are the moved values and types, so it doesn't need to import
+    /// it anymore.
+    ///
+    fn only_uses_moved_definition(&self, module: &Module) -> bool {
+        if module.name == self.source.name {
+            return false;
+        }
+        let mut used_names = references(module)
+            .filter(|(_, referenced_module, _, _)| **referenced_module == self.source.name)
+            .peekable();
+        used_names.peek().is_some()
+            && used_names.all(|(layer, referenced_module, name, _)| {
+                self.is_moved(referenced_module, layer, name)
+            })
+            && !self
+                .imports
+                .get(&(module.name.clone(), self.source.name.clone()))
+                .is_some_and(ImportChanges::imports_module)
+    }
+
+    /// The modules imported by a module once the definition is moved.
+    ///
+    fn imports_after_move(&self, module: &EcoString) -> HashSet<EcoString> {
+        let mut imported = HashSet::new();
+        if let Some(module) = self.modules.get(module) {
+            imported.extend(module.dependencies.iter().map(|(name, _)| name.clone()));
+
+            if self.only_uses_moved_definition(module) {
+                _ = imported.remove(&self.source.name);
+            }
+        }
+
+        for ((importing, imported_module), changes) in &self.imports {
+            if importing == module && changes.imports_module() {
+                _ = imported.insert(imported_module.clone());
+            }
+        }
+        imported
+    }
+
+    fn creates_import_cycle(&self) -> bool {
+        let mut visited = HashSet::new();
+        let mut to_visit = vec![self.destination_name.clone()];
+        while let Some(module) = to_visit.pop() {
+            for imported in self.imports_after_move(&module) {
+                if self.is_destination(&imported) {
+                    return true;
+                }
+                if visited.insert(imported.clone()) {
+                    to_visit.push(imported);
+                }
+            }
+        }
+        false
+    }
+
+    fn moved_code(&self) -> String {
+        let location = self.definition.location;
+        let mut code = String::new();
+        let mut position = location.start;
+        for (span, new_text) in self
+            .moved_code_edits
+            .iter()
+            .sorted_by_key(|(span, _)| (span.start, span.end))
+            .dedup()
+        {
+            code.push_str(code_at(
+                &self.source.code,
+                SrcSpan::new(position, span.start),
+            ));
+            code.push_str(new_text);
+            position = span.end;
+        }
+        code.push_str(code_at(
+            &self.source.code,
+            SrcSpan::new(position, location.end),
+        ));
+        code
+    }
+
+    fn edit(mut self, new_module_path: &str) -> Option<MoveOutcome> {
+        let moved_code = self.moved_code();
+
+        // The definition is removed from its module, along with the whitespace
+        // following it.
+        let code = &self.source.code;
+        let location = self.definition.location;
+        let end = trailing_whitespace_end(code, location.end);
+        let removed = if end as usize == code.len() {
+            let start = code_at(code, SrcSpan::new(0, location.start))
+                .trim_end()
+                .len();
+            SrcSpan::new(start as u32, location.end)
+        } else {
+            SrcSpan::new(location.start, end)
+        };
+        self.edits
+            .entry(self.source.name.clone())
+            .or_default()
+            .push((removed, "".into()));
+
+        let mut changes = HashMap::new();
+        let modules_to_edit = self
+            .edits
+            .keys()
+            .chain(self.imports.keys().map(|(module, _)| module))
+            .filter(|module| !self.is_destination(module))
+            .cloned()
+            .collect::<HashSet<_>>();
+        for module_name in modules_to_edit {
+            let module = self.modules.get(&module_name)?;
+            let mut edits = self.edits.remove(&module_name).unwrap_or_default();
+            edits.extend(self.import_edits(module));
+            let _ = changes.insert(
+                url_from_path(module.input_path.as_str())?,
+                text_edits(module, edits),
+            );
+        }
+
+        let private_items = self.private_items.iter().cloned().sorted().collect();
+
+        let Some(destination) = self.destination else {
+            let mut content = self
+                .new_imports(self.destination_name)
+                .into_iter()
+                .map(|import| format!("{import}\n"))
+                .join("");
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            content.push_str(&moved_code);
+            content.push('\n');
+
+            let uri = url_from_path(new_module_path)?;
+            let new_module_edit = TextEdit {
+                range: Default::default(),
+                new_text: content,
+            };
+            let mut operations = vec![
+                DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+                    uri: uri.clone(),
+                    options: None,
+                    annotation_id: None,
+                })),
+                text_document_edit(uri, vec![new_module_edit]),
+            ];
+            operations.extend(
+                changes
+                    .into_iter()
+                    .sorted_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()))
+                    .map(|(uri, edits)| text_document_edit(uri, edits)),
+            );
+
+            return Some(MoveOutcome::Moved {
+                edit: WorkspaceEdit {
+                    changes: None,
+                    document_changes: Some(DocumentChanges::Operations(operations)),
+                    change_annotations: None,
+                },
+                private_items,
+            });
+        };
+
+        // The moved definition goes at the end of the destination module.
+        let code = &destination.code;
+        let end = code.trim_end().len() as u32;
+        let mut edits = self.edits.remove(&destination.name).unwrap_or_default();
+        if end == 0 {
+            edits.push((
+                SrcSpan::new(0, code.len() as u32),
+                format!("{moved_code}\n"),
+            ));
+        } else {
+            edits.push((SrcSpan::new(end, end), format!("\n\n{moved_code}")));
+        }
+        edits.extend(self.import_edits(destination));
+        let _ = changes.insert(
+            url_from_path(destination.input_path.as_str())?,
+            text_edits(destination, edits),
+        );
+
+        Some(MoveOutcome::Moved {
+            edit: WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            },
+            private_items,
+        })
+    }
+
+    /// The new import statements needed by a module.
+    ///
+    fn new_imports(&self, module: &EcoString) -> Vec<String> {
+        self.imports
+            .iter()
+            .filter(|((importing, imported), changes)| {
+                importing == module
+                    && changes.imports_module()
+                    && self
+                        .modules
+                        .get(importing)
+                        .and_then(|importing| import_of(importing, imported))
+                        .is_none()
+            })
+            .sorted_by_key(|((_, imported), _)| imported)
+            .map(|((_, imported), changes)| {
+                let mut import = format!("import {imported}");
+                if !changes.added.is_empty() {
+                    import.push_str(&format!(".{{{}}}", changes.added_items().join(", ")));
+                }
+                if let Some(alias) = changes.alias.as_ref().filter(|_| changes.qualified) {
+                    import.push_str(&format!(" as {alias}"));
+                }
+                import
+            })
+            .collect()
+    }
+
+    /// The edits to the imports of an existing module: the unqualified items
+    /// of its imports are updated, and any missing import is added.
+    ///
+    fn import_edits(&self, module: &Module) -> Vec<(SrcSpan, String)> {
+        let mut edits = vec![];
+
+        // An import only used for the moved definition is removed.
+        let removed_import = import_of(module, &self.source.name)
+            .filter(|_| self.only_uses_moved_definition(module));
+        if let Some(import) = removed_import {
+            let end = match module.code.get(import.location.end as usize..) {
+                Some(rest) if rest.starts_with('\n') => import.location.end + 1,
+                Some(_) | None => import.location.end,
+            };
+            edits.push((SrcSpan::new(import.location.start, end), "".into()));
+        }
+
+        for ((_, imported), changes) in self
+            .imports
+            .iter()
+            .filter(|((importing, _), _)| *importing == module.name)
+        {
+            let Some(import) = import_of(module, imported) else {
+                continue;
+            };
+            if *imported == self.source.name && removed_import.is_some() {
+                continue;
+            }
+            let existing = unqualified_imports(import)
+                .sorted_by_key(|item| item.location.start)
+                .collect_vec();
+            let mut items = existing
+                .iter()
+                .filter(|item| !changes.removed.contains(&item.location))
+                .map(|item| EcoString::from(code_at(&module.code, item.location)))
+                .collect_vec();
+            for item in changes.added_items() {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+            if changes.removed.is_empty() && items.len() == existing.len() {
+                continue;
+            }
+            edits.push(unqualified_items_edit(&module.code, import, &items));
+        }
+
+        let new_imports = self.new_imports(&module.name);
+        if new_imports.is_empty() {
+            return edits;
+        }
+        let line_numbers = LineNumbers::new(&module.code);
+        let first_import = position_of_first_definition_if_import(module, &line_numbers);
+        let position = first_import.unwrap_or_default();
+        let newlines = match add_newlines_after_import(
+            position,
+            first_import.is_some(),
+            &line_numbers,
+            &module.code,
+        ) {
+            Newlines::Single => "\n",
+            Newlines::Double => "\n\n",
+        };
+        let at = line_numbers.byte_index(position.line, position.character);
+        edits.push((
+            SrcSpan::new(at, at),
+            format!("{}{newlines}", new_imports.join("\n")),
+        ));
+        edits
+    }
+}
+
+/// Replaces the unqualified items of an import, like the `.{type Wibble,
+/// wobble}` in `import wibble.{type Wibble, wobble}`.
+///
+fn unqualified_items_edit(
+    code: &str,
+    import: &Import<EcoString>,
+    items: &[EcoString],
+) -> (SrcSpan, String) {
+    let import_code = code_at(code, import.location);
+    let module_end = import_code
+        .find(import.module.as_str())
+        .map_or(import.location.end, |index| {
+            import.location.start + (index + import.module.len()) as u32
+        });
+    let end = match import_code.rfind('}') {
+        Some(index) => import.location.start + index as u32 + 1,
+        None => module_end,
+    };
+    let new_text = if items.is_empty() {
+        String::new()
+    } else {
+        format!(".{{{}}}", items.iter().join(", "))
+    };
+    (SrcSpan::new(module_end, end), new_text)
+}
+
+pub fn text_edits(module: &Module, edits: Vec<(SrcSpan, String)>) -> Vec<TextEdit> {
+    let line_numbers = LineNumbers::new(&module.code);
+    edits
+        .into_iter()
+        .sorted_by_key(|(span, _)| (span.start, span.end))
+        .dedup()
+        .map(|(span, new_text)| TextEdit {
+            range: src_span_to_lsp_range(span, &line_numbers),
+            new_text,
+        })
+        .collect()
+}
+
+pub fn text_document_edit(uri: Url, edits: Vec<TextEdit>) -> DocumentChangeOperation {
+    DocumentChangeOperation::Edit(TextDocumentEdit {
+        text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+        edits: edits.into_iter().map(OneOf::Left).collect(),
+    })
+}
+
+/// All the references to values and types inside a module.
+///
+pub fn references(
+    module: &Module,
+) -> impl Iterator<Item = (Layer, &EcoString, &EcoString, &Reference)> {
+    let references = &module.ast.type_info.references;
+    let values = references
+        .value_references
+        .iter()
+        .map(|(key, references)| (Layer::Value, key, references));
+    let types = references
+        .type_references
+        .iter()
+        .map(|(key, references)| (Layer::Type, key, references));
+
+    values
+        .chain(types)
+        .flat_map(|(layer, (module, name), references)| {
+            references
+                .iter()
+                .map(move |reference| (layer, module, name, reference))
+        })
+}
+
+fn import_of<'a>(module: &'a Module, imported: &str) -> Option<&'a Import<EcoString>> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .find_map(|definition| match definition {
+            Definition::Import(import) if import.module == imported => Some(import),
+            _ => None,
+        })
+}
+
+fn unqualified_imports(import: &Import<EcoString>) -> impl Iterator<Item = &UnqualifiedImport> {
+    import
+        .unqualified_values
+        .iter()
+        .chain(import.unqualified_types.iter())
+}
+
+/// The unqualified item of an import a reference of kind
+/// `ReferenceKind::Import` points to.
+///
+fn imported_item<'a>(
+    module: &'a Module,
+    imported: &str,
+    reference: &Reference,
+) -> Option<&'a UnqualifiedImport> {
+    unqualified_imports(import_of(module, imported)?)
+        .find(|item| item.imported_name_location == reference.location)
+}
+
+fn is_private(module: &Module, layer: Layer, name: &EcoString) -> bool {
+    let interface = &module.ast.type_info;
+    match layer {
+        Layer::Value => interface
+            .values
+            .get(name)
+            .is_some_and(|value| value.publicity.is_private()),
+        Layer::Type => interface
+            .types
+            .get(name)
+            .is_some_and(|type_| type_.publicity.is_private()),
+    }
+}
+
+pub fn default_alias(module: &str) -> EcoString {
+    module.split('/').next_back().unwrap_or(module).into()
+}
+
+/// The location of the `module.` qualifier of a qualified reference.
+///
+pub fn qualifier(code: &str, location: SrcSpan) -> SrcSpan {
+    let referenced = code_at(code, location);
+    // Some qualified references include the module qualifier.
+    if let Some(dot) = referenced.find('.') {
+        return SrcSpan::new(location.start, location.start + dot as u32 + 1);
+    }
+
+    let before = code_at(code, SrcSpan::new(0, location.start));
+    let without_dot = before.trim_end().trim_end_matches('.').trim_end();
+    let module_start = without_dot
+        .trim_end_matches(|char: char| char.is_alphanumeric() || char == '_')
+        .len();
+    SrcSpan::new(module_start as u32, location.start)
+}
+
+pub fn code_at(code: &str, location: SrcSpan) -> &str {
+    code.get(location.start as usize..location.end as usize)
+        .unwrap_or_default()
+}
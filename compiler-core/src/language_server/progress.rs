@@ -1,14 +1,28 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
 use debug_ignore::DebugIgnore;
 use lsp_types::{
     InitializeParams, NumberOrString, ProgressParams, ProgressParamsValue, WorkDoneProgress,
     WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    WorkDoneProgressReport,
 };
 
-const DOWNLOADING_TOKEN: &str = "downloading-dependencies";
+use crate::build::Telemetry;
+
+const DOWNLOADING_TOKEN_PREFIX: &str = "downloading-dependencies";
+const COMPILING_TOKEN_PREFIX: &str = "compiling-gleam";
 
 pub trait ProgressReporter {
     fn compilation_started(&self);
     fn compilation_finished(&self);
+    fn compiling_package(&self, name: &str);
+    fn compiling_module(&self, name: &str);
     fn dependency_downloading_started(&self);
     fn dependency_downloading_finished(&self);
 }
@@ -16,25 +30,43 @@ pub trait ProgressReporter {
 // Used to publish progress notifications to the client without waiting for
 // the usual request-response loop of the language server.
 #[derive(Debug, Clone)]
-pub struct ConnectionProgressReporter<'a> {
-    connection: DebugIgnore<&'a lsp_server::Connection>,
+pub struct ConnectionProgressReporter {
+    sender: DebugIgnore<crossbeam_channel::Sender<lsp_server::Message>>,
+    /// The number of compilations started so far. A progress token cannot be
+    /// used again once its progress has ended, so each compilation reports
+    /// its progress with a token of its own made from this number.
+    compilations: Arc<AtomicU64>,
+    /// The number of times dependencies started downloading, used for their
+    /// progress tokens in the same way.
+    downloads: Arc<AtomicU64>,
 }
 
-impl<'a> ConnectionProgressReporter<'a> {
+impl ConnectionProgressReporter {
     pub fn new(
-        connection: &'a lsp_server::Connection,
+        connection: &lsp_server::Connection,
         // We don't actually need these but we take them anyway to ensure that
         // this object is only created after the server has been initialised.
-        // If it was created before then the creation of the progress token
+        // If it was created before then the creation of the progress tokens
         // would fail.
         _initialise_params: &InitializeParams,
     ) -> Self {
-        create_token(DOWNLOADING_TOKEN, connection);
         Self {
-            connection: connection.into(),
+            sender: connection.sender.clone().into(),
+            compilations: Arc::new(AtomicU64::new(0)),
+            downloads: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    fn compiling_token(&self) -> String {
+        let compilation = self.compilations.load(Ordering::SeqCst);
+        format!("{COMPILING_TOKEN_PREFIX}-{compilation}")
+    }
+
+    fn downloading_token(&self) -> String {
+        let download = self.downloads.load(Ordering::SeqCst);
+        format!("{DOWNLOADING_TOKEN_PREFIX}-{download}")
+    }
+
     fn send_notification(&self, token: &str, work_done: WorkDoneProgress) {
         let params = ProgressParams {
             token: NumberOrString::String(token.to_string()),
@@ -44,34 +76,87 @@ impl<'a> ConnectionProgressReporter<'a> {
             method: "$/progress".into(),
             params: serde_json::to_value(params).expect("ProgressParams json"),
         };
-        self.connection
-            .sender
+        self.sender
             .send(lsp_server::Message::Notification(notification))
             .expect("send_work_done_notification send")
     }
 }
 
-impl ProgressReporter for ConnectionProgressReporter<'_> {
+impl ProgressReporter for ConnectionProgressReporter {
     fn compilation_started(&self) {
-        // Do nothing. This is only used for tests currently.
-        // In future we could make this emit a message to the client if compilation is taking a
-        // long time.
+        _ = self.compilations.fetch_add(1, Ordering::SeqCst);
+        let token = self.compiling_token();
+        create_token(&token, &self.sender);
+        self.send_notification(&token, begin_message("Compiling Gleam"));
     }
 
     fn compilation_finished(&self) {
-        // Do nothing. This is only used for tests currently.
+        self.send_notification(&self.compiling_token(), end_message());
+    }
+
+    fn compiling_package(&self, name: &str) {
+        let message = format!("Compiling package {name}");
+        self.send_notification(&self.compiling_token(), report_message(message));
+    }
+
+    fn compiling_module(&self, name: &str) {
+        let message = format!("Compiling module {name}");
+        self.send_notification(&self.compiling_token(), report_message(message));
     }
 
     fn dependency_downloading_started(&self) {
+        _ = self.downloads.fetch_add(1, Ordering::SeqCst);
+        let token = self.downloading_token();
+        create_token(&token, &self.sender);
         let title = "Downloading Gleam dependencies";
-        self.send_notification(DOWNLOADING_TOKEN, begin_message(title));
+        self.send_notification(&token, begin_message(title));
     }
 
     fn dependency_downloading_finished(&self) {
-        self.send_notification(DOWNLOADING_TOKEN, end_message());
+        self.send_notification(&self.downloading_token(), end_message());
+    }
+}
+
+/// Passes the progress the compiler makes through each package and module on
+/// to a progress reporter.
+pub struct CompilerProgress<Reporter> {
+    reporter: Reporter,
+}
+
+impl<Reporter> CompilerProgress<Reporter> {
+    pub fn new(reporter: Reporter) -> Self {
+        Self { reporter }
     }
 }
 
+impl<Reporter> std::fmt::Debug for CompilerProgress<Reporter> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompilerProgress").finish_non_exhaustive()
+    }
+}
+
+impl<Reporter: ProgressReporter> Telemetry for CompilerProgress<Reporter> {
+    fn compiling_package(&self, name: &str) {
+        self.reporter.compiling_package(name);
+    }
+
+    fn checking_package(&self, name: &str) {
+        self.reporter.compiling_package(name);
+    }
+
+    fn checking_module(&self, name: &str) {
+        self.reporter.compiling_module(name);
+    }
+
+    fn waiting_for_build_directory_lock(&self) {}
+    fn running(&self, _name: &str) {}
+    fn resolving_package_versions(&self) {}
+    fn downloading_package(&self, _name: &str) {}
+    fn packages_downloaded(&self, _start: Instant, _count: usize) {}
+    fn compiled_package(&self, _duration: Duration) {}
+    fn checked_package(&self, _duration: Duration) {}
+}
+
 fn end_message() -> WorkDoneProgress {
     WorkDoneProgress::End(WorkDoneProgressEnd { message: None })
 }
@@ -85,7 +170,15 @@ fn begin_message(title: &str) -> WorkDoneProgress {
     })
 }
 
-fn create_token(token: &str, connection: &lsp_server::Connection) {
+fn report_message(message: String) -> WorkDoneProgress {
+    WorkDoneProgress::Report(WorkDoneProgressReport {
+        cancellable: Some(false),
+        message: Some(message),
+        percentage: None,
+    })
+}
+
+fn create_token(token: &str, sender: &crossbeam_channel::Sender<lsp_server::Message>) {
     let params = WorkDoneProgressCreateParams {
         token: NumberOrString::String(token.into()),
     };
@@ -94,8 +187,7 @@ fn create_token(token: &str, connection: &lsp_server::Connection) {
         method: "window/workDoneProgress/create".into(),
         params: serde_json::to_value(params).expect("WorkDoneProgressCreateParams json"),
     };
-    connection
-        .sender
+    sender
         .send(lsp_server::Message::Request(request))
         .expect("WorkDoneProgressCreate");
 }
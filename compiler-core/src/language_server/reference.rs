@@ -41,6 +41,17 @@ pub enum Referenced {
     },
 }
 
+/// A value or type defined in a module, which can be referenced by any other
+/// module importing it. Projects depending on the package that defines it are
+/// asked for its references too.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleItem {
+    pub module: EcoString,
+    pub name: EcoString,
+    pub layer: ast::Layer,
+}
+
 pub fn reference_for_ast_node(
     found: Located<'_>,
     current_module: &EcoString,
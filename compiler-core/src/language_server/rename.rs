@@ -115,6 +115,27 @@ pub fn rename_module_entity(
         RenameTarget::Unqualified | RenameTarget::Qualified | RenameTarget::Definition => {}
     }
 
+    Some(rename_references(
+        modules,
+        sources,
+        renamed.module_name,
+        renamed.name,
+        &params.new_name,
+        renamed.layer,
+    ))
+}
+
+/// Renames a value or type, and all its references, in the given modules.
+/// The new name is expected to have already been validated.
+///
+pub fn rename_references(
+    modules: &im::HashMap<EcoString, ModuleInterface>,
+    sources: &HashMap<EcoString, ModuleSourceInformation>,
+    module_name: &EcoString,
+    name: &EcoString,
+    new_name: &str,
+    layer: ast::Layer,
+) -> WorkspaceEdit {
     let mut workspace_edit = WorkspaceEdit {
         changes: Some(HashMap::new()),
         document_changes: None,
@@ -122,12 +143,7 @@ pub fn rename_module_entity(
     };
 
     for module in modules.values() {
-        if &module.name == renamed.module_name
-            || module
-                .references
-                .imported_modules
-                .contains(renamed.module_name)
-        {
+        if &module.name == module_name || module.references.imported_modules.contains(module_name) {
             let Some(source_information) = sources.get(&module.name) else {
                 continue;
             };
@@ -136,15 +152,15 @@ pub fn rename_module_entity(
                 module,
                 source_information,
                 &mut workspace_edit,
-                renamed.module_name,
-                renamed.name,
-                params.new_name.clone(),
-                renamed.layer,
+                module_name,
+                name,
+                new_name.into(),
+                layer,
             );
         }
     }
 
-    Some(workspace_edit)
+    workspace_edit
 }
 
 fn rename_references_in_module(
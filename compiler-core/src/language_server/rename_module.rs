@@ -0,0 +1,428 @@
+use std::collections::HashMap;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{
+    DocumentChangeOperation, DocumentChanges, FileRename, RenameFile, ResourceOp, TextEdit, Url,
+    WorkspaceEdit,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{Definition, Import, SrcSpan},
+    build::{Module, Origin},
+    io::{FileSystemReader, is_native_file_extension},
+    line_numbers::LineNumbers,
+    paths::ProjectPaths,
+    reference::ReferenceKind,
+};
+
+use super::{
+    implementation::resolve_javascript_import,
+    move_definition::{
+        code_at, default_alias, is_valid_module_name, qualifier, references, text_document_edit,
+        text_edits,
+    },
+    src_span_to_lsp_range, url_from_path,
+};
+
+/// The command clients run to rename a module, moving its file.
+pub const RENAME_MODULE_COMMAND: &str = "gleam/renameModule";
+
+/// The arguments of the rename module command: the module at `uri` is
+/// renamed to `module`.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameModuleArguments {
+    pub uri: Url,
+    pub module: EcoString,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameModuleOutcome {
+    /// The module is renamed by applying the edit, which also renames its
+    /// file.
+    Renamed { edit: WorkspaceEdit },
+    /// The module can't be given the chosen name.
+    Refused { reason: String },
+}
+
+/// Renames `module` to `new_name`, moving its file and any FFI file sharing
+/// its name, and updating all the modules that import it.
+///
+pub fn rename_module(
+    modules: &HashMap<EcoString, Module>,
+    paths: &ProjectPaths,
+    io: &impl FileSystemReader,
+    module: &Module,
+    new_name: &EcoString,
+) -> Option<RenameModuleOutcome> {
+    let refuse = |reason: String| Some(RenameModuleOutcome::Refused { reason });
+
+    if new_name == &module.name {
+        return refuse(format!("The module is already called `{new_name}`"));
+    }
+    if !is_valid_module_name(new_name) {
+        return refuse(format!("`{new_name}` is not a valid module name"));
+    }
+    if modules.contains_key(new_name) {
+        return refuse(format!("A module called `{new_name}` already exists"));
+    }
+
+    let directory = match module.origin {
+        Origin::Src => paths.src_directory(),
+        Origin::Test => paths.test_directory(),
+    };
+    let new_path = directory.join(format!("{new_name}.gleam"));
+    let renames = Renames::new(vec![(module.input_path.clone(), new_path)], io);
+    let edit = renames.workspace_edit(modules, paths, io, true)?;
+    Some(RenameModuleOutcome::Renamed { edit })
+}
+
+/// The edit to apply before the client renames some files or directories, so
+/// that the modules importing the renamed modules keep compiling.
+///
+pub fn will_rename_files(
+    modules: &HashMap<EcoString, Module>,
+    paths: &ProjectPaths,
+    io: &impl FileSystemReader,
+    files: &[FileRename],
+) -> Option<WorkspaceEdit> {
+    let renames = files
+        .iter()
+        .filter_map(|file| {
+            let old = Url::parse(&file.old_uri).ok()?;
+            let new = Url::parse(&file.new_uri).ok()?;
+            Some((super::path(&old), super::path(&new)))
+        })
+        .collect_vec();
+
+    Renames::new(renames, io).workspace_edit(modules, paths, io, false)
+}
+
+/// The files and directories being renamed, from their old path to the new
+/// one.
+///
+#[derive(Debug)]
+struct Renames {
+    /// What the programmer asked to rename.
+    renamed: Vec<(Utf8PathBuf, Utf8PathBuf)>,
+    /// The FFI files that are renamed along with a Gleam module.
+    ffi: Vec<(Utf8PathBuf, Utf8PathBuf)>,
+}
+
+impl Renames {
+    fn new(renamed: Vec<(Utf8PathBuf, Utf8PathBuf)>, io: &impl FileSystemReader) -> Self {
+        let ffi = renamed
+            .iter()
+            .filter(|(old, _)| old.extension() == Some("gleam"))
+            .flat_map(|(old, new)| ffi_renames(old, new, io))
+            .filter(|(old, _)| !renamed.iter().any(|(renamed, _)| renamed == old))
+            .collect();
+        Self { renamed, ffi }
+    }
+
+    fn all(&self) -> impl Iterator<Item = &(Utf8PathBuf, Utf8PathBuf)> {
+        self.renamed.iter().chain(self.ffi.iter())
+    }
+
+    /// The path a file will have once everything is renamed.
+    ///
+    fn new_path(&self, path: &Utf8Path) -> Utf8PathBuf {
+        self.all()
+            .find_map(|(old, new)| {
+                let rest = path.strip_prefix(old).ok()?;
+                if rest.as_str().is_empty() {
+                    Some(new.clone())
+                } else {
+                    Some(new.join(rest))
+                }
+            })
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+
+    /// The renamed Erlang modules, from their old name to the new one.
+    ///
+    fn erlang_modules(&self) -> HashMap<&str, &str> {
+        self.all()
+            .filter(|(old, new)| old.extension() == Some("erl") && new.extension() == Some("erl"))
+            .filter_map(|(old, new)| Some((old.file_stem()?, new.file_stem()?)))
+            .filter(|(old, new)| old != new)
+            .collect()
+    }
+
+    /// The edit updating all the modules of the root package. When
+    /// `rename_modules` is true the edit also renames the files of the Gleam
+    /// modules, otherwise it's left to the client.
+    ///
+    fn workspace_edit(
+        &self,
+        modules: &HashMap<EcoString, Module>,
+        paths: &ProjectPaths,
+        io: &impl FileSystemReader,
+        rename_modules: bool,
+    ) -> Option<WorkspaceEdit> {
+        let renamed_modules: HashMap<&EcoString, EcoString> = modules
+            .values()
+            .filter_map(|module| {
+                let new_name = module_name(paths, &self.new_path(&module.input_path))?;
+                (new_name != module.name).then_some((&module.name, new_name))
+            })
+            .collect();
+        let erlang_modules = self.erlang_modules();
+
+        let mut operations = vec![];
+        for module in modules.values().sorted_by_key(|module| &module.name) {
+            let edits = self.module_edits(module, &renamed_modules, &erlang_modules);
+            if edits.is_empty() {
+                continue;
+            }
+            let uri = url_from_path(module.input_path.as_str())?;
+            operations.push(text_document_edit(uri, text_edits(module, edits)));
+        }
+
+        for (old, new) in self.all() {
+            let (Some(old_name), Some(new_name)) = (old.file_stem(), new.file_stem()) else {
+                continue;
+            };
+            let Some(edit) = erlang_module_attribute_edit(old, old_name, new_name, io) else {
+                continue;
+            };
+            let uri = url_from_path(old.as_str())?;
+            operations.push(text_document_edit(uri, vec![edit]));
+        }
+
+        let renamed_files = if rename_modules {
+            self.all().collect_vec()
+        } else {
+            self.ffi.iter().collect_vec()
+        };
+        for (old, new) in renamed_files {
+            operations.push(DocumentChangeOperation::Op(ResourceOp::Rename(
+                RenameFile {
+                    old_uri: url_from_path(old.as_str())?,
+                    new_uri: url_from_path(new.as_str())?,
+                    options: None,
+                    annotation_id: None,
+                },
+            )));
+        }
+
+        if operations.is_empty() {
+            return None;
+        }
+        Some(WorkspaceEdit {
+            changes: None,
+            document_changes: Some(DocumentChanges::Operations(operations)),
+            change_annotations: None,
+        })
+    }
+
+    /// The edits to the imports, qualified references and externals of a
+    /// module of the root package.
+    ///
+    fn module_edits(
+        &self,
+        module: &Module,
+        renamed_modules: &HashMap<&EcoString, EcoString>,
+        erlang_modules: &HashMap<&str, &str>,
+    ) -> Vec<(SrcSpan, String)> {
+        let mut edits = vec![];
+
+        for import in imports(module) {
+            let Some(new_name) = renamed_modules.get(&import.module) else {
+                continue;
+            };
+            if let Some(location) = imported_module_location(&module.code, import) {
+                edits.push((location, new_name.to_string()));
+            }
+
+            // If the import has an alias the module keeps being referred to
+            // with it.
+            let old_alias = default_alias(&import.module);
+            let new_alias = default_alias(new_name);
+            if import.as_name.is_some() || old_alias == new_alias {
+                continue;
+            }
+
+            // If another import is already using the new name, the renamed
+            // module gets aliased to its old name instead.
+            let alias_in_use = imports(module).any(|other| {
+                other.module != import.module && other.used_name().as_ref() == Some(&new_alias)
+            });
+            if alias_in_use {
+                let end = import.location.end;
+                edits.push((SrcSpan::new(end, end), format!(" as {old_alias}")));
+                continue;
+            }
+
+            for (_, referenced_module, _, reference) in references(module) {
+                if *referenced_module == import.module && reference.kind == ReferenceKind::Qualified
+                {
+                    edits.push((
+                        qualifier(&module.code, reference.location),
+                        format!("{new_alias}."),
+                    ));
+                }
+            }
+        }
+
+        let directory = module.input_path.parent().unwrap_or(Utf8Path::new(""));
+        let new_path = self.new_path(&module.input_path);
+        let new_directory = new_path.parent().unwrap_or(Utf8Path::new(""));
+
+        for function in module
+            .ast
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Function(function) => Some(function),
+                _ => None,
+            })
+        {
+            if let Some((erlang_module, _, location)) = &function.external_erlang
+                && let Some(new_name) = erlang_modules.get(erlang_module.as_str())
+                && let Some(location) = string_location(&module.code, *location, erlang_module)
+            {
+                edits.push((location, new_name.to_string()));
+            }
+
+            if let Some((javascript_module, _, location)) = &function.external_javascript
+                && let Some(file) = resolve_javascript_import(directory, javascript_module)
+            {
+                let new_import = relative_import(new_directory, &self.new_path(&file));
+                if new_import != javascript_module.as_str()
+                    && let Some(location) =
+                        string_location(&module.code, *location, javascript_module)
+                {
+                    edits.push((location, new_import));
+                }
+            }
+        }
+
+        edits
+    }
+}
+
+/// The FFI files in the same directory as a Gleam module that share its name,
+/// like `wibble.mjs` or `wibble_ffi.erl` for `wibble.gleam`.
+///
+fn ffi_renames(
+    old: &Utf8Path,
+    new: &Utf8Path,
+    io: &impl FileSystemReader,
+) -> Vec<(Utf8PathBuf, Utf8PathBuf)> {
+    let (Some(directory), Some(old_name), Some(new_directory), Some(new_name)) =
+        (old.parent(), old.file_stem(), new.parent(), new.file_stem())
+    else {
+        return vec![];
+    };
+    let Ok(entries) = io.read_dir(directory) else {
+        return vec![];
+    };
+
+    entries
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = entry.pathbuf;
+            let extension = path.extension().filter(|it| is_native_file_extension(it))?;
+            let suffix = path.file_stem()?.strip_prefix(old_name)?;
+            if !suffix.is_empty() && suffix != "_ffi" {
+                return None;
+            }
+            let new_path = new_directory.join(format!("{new_name}{suffix}.{extension}"));
+            Some((path, new_path))
+        })
+        .sorted()
+        .collect()
+}
+
+/// An Erlang module must be called like its file, so its `-module` attribute
+/// is updated when the file is renamed.
+///
+fn erlang_module_attribute_edit(
+    path: &Utf8Path,
+    old_name: &str,
+    new_name: &str,
+    io: &impl FileSystemReader,
+) -> Option<TextEdit> {
+    if path.extension() != Some("erl") || old_name == new_name {
+        return None;
+    }
+    let code = io.read(path).ok()?;
+    let attribute = format!("-module({old_name})");
+    let start = code.find(&attribute)? + "-module(".len();
+    let location = SrcSpan::new(start as u32, (start + old_name.len()) as u32);
+    Some(TextEdit {
+        range: src_span_to_lsp_range(location, &LineNumbers::new(&code)),
+        new_text: new_name.into(),
+    })
+}
+
+/// The name a Gleam file at `path` has, if it's in the `src` or `test`
+/// directory.
+///
+fn module_name(paths: &ProjectPaths, path: &Utf8Path) -> Option<EcoString> {
+    let relative = [paths.src_directory(), paths.test_directory()]
+        .iter()
+        .find_map(|directory| path.strip_prefix(directory).ok())?;
+    let name = relative
+        .components()
+        .map(|component| component.as_str())
+        .join("/");
+    name.strip_suffix(".gleam").map(EcoString::from)
+}
+
+fn imports(module: &Module) -> impl Iterator<Item = &Import<EcoString>> {
+    module
+        .ast
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            Definition::Import(import) => Some(import),
+            _ => None,
+        })
+}
+
+/// The location of the module name in an import, like `wibble/wobble` in
+/// `import wibble/wobble.{Wobble}`.
+///
+fn imported_module_location(code: &str, import: &Import<EcoString>) -> Option<SrcSpan> {
+    let after_keyword = import.location.start + "import".len() as u32;
+    let rest = code_at(code, SrcSpan::new(after_keyword, import.location.end));
+    let start = after_keyword + rest.find(import.module.as_str())? as u32;
+    Some(SrcSpan::new(start, start + import.module.len() as u32))
+}
+
+/// The location of the contents of the `"string"` in the given span.
+///
+fn string_location(code: &str, location: SrcSpan, string: &str) -> Option<SrcSpan> {
+    let index = code_at(code, location).find(&format!("\"{string}\""))?;
+    let start = location.start + index as u32 + 1;
+    Some(SrcSpan::new(start, start + string.len() as u32))
+}
+
+/// The import of `file` from a JavaScript module in `directory`.
+///
+fn relative_import(directory: &Utf8Path, file: &Utf8Path) -> String {
+    let directory = directory.components().collect_vec();
+    let file = file.components().collect_vec();
+    let common = directory
+        .iter()
+        .zip(file.iter())
+        .take_while(|(one, other)| one == other)
+        .count();
+    let path = file
+        .iter()
+        .skip(common)
+        .map(|component| component.as_str())
+        .join("/");
+
+    match directory.len().saturating_sub(common) {
+        0 => format!("./{path}"),
+        parents => format!("{}{path}", "../".repeat(parents)),
+    }
+}
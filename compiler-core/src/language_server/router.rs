@@ -15,6 +15,7 @@ use std::{
 };
 
 use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
 
 use super::feedback::FeedbackBookKeeper;
 
@@ -43,7 +44,7 @@ where
         + MakeLocker
         + Clone,
     // IO to be supplied from inside of gleam-core
-    Reporter: ProgressReporter + Clone,
+    Reporter: ProgressReporter + Clone + 'static,
 {
     pub fn new(progress_reporter: Reporter, io: FileSystemProxy<IO>) -> Self {
         Self {
@@ -92,6 +93,30 @@ where
         }))
     }
 
+    /// All the projects that currently have an engine running.
+    pub fn projects(&mut self) -> impl Iterator<Item = &mut Project<IO, Reporter>> {
+        self.engines.values_mut()
+    }
+
+    /// The roots of the loaded projects that depend on the project at `root`
+    /// through its path. These compile the project's modules from source, so
+    /// they are affected by any change made to them.
+    pub fn dependent_project_paths(&self, root: &Utf8Path) -> Vec<Utf8PathBuf> {
+        let Some(project) = self.engines.get(root) else {
+            return vec![];
+        };
+        let package = project.engine.package_name();
+
+        self.engines
+            .iter()
+            .filter(|(path, dependent)| {
+                path.as_path() != root && dependent.engine.has_path_dependency(package)
+            })
+            .map(|(path, _)| path.clone())
+            .sorted()
+            .collect()
+    }
+
     /// Has gleam.toml changed since the last time we saw this project?
     fn gleam_toml_changed(
         path: &Utf8PathBuf,
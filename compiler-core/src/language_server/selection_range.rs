@@ -0,0 +1,158 @@
+use itertools::Itertools;
+use lsp_types::{Position, SelectionRange};
+
+use crate::{
+    ast::{
+        Definition, SrcSpan, TypeAst, TypedClause, TypedCustomType, TypedDefinition, TypedExpr,
+        TypedFunction, TypedModuleConstant, TypedPattern, TypedStatement,
+        visit::{self, Visit},
+    },
+    build::Module,
+    line_numbers::LineNumbers,
+    type_::TypedCallArg,
+};
+
+use super::src_span_to_lsp_range;
+
+/// Returns the selection range for a position in a module: starting from the
+/// node under the cursor each parent grows the selection to the enclosing
+/// expression, statement, clause and definition, up to the whole module.
+///
+pub fn selection_range(
+    module: &Module,
+    line_numbers: &LineNumbers,
+    position: Position,
+) -> SelectionRange {
+    let byte_index = line_numbers.byte_index(position.line, position.character);
+
+    let mut collector = SelectionRangeCollector {
+        byte_index,
+        spans: vec![SrcSpan::new(0, module.code.len() as u32)],
+    };
+    if let Some(node) = module.find_node(byte_index) {
+        collector.push(node.location());
+    }
+    for definition in &module.ast.definitions {
+        collector.definition(definition);
+    }
+
+    // Starting from the outermost span, each one must be inside its parent
+    // to be part of the selection.
+    let spans = collector
+        .spans
+        .into_iter()
+        .sorted_by_key(|span| (std::cmp::Reverse(span.end - span.start), span.start))
+        .dedup();
+    let mut chain: Vec<SrcSpan> = vec![];
+    for span in spans {
+        let is_nested = chain
+            .last()
+            .is_none_or(|parent| parent.start <= span.start && span.end <= parent.end);
+        if is_nested {
+            chain.push(span);
+        }
+    }
+
+    let mut selection = None;
+    for span in chain {
+        selection = Some(SelectionRange {
+            range: src_span_to_lsp_range(span, line_numbers),
+            parent: selection.map(Box::new),
+        });
+    }
+    selection.unwrap_or_else(|| SelectionRange {
+        range: lsp_types::Range::new(position, position),
+        parent: None,
+    })
+}
+
+struct SelectionRangeCollector {
+    byte_index: u32,
+    spans: Vec<SrcSpan>,
+}
+
+impl SelectionRangeCollector {
+    fn push(&mut self, location: SrcSpan) {
+        if location.contains(self.byte_index) {
+            self.spans.push(location);
+        }
+    }
+
+    fn definition(&mut self, definition: &TypedDefinition) {
+        match definition {
+            Definition::Function(function) => self.visit_typed_function(function),
+            Definition::CustomType(custom_type) => self.visit_typed_custom_type(custom_type),
+            Definition::ModuleConstant(constant) => self.visit_typed_module_constant(constant),
+            Definition::TypeAlias(alias) => {
+                self.push(alias.location.merge(&alias.type_ast.location()));
+                self.visit_type_ast(&alias.type_ast);
+            }
+            Definition::Import(import) => self.push(import.location),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for SelectionRangeCollector {
+    fn visit_typed_function(&mut self, fun: &'ast TypedFunction) {
+        self.push(fun.full_location());
+        for argument in &fun.arguments {
+            self.push(argument.location);
+            if let Some(annotation) = &argument.annotation {
+                self.visit_type_ast(annotation);
+            }
+        }
+        if let Some(annotation) = &fun.return_annotation {
+            self.visit_type_ast(annotation);
+        }
+        visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        self.push(custom_type.full_location());
+        for constructor in &custom_type.constructors {
+            self.push(constructor.location);
+            for argument in &constructor.arguments {
+                self.push(argument.location);
+                self.visit_type_ast(&argument.ast);
+            }
+        }
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        self.push(constant.location.merge(&constant.value.location()));
+        self.push(constant.value.location());
+        if let Some(annotation) = &constant.annotation {
+            self.visit_type_ast(annotation);
+        }
+    }
+
+    fn visit_typed_statement(&mut self, statement: &'ast TypedStatement) {
+        self.push(statement.location());
+        visit::visit_typed_statement(self, statement);
+    }
+
+    fn visit_typed_expr(&mut self, expr: &'ast TypedExpr) {
+        self.push(expr.location());
+        visit::visit_typed_expr(self, expr);
+    }
+
+    fn visit_typed_clause(&mut self, clause: &'ast TypedClause) {
+        self.push(clause.location());
+        visit::visit_typed_clause(self, clause);
+    }
+
+    fn visit_typed_pattern(&mut self, pattern: &'ast TypedPattern) {
+        self.push(pattern.location());
+        visit::visit_typed_pattern(self, pattern);
+    }
+
+    fn visit_typed_call_arg(&mut self, arg: &'ast TypedCallArg) {
+        self.push(arg.location);
+        visit::visit_typed_call_arg(self, arg);
+    }
+
+    fn visit_type_ast(&mut self, node: &'ast TypeAst) {
+        self.push(node.location());
+        visit::visit_type_ast(self, node);
+    }
+}
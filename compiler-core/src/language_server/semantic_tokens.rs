@@ -0,0 +1,901 @@
+use std::sync::Arc;
+
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensEdit,
+    SemanticTokensLegend,
+};
+use vec1::Vec1;
+
+use crate::{
+    analyse::Inferred,
+    ast::{
+        self, ArgNames, AssignName, CallArg, Definition, Publicity, SrcSpan, TypeAst, TypedArg,
+        TypedAssignment, TypedConstant, TypedCustomType, TypedExpr, TypedFunction,
+        TypedModuleConstant, TypedPattern, TypedStatement, TypedUse,
+        visit::{self, Visit},
+    },
+    build::Module,
+    line_numbers::LineNumbers,
+    type_::{
+        self, Deprecation, ModuleInterface, ModuleValueConstructor, PatternConstructor, Type,
+        TypeConstructor, TypedCallArg, ValueConstructor, ValueConstructorVariant,
+        error::VariableOrigin,
+    },
+};
+
+use super::src_span_to_lsp_range;
+
+/// The token types the language server can produce, in the order they are
+/// advertised to the client in the legend.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenType {
+    Namespace,
+    Type,
+    TypeParameter,
+    Constructor,
+    Function,
+    Parameter,
+    Variable,
+    Property,
+}
+
+const TOKEN_TYPES: [SemanticTokenType; 8] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+];
+
+// The token modifiers are a bit set, each modifier is the index of the
+// corresponding modifier in the legend.
+const DECLARATION: u32 = 1 << 0;
+const READONLY: u32 = 1 << 1;
+const DEPRECATED: u32 = 1 << 2;
+const UNUSED: u32 = 1 << 3;
+const PUBLIC: u32 = 1 << 4;
+const INTERNAL: u32 = 1 << 5;
+const EXTERNAL: u32 = 1 << 6;
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: vec![
+            SemanticTokenModifier::DECLARATION,
+            SemanticTokenModifier::READONLY,
+            SemanticTokenModifier::DEPRECATED,
+            SemanticTokenModifier::new("unused"),
+            SemanticTokenModifier::new("public"),
+            SemanticTokenModifier::new("internal"),
+            SemanticTokenModifier::new("external"),
+        ],
+    }
+}
+
+/// Returns the semantic tokens for all the names appearing in a module, in the
+/// relative encoding expected by the client.
+///
+pub fn semantic_tokens(
+    module: &Module,
+    importable_modules: &im::HashMap<EcoString, ModuleInterface>,
+) -> Vec<SemanticToken> {
+    let mut collector = TokenCollector::new(module, importable_modules);
+    for definition in &module.ast.definitions {
+        collector.definition(definition);
+    }
+    encode(collector.tokens, &LineNumbers::new(&module.code))
+}
+
+/// Computes the edits needed to turn the previous tokens into the current
+/// ones. Since tokens usually change around the place being edited, a single
+/// edit replacing everything between the common prefix and the common suffix
+/// is good enough.
+///
+pub fn semantic_tokens_edits(
+    previous: &[SemanticToken],
+    current: &[SemanticToken],
+) -> Vec<SemanticTokensEdit> {
+    let prefix = previous
+        .iter()
+        .zip(current)
+        .take_while(|(previous, current)| previous == current)
+        .count();
+
+    if prefix == previous.len() && prefix == current.len() {
+        return vec![];
+    }
+
+    let suffix = previous
+        .iter()
+        .skip(prefix)
+        .rev()
+        .zip(current.iter().skip(prefix).rev())
+        .take_while(|(previous, current)| previous == current)
+        .count();
+
+    let deleted = previous.len() - prefix - suffix;
+    let inserted = current.len() - prefix - suffix;
+
+    // Edits refer to positions in the flattened array of integers sent to the
+    // client, where each token takes up five integers.
+    vec![SemanticTokensEdit {
+        start: (prefix * 5) as u32,
+        delete_count: (deleted * 5) as u32,
+        data: Some(
+            current
+                .iter()
+                .skip(prefix)
+                .take(inserted)
+                .copied()
+                .collect(),
+        ),
+    }]
+}
+
+#[derive(Debug)]
+struct Token {
+    location: SrcSpan,
+    type_: TokenType,
+    modifiers: u32,
+}
+
+fn encode(tokens: Vec<Token>, line_numbers: &LineNumbers) -> Vec<SemanticToken> {
+    let mut previous_line = 0;
+    let mut previous_start = 0;
+
+    tokens
+        .into_iter()
+        .sorted_by_key(|token| token.location.start)
+        // The same name could be reached more than once, for example when a
+        // pattern is desugared into a function argument, but a client
+        // doesn't accept overlapping tokens.
+        .dedup_by(|one, other| one.location.start == other.location.start)
+        .map(|token| {
+            let range = src_span_to_lsp_range(token.location, line_numbers);
+            let line = range.start.line;
+            let start = range.start.character;
+            let delta_start = if line == previous_line {
+                start - previous_start
+            } else {
+                start
+            };
+            let encoded = SemanticToken {
+                delta_line: line - previous_line,
+                delta_start,
+                length: range.end.character - start,
+                token_type: token.type_ as u32,
+                token_modifiers_bitset: token.modifiers,
+            };
+            previous_line = line;
+            previous_start = start;
+            encoded
+        })
+        .collect()
+}
+
+struct TokenCollector<'a> {
+    module: &'a Module,
+    importable_modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    /// The locations of the unused definitions and imports reported by the
+    /// type checker.
+    unused_definitions: Vec<SrcSpan>,
+    /// The locations of the unused variables reported by the type checker.
+    unused_variables: Vec<SrcSpan>,
+    /// The locations where function parameters are defined, used to tell them
+    /// apart from other local variables.
+    parameters: Vec<SrcSpan>,
+    tokens: Vec<Token>,
+}
+
+impl<'a> TokenCollector<'a> {
+    fn new(
+        module: &'a Module,
+        importable_modules: &'a im::HashMap<EcoString, ModuleInterface>,
+    ) -> Self {
+        let mut unused_definitions = vec![];
+        let mut unused_variables = vec![];
+        for warning in &module.ast.type_info.warnings {
+            match warning {
+                type_::Warning::UnusedVariable { location, .. } => {
+                    unused_variables.push(*location);
+                }
+                type_::Warning::UnusedType { location, .. }
+                | type_::Warning::UnusedConstructor { location, .. }
+                | type_::Warning::UnusedImportedValue { location, .. }
+                | type_::Warning::UnusedImportedModule { location, .. }
+                | type_::Warning::UnusedImportedModuleAlias { location, .. }
+                | type_::Warning::UnusedPrivateModuleConstant { location, .. }
+                | type_::Warning::UnusedPrivateFunction { location, .. } => {
+                    unused_definitions.push(*location);
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            module,
+            importable_modules,
+            unused_definitions,
+            unused_variables,
+            parameters: vec![],
+            tokens: vec![],
+        }
+    }
+
+    /// Adds a token for the given name. Nodes generated by the compiler don't
+    /// have the name in the source code at their location, so those are
+    /// ignored.
+    ///
+    fn push(&mut self, location: SrcSpan, name: &str, type_: TokenType, modifiers: u32) {
+        let source = self
+            .module
+            .code
+            .get(location.start as usize..location.end as usize);
+        if source != Some(name) {
+            return;
+        }
+
+        self.tokens.push(Token {
+            location,
+            type_,
+            modifiers,
+        });
+    }
+
+    /// Adds a token for a name that ends where the given location ends.
+    ///
+    fn push_ending_at(&mut self, location: SrcSpan, name: &str, type_: TokenType, modifiers: u32) {
+        let start = location.end.saturating_sub(name.len() as u32);
+        self.push(SrcSpan::new(start, location.end), name, type_, modifiers);
+    }
+
+    fn unused_definition(&self, location: SrcSpan) -> u32 {
+        let unused = self
+            .unused_definitions
+            .iter()
+            .any(|unused| unused.contains(location.start) && location.end <= unused.end);
+        if unused { UNUSED } else { 0 }
+    }
+
+    fn unused_variable(&self, location: SrcSpan) -> u32 {
+        if self.unused_variables.contains(&location) {
+            UNUSED
+        } else {
+            0
+        }
+    }
+
+    fn definition(&mut self, definition: &'a ast::TypedDefinition) {
+        match definition {
+            Definition::Function(function) => self.visit_typed_function(function),
+            Definition::ModuleConstant(constant) => self.visit_typed_module_constant(constant),
+            Definition::CustomType(custom_type) => self.visit_typed_custom_type(custom_type),
+            Definition::TypeAlias(alias) => {
+                let modifiers = DECLARATION
+                    | publicity_modifiers(&alias.publicity)
+                    | deprecation_modifiers(&alias.deprecation)
+                    | self.unused_definition(alias.name_location);
+                self.push(
+                    alias.name_location,
+                    &alias.alias,
+                    TokenType::Type,
+                    modifiers,
+                );
+                for (location, name) in &alias.parameters {
+                    self.push(*location, name, TokenType::TypeParameter, DECLARATION);
+                }
+                self.visit_type_ast(&alias.type_ast);
+            }
+            Definition::Import(import) => self.import(import),
+        }
+    }
+
+    fn import(&mut self, import: &'a ast::Import<EcoString>) {
+        let module_interface = self.importable_modules.get(&import.module);
+
+        // The import's location starts with the `import` keyword so we have to
+        // look for the module name in the source code following it. The
+        // search can't start at the keyword itself, or modules with a name
+        // like `im` or `port` would be found inside of it.
+        let name_start = import.location.start + "import".len() as u32;
+        let module_location = self
+            .module
+            .code
+            .get(name_start as usize..import.location.end as usize)
+            .and_then(|source| source.find(import.module.as_str()))
+            .map(|offset| {
+                let start = name_start + offset as u32;
+                SrcSpan::new(start, start + import.module.len() as u32)
+            });
+        if let Some(location) = module_location {
+            let modifiers = self.unused_definition(location);
+            self.push(location, &import.module, TokenType::Namespace, modifiers);
+        }
+
+        if let Some((AssignName::Variable(alias), location)) = &import.as_name {
+            let modifiers = self.unused_definition(*location);
+            self.push_ending_at(*location, alias, TokenType::Namespace, modifiers);
+        }
+
+        for value in &import.unqualified_values {
+            let Some(constructor) =
+                module_interface.and_then(|module| module.values.get(&value.name))
+            else {
+                continue;
+            };
+            let type_ = self.value_token_type(constructor);
+            let modifiers = value_modifiers(constructor) | self.unused_definition(value.location);
+            self.push(value.imported_name_location, &value.name, type_, modifiers);
+            if let Some(alias) = &value.as_name {
+                self.push_ending_at(value.location, alias, type_, modifiers);
+            }
+        }
+
+        for imported_type in &import.unqualified_types {
+            let modifiers = module_interface
+                .and_then(|module| module.types.get(&imported_type.name))
+                .map(type_modifiers)
+                .unwrap_or_default()
+                | self.unused_definition(imported_type.location);
+            self.push(
+                imported_type.imported_name_location,
+                &imported_type.name,
+                TokenType::Type,
+                modifiers,
+            );
+            if let Some(alias) = &imported_type.as_name {
+                self.push_ending_at(imported_type.location, alias, TokenType::Type, modifiers);
+            }
+        }
+    }
+
+    fn arguments(&mut self, arguments: &'a [TypedArg]) {
+        for argument in arguments {
+            match &argument.names {
+                ArgNames::Discard { name, location } => {
+                    self.push(*location, name, TokenType::Parameter, DECLARATION);
+                }
+                ArgNames::LabelledDiscard {
+                    label,
+                    label_location,
+                    name,
+                    name_location,
+                } => {
+                    self.push(*label_location, label, TokenType::Property, DECLARATION);
+                    self.push(*name_location, name, TokenType::Parameter, DECLARATION);
+                }
+                ArgNames::Named { name, location } => {
+                    self.parameters.push(*location);
+                    let modifiers = DECLARATION | self.unused_variable(*location);
+                    self.push(*location, name, TokenType::Parameter, modifiers);
+                }
+                ArgNames::NamedLabelled {
+                    label,
+                    label_location,
+                    name,
+                    name_location,
+                } => {
+                    self.parameters.push(*name_location);
+                    let modifiers = DECLARATION | self.unused_variable(*name_location);
+                    self.push(*label_location, label, TokenType::Property, DECLARATION);
+                    self.push(*name_location, name, TokenType::Parameter, modifiers);
+                }
+            }
+
+            if let Some(annotation) = &argument.annotation {
+                self.visit_type_ast(annotation);
+            }
+        }
+    }
+
+    fn constant(&mut self, constant: &'a TypedConstant) {
+        match constant {
+            ast::Constant::Int { .. }
+            | ast::Constant::Float { .. }
+            | ast::Constant::String { .. }
+            | ast::Constant::BitArray { .. }
+            | ast::Constant::Invalid { .. } => {}
+
+            ast::Constant::Tuple { elements, .. } | ast::Constant::List { elements, .. } => {
+                for element in elements {
+                    self.constant(element);
+                }
+            }
+
+            ast::Constant::StringConcatenation { left, right, .. } => {
+                self.constant(left);
+                self.constant(right);
+            }
+
+            ast::Constant::Record {
+                location,
+                module,
+                name,
+                args,
+                ..
+            } => {
+                let name_start = self.qualifier(module).unwrap_or(location.start);
+                let modifiers = self
+                    .value_in_scope(module, name)
+                    .map(value_modifiers)
+                    .unwrap_or_default();
+                let name_location = SrcSpan::new(name_start, name_start + name.len() as u32);
+                self.push(name_location, name, TokenType::Constructor, modifiers);
+
+                for argument in args {
+                    self.label(argument);
+                    self.constant(&argument.value);
+                }
+            }
+
+            ast::Constant::Var {
+                location,
+                module,
+                name,
+                constructor,
+                ..
+            } => {
+                let name_start = self.qualifier(module).unwrap_or(location.start);
+                if let Some(constructor) = constructor {
+                    let type_ = self.value_token_type(constructor);
+                    let name_location = SrcSpan::new(name_start, name_start + name.len() as u32);
+                    self.push(name_location, name, type_, value_modifiers(constructor));
+                }
+            }
+        }
+    }
+
+    /// Adds a token for the module qualifying a name, returning the position
+    /// where the qualified name starts.
+    ///
+    fn qualifier(&mut self, module: &Option<(EcoString, SrcSpan)>) -> Option<u32> {
+        let (alias, location) = module.as_ref()?;
+        let alias_location = SrcSpan::new(location.start, location.start + alias.len() as u32);
+        self.push(alias_location, alias, TokenType::Namespace, 0);
+        // The name comes right after the `.`
+        Some(alias_location.end + 1)
+    }
+
+    fn label<A>(&mut self, argument: &CallArg<A>) {
+        if argument.implicit.is_some() {
+            return;
+        }
+        if let Some(label) = &argument.label {
+            let location = SrcSpan::new(
+                argument.location.start,
+                argument.location.start + label.len() as u32,
+            );
+            self.push(location, label, TokenType::Property, 0);
+        }
+    }
+
+    fn value_token_type(&self, constructor: &ValueConstructor) -> TokenType {
+        match &constructor.variant {
+            ValueConstructorVariant::LocalVariable { location, .. }
+                if self.parameters.contains(location) =>
+            {
+                TokenType::Parameter
+            }
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::ModuleConstant { .. }
+            | ValueConstructorVariant::LocalConstant { .. } => TokenType::Variable,
+            ValueConstructorVariant::ModuleFn { .. } => TokenType::Function,
+            ValueConstructorVariant::Record { .. } => TokenType::Constructor,
+        }
+    }
+
+    /// Finds the value with the given name, optionally qualified with the
+    /// alias of an imported module.
+    ///
+    fn value_in_scope(
+        &self,
+        module_alias: &Option<(EcoString, SrcSpan)>,
+        name: &EcoString,
+    ) -> Option<&'a ValueConstructor> {
+        match module_alias {
+            Some((alias, _)) => self
+                .imported_module(alias)
+                .and_then(|module| module.values.get(name)),
+            None => self.module.ast.type_info.values.get(name),
+        }
+    }
+
+    /// Finds the type with the given name, optionally qualified with the alias
+    /// of an imported module.
+    ///
+    fn type_in_scope(
+        &self,
+        module_alias: &Option<(EcoString, SrcSpan)>,
+        name: &EcoString,
+    ) -> Option<&'a TypeConstructor> {
+        match module_alias {
+            Some((alias, _)) => self
+                .imported_module(alias)
+                .and_then(|module| module.types.get(name)),
+            None => self
+                .module
+                .ast
+                .type_info
+                .types
+                .get(name)
+                .or_else(|| self.unqualified_type(name)),
+        }
+    }
+
+    fn unqualified_type(&self, name: &EcoString) -> Option<&'a TypeConstructor> {
+        self.module.ast.definitions.iter().find_map(|definition| {
+            let Definition::Import(import) = definition else {
+                return None;
+            };
+            let imported = import
+                .unqualified_types
+                .iter()
+                .find(|imported| imported.used_name() == name)?;
+            self.importable_modules
+                .get(&import.module)?
+                .types
+                .get(&imported.name)
+        })
+    }
+
+    fn imported_module(&self, alias: &EcoString) -> Option<&'a ModuleInterface> {
+        self.module
+            .ast
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Import(import) if import.used_name().as_ref() == Some(alias) => {
+                    self.importable_modules.get(&import.module)
+                }
+                _ => None,
+            })
+    }
+
+    fn module_value(&self, module: &EcoString, name: &EcoString) -> Option<&'a ValueConstructor> {
+        if *module == self.module.name {
+            self.module.ast.type_info.values.get(name)
+        } else {
+            self.importable_modules
+                .get(module)
+                .and_then(|module| module.values.get(name))
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for TokenCollector<'ast> {
+    fn visit_typed_function(&mut self, fun: &'ast TypedFunction) {
+        if let Some((location, name)) = &fun.name {
+            let external = fun.external_erlang.is_some() || fun.external_javascript.is_some();
+            let modifiers = DECLARATION
+                | publicity_modifiers(&fun.publicity)
+                | deprecation_modifiers(&fun.deprecation)
+                | if external { EXTERNAL } else { 0 }
+                | self.unused_definition(*location);
+            self.push(*location, name, TokenType::Function, modifiers);
+        }
+
+        self.arguments(&fun.arguments);
+        if let Some(annotation) = &fun.return_annotation {
+            self.visit_type_ast(annotation);
+        }
+        visit::visit_typed_function(self, fun);
+    }
+
+    fn visit_typed_module_constant(&mut self, constant: &'ast TypedModuleConstant) {
+        let modifiers = DECLARATION
+            | READONLY
+            | publicity_modifiers(&constant.publicity)
+            | deprecation_modifiers(&constant.deprecation)
+            | self.unused_definition(constant.name_location);
+        self.push(
+            constant.name_location,
+            &constant.name,
+            TokenType::Variable,
+            modifiers,
+        );
+        if let Some(annotation) = &constant.annotation {
+            self.visit_type_ast(annotation);
+        }
+        self.constant(&constant.value);
+    }
+
+    fn visit_typed_custom_type(&mut self, custom_type: &'ast TypedCustomType) {
+        let publicity = publicity_modifiers(&custom_type.publicity);
+        let modifiers = DECLARATION
+            | publicity
+            | deprecation_modifiers(&custom_type.deprecation)
+            | self.unused_definition(custom_type.name_location);
+        self.push(
+            custom_type.name_location,
+            &custom_type.name,
+            TokenType::Type,
+            modifiers,
+        );
+
+        for (location, name) in &custom_type.parameters {
+            self.push(*location, name, TokenType::TypeParameter, DECLARATION);
+        }
+
+        for constructor in &custom_type.constructors {
+            let modifiers = DECLARATION
+                | publicity
+                | deprecation_modifiers(&constructor.deprecation)
+                | self.unused_definition(constructor.name_location);
+            self.push(
+                constructor.name_location,
+                &constructor.name,
+                TokenType::Constructor,
+                modifiers,
+            );
+
+            for argument in &constructor.arguments {
+                if let Some((location, label)) = &argument.label {
+                    self.push(*location, label, TokenType::Property, DECLARATION);
+                }
+                self.visit_type_ast(&argument.ast);
+            }
+        }
+    }
+
+    fn visit_typed_expr_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        constructor: &'ast ValueConstructor,
+        name: &'ast EcoString,
+    ) {
+        let type_ = self.value_token_type(constructor);
+        self.push(*location, name, type_, value_modifiers(constructor));
+    }
+
+    fn visit_typed_expr_fn(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        kind: &'ast ast::FunctionLiteralKind,
+        args: &'ast [TypedArg],
+        body: &'ast Vec1<TypedStatement>,
+        return_annotation: &'ast Option<TypeAst>,
+    ) {
+        // The arguments of a `use` callback are the patterns on the left hand
+        // side of the arrow, those are visited as patterns instead.
+        if !matches!(kind, ast::FunctionLiteralKind::Use { .. }) {
+            self.arguments(args);
+        }
+        if let Some(annotation) = return_annotation {
+            self.visit_type_ast(annotation);
+        }
+        visit::visit_typed_expr_fn(self, location, type_, kind, args, body, return_annotation);
+    }
+
+    fn visit_typed_expr_record_access(
+        &mut self,
+        location: &'ast SrcSpan,
+        type_: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        index: &'ast u64,
+        record: &'ast TypedExpr,
+    ) {
+        visit::visit_typed_expr_record_access(self, location, type_, label, index, record);
+        self.push_ending_at(*location, label, TokenType::Property, 0);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_expr_module_select(
+        &mut self,
+        location: &'ast SrcSpan,
+        field_start: &'ast u32,
+        _type_: &'ast Arc<Type>,
+        label: &'ast EcoString,
+        module_name: &'ast EcoString,
+        module_alias: &'ast EcoString,
+        constructor: &'ast ModuleValueConstructor,
+    ) {
+        let alias_location =
+            SrcSpan::new(location.start, location.start + module_alias.len() as u32);
+        self.push(alias_location, module_alias, TokenType::Namespace, 0);
+
+        let (type_, modifiers) = match constructor {
+            ModuleValueConstructor::Record { .. } => (TokenType::Constructor, 0),
+            ModuleValueConstructor::Fn {
+                external_erlang,
+                external_javascript,
+                ..
+            } => {
+                let external = external_erlang.is_some() || external_javascript.is_some();
+                (TokenType::Function, if external { EXTERNAL } else { 0 })
+            }
+            ModuleValueConstructor::Constant { .. } => (TokenType::Variable, READONLY),
+        };
+        let modifiers = self
+            .module_value(module_name, label)
+            .map(|value| {
+                publicity_modifiers(&value.publicity) | deprecation_modifiers(&value.deprecation)
+            })
+            .unwrap_or_default()
+            | modifiers;
+        self.push(
+            SrcSpan::new(*field_start, location.end),
+            label,
+            type_,
+            modifiers,
+        );
+    }
+
+    fn visit_typed_assignment(&mut self, assignment: &'ast TypedAssignment) {
+        visit::visit_typed_assignment(self, assignment);
+        if let Some(annotation) = &assignment.annotation {
+            self.visit_type_ast(annotation);
+        }
+    }
+
+    fn visit_typed_use(&mut self, use_: &'ast TypedUse) {
+        for assignment in &use_.assignments {
+            self.visit_typed_pattern(&assignment.pattern);
+            if let Some(annotation) = &assignment.annotation {
+                self.visit_type_ast(annotation);
+            }
+        }
+        visit::visit_typed_use(self, use_);
+    }
+
+    fn visit_typed_call_arg(&mut self, arg: &'ast TypedCallArg) {
+        self.label(arg);
+        visit::visit_typed_call_arg(self, arg);
+    }
+
+    fn visit_typed_clause_guard_var(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        _type_: &'ast Arc<Type>,
+        definition_location: &'ast SrcSpan,
+    ) {
+        let type_ = if self.parameters.contains(definition_location) {
+            TokenType::Parameter
+        } else {
+            TokenType::Variable
+        };
+        self.push(*location, name, type_, 0);
+    }
+
+    fn visit_typed_pattern_variable(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        _type_: &'ast Arc<Type>,
+        _origin: &'ast VariableOrigin,
+    ) {
+        let modifiers = DECLARATION | self.unused_variable(*location);
+        self.push(*location, name, TokenType::Variable, modifiers);
+    }
+
+    fn visit_typed_pattern_var_usage(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        constructor: &'ast Option<ValueConstructor>,
+        _type_: &'ast Arc<Type>,
+    ) {
+        let type_ = match constructor {
+            Some(constructor) => self.value_token_type(constructor),
+            None => TokenType::Variable,
+        };
+        self.push(*location, name, type_, 0);
+    }
+
+    fn visit_typed_pattern_assign(
+        &mut self,
+        location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        pattern: &'ast TypedPattern,
+    ) {
+        let modifiers = DECLARATION | self.unused_variable(*location);
+        self.push(*location, name, TokenType::Variable, modifiers);
+        visit::visit_typed_pattern_assign(self, location, name, pattern);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_typed_pattern_constructor(
+        &mut self,
+        location: &'ast SrcSpan,
+        name_location: &'ast SrcSpan,
+        name: &'ast EcoString,
+        arguments: &'ast Vec<CallArg<TypedPattern>>,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        constructor: &'ast Inferred<PatternConstructor>,
+        spread: &'ast Option<SrcSpan>,
+        type_: &'ast Arc<Type>,
+    ) {
+        let _ = self.qualifier(module);
+        let modifiers = match constructor {
+            Inferred::Known(constructor) => self
+                .module_value(&constructor.module, &constructor.name)
+                .map(value_modifiers)
+                .unwrap_or_default(),
+            Inferred::Unknown => 0,
+        };
+        self.push(*name_location, name, TokenType::Constructor, modifiers);
+        visit::visit_typed_pattern_constructor(
+            self,
+            location,
+            name_location,
+            name,
+            arguments,
+            module,
+            constructor,
+            spread,
+            type_,
+        );
+    }
+
+    fn visit_typed_pattern_call_arg(&mut self, arg: &'ast CallArg<TypedPattern>) {
+        self.label(arg);
+        visit::visit_typed_pattern_call_arg(self, arg);
+    }
+
+    fn visit_type_ast_constructor(
+        &mut self,
+        location: &'ast SrcSpan,
+        name_location: &'ast SrcSpan,
+        module: &'ast Option<(EcoString, SrcSpan)>,
+        name: &'ast EcoString,
+        arguments: &'ast Vec<TypeAst>,
+    ) {
+        let _ = self.qualifier(module);
+        let modifiers = self
+            .type_in_scope(module, name)
+            .map(type_modifiers)
+            .unwrap_or_default();
+        self.push(*name_location, name, TokenType::Type, modifiers);
+        visit::visit_type_ast_constructor(self, location, name_location, module, name, arguments);
+    }
+
+    fn visit_type_ast_var(&mut self, location: &'ast SrcSpan, name: &'ast EcoString) {
+        self.push(*location, name, TokenType::TypeParameter, 0);
+    }
+}
+
+fn publicity_modifiers(publicity: &Publicity) -> u32 {
+    match publicity {
+        Publicity::Public => PUBLIC,
+        Publicity::Internal { .. } => INTERNAL,
+        Publicity::Private => 0,
+    }
+}
+
+fn deprecation_modifiers(deprecation: &Deprecation) -> u32 {
+    if deprecation.is_deprecated() {
+        DEPRECATED
+    } else {
+        0
+    }
+}
+
+fn value_modifiers(constructor: &ValueConstructor) -> u32 {
+    let variant_modifiers = match &constructor.variant {
+        ValueConstructorVariant::ModuleFn {
+            external_erlang,
+            external_javascript,
+            ..
+        } if external_erlang.is_some() || external_javascript.is_some() => EXTERNAL,
+        ValueConstructorVariant::ModuleConstant { .. }
+        | ValueConstructorVariant::LocalConstant { .. } => READONLY,
+        ValueConstructorVariant::ModuleFn { .. }
+        | ValueConstructorVariant::LocalVariable { .. }
+        | ValueConstructorVariant::Record { .. } => 0,
+    };
+
+    variant_modifiers
+        | publicity_modifiers(&constructor.publicity)
+        | deprecation_modifiers(&constructor.deprecation)
+}
+
+fn type_modifiers(constructor: &TypeConstructor) -> u32 {
+    publicity_modifiers(&constructor.publicity) | deprecation_modifiers(&constructor.deprecation)
+}
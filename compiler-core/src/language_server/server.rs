@@ -4,20 +4,31 @@ use super::{
 };
 use crate::{
     Result,
-    diagnostic::{Diagnostic, Level},
+    ast::SrcSpan,
+    diagnostic::{Diagnostic, Level, Tag},
     io::{BeamCompiler, CommandExecutor, FileSystemReader, FileSystemWriter},
     language_server::{
         DownloadDependencies, MakeLocker,
+        change_signature::{
+            CHANGE_SIGNATURE_COMMAND, ChangeSignatureArguments, ChangeSignatureOutcome,
+        },
+        configuration::Configuration,
         engine::{self, LanguageServerEngine},
         feedback::{Feedback, FeedbackBookKeeper},
         files::FileSystemProxy,
+        generated_code::GeneratedCodeParams,
+        lsp_range_to_src_span,
+        move_definition::{MOVE_DEFINITION_COMMAND, MoveDefinitionArguments, MoveOutcome},
+        rename_module::{RENAME_MODULE_COMMAND, RenameModuleArguments, RenameModuleOutcome},
         router::Router,
-        src_span_to_lsp_range,
+        semantic_tokens, src_span_to_lsp_range, url_from_path,
+        workspace_symbols::sort_workspace_symbols,
     },
     line_numbers::LineNumbers,
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use debug_ignore::DebugIgnore;
+use ecow::EcoString;
 use itertools::Itertools;
 use lsp_types::{
     self as lsp, HoverProviderCapability, InitializeParams, Position, PublishDiagnosticsParams,
@@ -39,11 +50,17 @@ use std::collections::{HashMap, HashSet};
 #[derive(Debug)]
 pub struct LanguageServer<'a, IO> {
     initialise_params: InitializeParams,
+    configuration: Configuration,
     connection: DebugIgnore<&'a lsp_server::Connection>,
     outside_of_project_feedback: FeedbackBookKeeper,
-    router: Router<IO, ConnectionProgressReporter<'a>>,
+    router: Router<IO, ConnectionProgressReporter>,
     changed_projects: HashSet<Utf8PathBuf>,
     io: FileSystemProxy<IO>,
+    /// The latest diagnostics of each file, sent to clients that ask for them
+    /// rather than having them published.
+    diagnostics: HashMap<Utf8PathBuf, Vec<lsp::Diagnostic>>,
+    diagnostic_refreshes: u64,
+    applied_edits: u64,
 }
 
 impl<'a, IO> LanguageServer<'a, IO>
@@ -57,20 +74,58 @@ where
         + Clone,
 {
     pub fn new(connection: &'a lsp_server::Connection, io: IO) -> Result<Self> {
-        let initialise_params = initialisation_handshake(connection);
+        let initialise_params = initialisation_handshake(connection)?;
+        let configuration = Configuration::from_initialisation_options(
+            initialise_params.initialization_options.as_ref(),
+        );
         let reporter = ConnectionProgressReporter::new(connection, &initialise_params);
         let io = FileSystemProxy::new(io);
         let router = Router::new(reporter, io.clone());
         Ok(Self {
             connection: connection.into(),
             initialise_params,
+            configuration,
             changed_projects: HashSet::new(),
             outside_of_project_feedback: FeedbackBookKeeper::default(),
             router,
             io,
+            diagnostics: HashMap::new(),
+            diagnostic_refreshes: 0,
+            applied_edits: 0,
         })
     }
 
+    /// Starts serving a new client over the same connection, once the previous
+    /// client has shut the server down. The projects compiled for the previous
+    /// client are kept, so they don't need compiling again from scratch.
+    ///
+    /// The files the previous client had open are closed, and the diagnostics
+    /// found so far are published again for the new client.
+    ///
+    /// If the new client goes before initialising the server an error is
+    /// returned, and the server is left ready to be reconnected again.
+    ///
+    pub fn reconnect(&mut self) -> Result<()> {
+        self.initialise_params = initialisation_handshake(*self.connection)?;
+        self.configuration = Configuration::from_initialisation_options(
+            self.initialise_params.initialization_options.as_ref(),
+        );
+
+        for path in self.io.cached_files() {
+            let feedback = self.source_file_closed(path);
+            self.publish_feedback(feedback);
+        }
+
+        if !self.client_pulls_diagnostics() {
+            for (path, diagnostics) in self.diagnostics.iter().sorted_by_key(|(path, _)| *path) {
+                if !diagnostics.is_empty() {
+                    self.send_diagnostics(path.clone(), diagnostics.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn run(&mut self) -> Result<()> {
         self.start_watching_gleam_toml();
         let mut buffer = MessageBuffer::new();
@@ -81,7 +136,16 @@ where
                 Next::MorePlease => (),
                 Next::Handle(messages) => {
                     for message in messages {
-                        self.handle_message(message);
+                        match message {
+                            // Requests wait for any compilation before them,
+                            // in which time the client may have lost interest.
+                            Message::Request(id, _)
+                                if buffer.is_cancelled(*self.connection, &id) =>
+                            {
+                                self.request_cancelled(id)
+                            }
+                            message => self.handle_message(message),
+                        }
                     }
                 }
             }
@@ -100,6 +164,8 @@ where
     fn handle_request(&mut self, id: lsp_server::RequestId, request: Request) {
         let (payload, feedback) = match request {
             Request::Format(param) => self.format(param),
+            Request::RangeFormat(param) => self.range_format(param),
+            Request::OnTypeFormat(param) => self.on_type_format(param),
             Request::Hover(param) => self.hover(param),
             Request::GoToDefinition(param) => self.goto_definition(param),
             Request::Completion(param) => self.completion(param),
@@ -109,7 +175,24 @@ where
             Request::PrepareRename(param) => self.prepare_rename(param),
             Request::Rename(param) => self.rename(param),
             Request::GoToTypeDefinition(param) => self.goto_type_definition(param),
+            Request::GoToImplementation(param) => self.goto_implementation(param),
             Request::FindReferences(param) => self.find_references(param),
+            Request::WorkspaceSymbol(param) => self.workspace_symbol(param),
+            Request::InlayHints(param) => self.inlay_hints(param),
+            Request::SemanticTokensFull(param) => self.semantic_tokens_full(param),
+            Request::SemanticTokensFullDelta(param) => self.semantic_tokens_full_delta(param),
+            Request::FoldingRange(param) => self.folding_range(param),
+            Request::SelectionRange(param) => self.selection_range(param),
+            Request::DocumentHighlight(param) => self.document_highlight(param),
+            Request::CodeLens(param) => self.code_lens(param),
+            Request::PrepareCallHierarchy(param) => self.prepare_call_hierarchy(param),
+            Request::IncomingCalls(param) => self.incoming_calls(*param),
+            Request::OutgoingCalls(param) => self.outgoing_calls(*param),
+            Request::DocumentDiagnostic(param) => self.document_diagnostic(param),
+            Request::WorkspaceDiagnostic(param) => self.workspace_diagnostic(param),
+            Request::ExecuteCommand(param) => self.execute_command(param),
+            Request::WillRenameFiles(param) => self.will_rename_files(param),
+            Request::GeneratedCode(param) => self.generated_code(param),
         };
 
         self.publish_feedback(feedback);
@@ -125,10 +208,23 @@ where
             .expect("channel send LSP response")
     }
 
+    fn request_cancelled(&mut self, id: lsp_server::RequestId) {
+        let response = lsp_server::Response::new_err(
+            id,
+            lsp_server::ErrorCode::RequestCanceled as i32,
+            "Request cancelled".into(),
+        );
+        self.connection
+            .sender
+            .send(lsp_server::Message::Response(response))
+            .expect("channel send LSP response")
+    }
+
     fn handle_notification(&mut self, notification: Notification) {
         let feedback = match notification {
             Notification::CompilePlease => self.compile_please(),
             Notification::SourceFileMatchesDisc { path } => self.discard_in_memory_cache(path),
+            Notification::SourceFileClosed { path } => self.source_file_closed(path),
             Notification::SourceFileChangedInMemory { path, text } => {
                 self.cache_file_in_memory(path, text)
             }
@@ -137,35 +233,134 @@ where
         self.publish_feedback(feedback);
     }
 
-    fn publish_feedback(&self, feedback: Feedback) {
+    fn publish_feedback(&mut self, feedback: Feedback) {
         self.publish_diagnostics(feedback.diagnostics);
         self.publish_messages(feedback.messages);
     }
 
-    fn publish_diagnostics(&self, diagnostics: HashMap<Utf8PathBuf, Vec<Diagnostic>>) {
+    fn publish_diagnostics(&mut self, diagnostics: HashMap<Utf8PathBuf, Vec<Diagnostic>>) {
+        let pulls_diagnostics = self.client_pulls_diagnostics();
+        let changed_files = !diagnostics.is_empty();
+
         for (path, diagnostics) in diagnostics {
             let diagnostics = diagnostics
                 .into_iter()
                 .flat_map(diagnostic_to_lsp)
                 .collect::<Vec<_>>();
-            let uri = path_to_uri(path);
+            _ = self.diagnostics.insert(path.clone(), diagnostics.clone());
 
-            // Publish the diagnostics
-            let diagnostic_params = PublishDiagnosticsParams {
-                uri,
-                diagnostics,
-                version: None,
-            };
-            let notification = lsp_server::Notification {
-                method: "textDocument/publishDiagnostics".into(),
-                params: serde_json::to_value(diagnostic_params)
-                    .expect("textDocument/publishDiagnostics to json"),
-            };
-            self.connection
-                .sender
-                .send(lsp_server::Message::Notification(notification))
-                .expect("send textDocument/publishDiagnostics");
+            // Clients asking for diagnostics would show them twice if they
+            // were also published.
+            if pulls_diagnostics {
+                continue;
+            }
+
+            self.send_diagnostics(path, diagnostics);
+        }
+
+        if pulls_diagnostics && changed_files {
+            self.refresh_diagnostics();
+        }
+    }
+
+    fn send_diagnostics(&self, path: Utf8PathBuf, diagnostics: Vec<lsp::Diagnostic>) {
+        let uri = path_to_uri(path);
+
+        // Publish the diagnostics
+        let diagnostic_params = PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        };
+        let notification = lsp_server::Notification {
+            method: "textDocument/publishDiagnostics".into(),
+            params: serde_json::to_value(diagnostic_params)
+                .expect("textDocument/publishDiagnostics to json"),
+        };
+        self.connection
+            .sender
+            .send(lsp_server::Message::Notification(notification))
+            .expect("send textDocument/publishDiagnostics");
+    }
+
+    /// While a project is being compiled its published diagnostics may no
+    /// longer be accurate, so they are published again marked as stale.
+    /// Returns the files whose diagnostics have been marked.
+    ///
+    fn publish_stale_diagnostics(&self, projects: &HashSet<Utf8PathBuf>) -> Vec<Utf8PathBuf> {
+        // Clients asking for diagnostics only do so after compilation.
+        if self.client_pulls_diagnostics() {
+            return vec![];
+        }
+
+        self.diagnostics
+            .iter()
+            .filter(|(path, diagnostics)| {
+                !diagnostics.is_empty() && projects.iter().any(|project| path.starts_with(project))
+            })
+            .map(|(path, diagnostics)| {
+                let stale = diagnostics.iter().cloned().map(stale_diagnostic).collect();
+                self.send_diagnostics(path.clone(), stale);
+                path.clone()
+            })
+            .collect()
+    }
+
+    fn client_pulls_diagnostics(&self) -> bool {
+        self.initialise_params
+            .capabilities
+            .text_document
+            .as_ref()
+            .is_some_and(|text_document| text_document.diagnostic.is_some())
+    }
+
+    /// Ask the client to pull the diagnostics again, as changing a file could
+    /// have changed the diagnostics of other files depending on it.
+    ///
+    fn refresh_diagnostics(&mut self) {
+        let supports_refresh = self
+            .initialise_params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.diagnostic.as_ref())
+            .and_then(|diagnostic| diagnostic.refresh_support)
+            .unwrap_or(false);
+
+        if !supports_refresh {
+            return;
         }
+
+        self.diagnostic_refreshes += 1;
+        let request = lsp_server::Request {
+            id: format!("refresh-diagnostics--{}", self.diagnostic_refreshes).into(),
+            method: "workspace/diagnostic/refresh".into(),
+            params: Json::Null,
+        };
+        self.connection
+            .sender
+            .send(lsp_server::Message::Request(request))
+            .expect("send workspace/diagnostic/refresh");
+    }
+
+    /// Ask the client to apply an edit to the workspace, for commands that
+    /// change files.
+    ///
+    fn apply_edit(&mut self, label: &str, edit: lsp::WorkspaceEdit) {
+        self.applied_edits += 1;
+        let params = lsp::ApplyWorkspaceEditParams {
+            label: Some(label.into()),
+            edit,
+        };
+        let request = lsp_server::Request {
+            id: format!("apply-edit--{}", self.applied_edits).into(),
+            method: "workspace/applyEdit".into(),
+            params: serde_json::to_value(params).expect("workspace/applyEdit to json"),
+        };
+        self.connection
+            .sender
+            .send(lsp_server::Message::Request(request))
+            .expect("send workspace/applyEdit");
     }
 
     fn start_watching_gleam_toml(&mut self) {
@@ -240,7 +435,28 @@ where
     where
         T: serde::Serialize,
         Handler: FnOnce(
-            &mut LanguageServerEngine<IO, ConnectionProgressReporter<'a>>,
+            &mut LanguageServerEngine<IO, ConnectionProgressReporter>,
+        ) -> engine::Response<T>,
+    {
+        let (value, feedback) = self.engine_response(path, handler);
+        let json = match value {
+            Some(value) => serde_json::to_value(value).expect("response to json"),
+            None => Json::Null,
+        };
+        (json, feedback)
+    }
+
+    /// Runs a handler with the engine of the project a file belongs to,
+    /// returning its result and the feedback for the client.
+    ///
+    fn engine_response<T, Handler>(
+        &mut self,
+        path: Utf8PathBuf,
+        handler: Handler,
+    ) -> (Option<T>, Feedback)
+    where
+        Handler: FnOnce(
+            &mut LanguageServerEngine<IO, ConnectionProgressReporter>,
         ) -> engine::Response<T>,
     {
         match self.router.project_for_path(path) {
@@ -253,19 +469,18 @@ where
                 match result {
                     Ok(value) => {
                         let feedback = project.feedback.response(compilation, warnings);
-                        let json = serde_json::to_value(value).expect("response to json");
-                        (json, feedback)
+                        (Some(value), feedback)
                     }
                     Err(e) => {
                         let feedback = project.feedback.build_with_error(e, compilation, warnings);
-                        (Json::Null, feedback)
+                        (None, feedback)
                     }
                 }
             }
 
-            Ok(None) => (Json::Null, Feedback::default()),
+            Ok(None) => (None, Feedback::default()),
 
-            Err(error) => (Json::Null, self.outside_of_project_feedback.error(error)),
+            Err(error) => (None, self.outside_of_project_feedback.error(error)),
         }
     }
 
@@ -301,6 +516,105 @@ where
         (json, Feedback::default())
     }
 
+    fn range_format(&mut self, params: lsp::DocumentRangeFormattingParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+
+        let src: EcoString = match self.io.read(&path) {
+            Ok(src) => src.into(),
+            Err(error) => return self.path_error_response(path, error),
+        };
+
+        let line_numbers = LineNumbers::new(&src);
+        let range = lsp_range_to_src_span(params.range, &line_numbers);
+        match crate::format::pretty_range(&src, &path, range) {
+            Ok(edits) => (formatting_edits(edits, &line_numbers), Feedback::default()),
+            Err(error) => self.path_error_response(path, error),
+        }
+    }
+
+    /// Formats the code that was just completed by typing a `}` or a new
+    /// line.
+    ///
+    fn on_type_format(&mut self, params: lsp::DocumentOnTypeFormattingParams) -> (Json, Feedback) {
+        let position = params.text_document_position;
+        let path = super::path(&position.text_document.uri);
+
+        let src: EcoString = match self.io.read(&path) {
+            Ok(src) => src.into(),
+            Err(error) => return self.path_error_response(path, error),
+        };
+
+        let line_numbers = LineNumbers::new(&src);
+        let byte_index = match params.ch.as_str() {
+            // With a new line it's the code on the previous line that was
+            // just completed.
+            "\n" => line_numbers
+                .byte_index(position.position.line, 0)
+                .saturating_sub(1),
+            _ => line_numbers.byte_index(position.position.line, position.position.character),
+        };
+
+        // The code is likely still being written and might not parse, in
+        // which case there's nothing to format.
+        let range = SrcSpan::new(byte_index, byte_index);
+        let edits = crate::format::pretty_range(&src, &path, range).unwrap_or_default();
+        (formatting_edits(edits, &line_numbers), Feedback::default())
+    }
+
+    /// Any outdated code is compiled before requests are handled, so the
+    /// stored diagnostics are always up to date.
+    ///
+    fn document_diagnostic(&mut self, params: lsp::DocumentDiagnosticParams) -> (Json, Feedback) {
+        self.compile_changed_projects();
+        let path = super::path(&params.text_document.uri);
+        let items = self.diagnostics.get(&path).cloned().unwrap_or_default();
+        let report = lsp::DocumentDiagnosticReportResult::Report(
+            lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                    result_id: None,
+                    items,
+                },
+            }),
+        );
+        let json = serde_json::to_value(report).expect("to JSON value");
+        (json, Feedback::default())
+    }
+
+    fn workspace_diagnostic(&mut self, _: lsp::WorkspaceDiagnosticParams) -> (Json, Feedback) {
+        self.compile_changed_projects();
+        let items = self
+            .diagnostics
+            .iter()
+            .sorted_by_key(|(path, _)| *path)
+            .map(|(path, diagnostics)| {
+                lsp::WorkspaceDocumentDiagnosticReport::Full(
+                    lsp::WorkspaceFullDocumentDiagnosticReport {
+                        uri: path_to_uri(path.clone()),
+                        version: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: None,
+                            items: diagnostics.clone(),
+                        },
+                    },
+                )
+            })
+            .collect_vec();
+        let report =
+            lsp::WorkspaceDiagnosticReportResult::Report(lsp::WorkspaceDiagnosticReport { items });
+        let json = serde_json::to_value(report).expect("to JSON value");
+        (json, Feedback::default())
+    }
+
+    /// Compiles any project edited since it was last compiled, so that the
+    /// diagnostics reported for it reflect the latest edits.
+    fn compile_changed_projects(&mut self) {
+        if !self.changed_projects.is_empty() {
+            let feedback = self.compile_please();
+            self.publish_feedback(feedback);
+        }
+    }
+
     fn hover(&mut self, params: lsp::HoverParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document_position_params.text_document.uri);
         self.respond_with_engine(path, |engine| engine.hover(params))
@@ -319,6 +633,11 @@ where
         self.respond_with_engine(path, |engine| engine.goto_type_definition(params))
     }
 
+    fn goto_implementation(&mut self, params: lsp_types::GotoDefinitionParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.goto_implementation(params))
+    }
+
     fn completion(&mut self, params: lsp::CompletionParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document_position.text_document.uri);
 
@@ -341,6 +660,146 @@ where
         self.respond_with_engine(path, |engine| engine.code_actions(params))
     }
 
+    fn execute_command(&mut self, params: lsp::ExecuteCommandParams) -> (Json, Feedback) {
+        match params.command.as_str() {
+            MOVE_DEFINITION_COMMAND => self.move_definition(params.arguments),
+            CHANGE_SIGNATURE_COMMAND => self.change_signature(params.arguments),
+            RENAME_MODULE_COMMAND => self.rename_module(params.arguments),
+            _ => (Json::Null, Feedback::default()),
+        }
+    }
+
+    fn move_definition(&mut self, arguments: Vec<Json>) -> (Json, Feedback) {
+        let arguments = arguments.into_iter().next().and_then(|arguments| {
+            serde_json::from_value::<MoveDefinitionArguments>(arguments).ok()
+        });
+        let Some(arguments) = arguments else {
+            return (Json::Null, Feedback::default());
+        };
+
+        let path = super::path(&arguments.uri);
+        let (outcome, mut feedback) =
+            self.engine_response(path, |engine| engine.move_definition(arguments));
+        let message = match outcome.flatten() {
+            Some(MoveOutcome::Moved {
+                edit,
+                private_items,
+            }) => {
+                self.apply_edit("Move definition", edit);
+                if private_items.is_empty() {
+                    return (Json::Null, feedback);
+                }
+                format!(
+                    "The moved code uses private definitions of its original module, \
+which need to be made public: {}",
+                    private_items
+                        .iter()
+                        .map(|name| format!("`{name}`"))
+                        .join(", ")
+                )
+            }
+            Some(MoveOutcome::Refused { reason }) => reason,
+            None => return (Json::Null, feedback),
+        };
+        feedback.append_message(Diagnostic {
+            title: "Move definition".into(),
+            text: message,
+            level: Level::Warning,
+            tags: vec![],
+            location: None,
+            hint: None,
+        });
+        (Json::Null, feedback)
+    }
+
+    fn change_signature(&mut self, arguments: Vec<Json>) -> (Json, Feedback) {
+        let arguments = arguments.into_iter().next().and_then(|arguments| {
+            serde_json::from_value::<ChangeSignatureArguments>(arguments).ok()
+        });
+        let Some(arguments) = arguments else {
+            return (Json::Null, Feedback::default());
+        };
+
+        let path = super::path(&arguments.uri);
+        let (outcome, mut feedback) =
+            self.engine_response(path, |engine| engine.change_signature(arguments));
+        let message = match outcome.flatten() {
+            Some(ChangeSignatureOutcome::Changed { edit, unsafe_calls }) => {
+                self.apply_edit("Change signature", edit);
+                if unsafe_calls.is_empty() {
+                    return (Json::Null, feedback);
+                }
+                format!(
+                    "These uses of the function couldn't be safely updated \
+and need to be changed by hand: {}",
+                    unsafe_calls
+                        .iter()
+                        .map(|location| format!(
+                            "{}:{}:{}",
+                            super::path(&location.uri),
+                            location.range.start.line + 1,
+                            location.range.start.character + 1
+                        ))
+                        .join(", ")
+                )
+            }
+            Some(ChangeSignatureOutcome::Refused { reason }) => reason,
+            None => return (Json::Null, feedback),
+        };
+        feedback.append_message(Diagnostic {
+            title: "Change signature".into(),
+            text: message,
+            level: Level::Warning,
+            tags: vec![],
+            location: None,
+            hint: None,
+        });
+        (Json::Null, feedback)
+    }
+
+    fn rename_module(&mut self, arguments: Vec<Json>) -> (Json, Feedback) {
+        let arguments = arguments
+            .into_iter()
+            .next()
+            .and_then(|arguments| serde_json::from_value::<RenameModuleArguments>(arguments).ok());
+        let Some(arguments) = arguments else {
+            return (Json::Null, Feedback::default());
+        };
+
+        let path = super::path(&arguments.uri);
+        let (outcome, mut feedback) =
+            self.engine_response(path, |engine| engine.rename_module(arguments));
+        let message = match outcome.flatten() {
+            Some(RenameModuleOutcome::Renamed { edit }) => {
+                self.apply_edit("Rename module", edit);
+                return (Json::Null, feedback);
+            }
+            Some(RenameModuleOutcome::Refused { reason }) => reason,
+            None => return (Json::Null, feedback),
+        };
+        feedback.append_message(Diagnostic {
+            title: "Rename module".into(),
+            text: message,
+            level: Level::Warning,
+            tags: vec![],
+            location: None,
+            hint: None,
+        });
+        (Json::Null, feedback)
+    }
+
+    fn will_rename_files(&mut self, params: lsp::RenameFilesParams) -> (Json, Feedback) {
+        let Some(path) = params
+            .files
+            .first()
+            .and_then(|file| Url::parse(&file.old_uri).ok())
+            .map(|uri| super::path(&uri))
+        else {
+            return (Json::Null, Feedback::default());
+        };
+        self.respond_with_engine(path, |engine| engine.will_rename_files(params))
+    }
+
     fn document_symbol(&mut self, params: lsp::DocumentSymbolParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document.uri);
         self.respond_with_engine(path, |engine| engine.document_symbol(params))
@@ -353,12 +812,182 @@ where
 
     fn rename(&mut self, params: lsp::RenameParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document_position.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.rename(params))
+        let position = params.text_document_position.clone();
+        let new_name = params.new_name.clone();
+        let (edit, mut feedback) =
+            self.engine_response(path.clone(), |engine| engine.rename(params));
+        let Some(Some(mut edit)) = edit else {
+            return (Json::Null, feedback);
+        };
+
+        // Projects depending on this one through its path might be using the
+        // renamed value or type, so their references are renamed too.
+        let dependents = self.dependent_projects(&path);
+        if !dependents.is_empty() {
+            let (item, item_feedback) =
+                self.engine_response(path, |engine| engine.renamed_module_item(&position));
+            feedback.append_feedback(item_feedback);
+
+            if let Some(Some(item)) = item {
+                for dependent in dependents {
+                    let (dependent_edit, dependent_feedback) = self
+                        .engine_response(dependent, |engine| {
+                            engine.rename_dependency_references(&item, &new_name)
+                        });
+                    feedback.append_feedback(dependent_feedback);
+
+                    if let Some(dependent_changes) =
+                        dependent_edit.and_then(|dependent_edit| dependent_edit.changes)
+                    {
+                        edit.changes
+                            .get_or_insert_with(HashMap::new)
+                            .extend(dependent_changes);
+                    }
+                }
+            }
+        }
+
+        let json = serde_json::to_value(edit).expect("response to json");
+        (json, feedback)
     }
 
     fn find_references(&mut self, params: lsp_types::ReferenceParams) -> (Json, Feedback) {
         let path = super::path(&params.text_document_position.text_document.uri);
-        self.respond_with_engine(path, |engine| engine.find_references(params))
+        let position = params.text_document_position.clone();
+        let (locations, mut feedback) =
+            self.engine_response(path.clone(), |engine| engine.find_references(params));
+        let Some(Some(mut locations)) = locations else {
+            return (Json::Null, feedback);
+        };
+
+        // Projects depending on this one through its path might be using the
+        // referenced value or type, so they are searched too.
+        let dependents = self.dependent_projects(&path);
+        if !dependents.is_empty() {
+            let (item, item_feedback) =
+                self.engine_response(path, |engine| engine.referenced_module_item(&position));
+            feedback.append_feedback(item_feedback);
+
+            if let Some(Some(item)) = item {
+                for dependent in dependents {
+                    let (dependent_locations, dependent_feedback) = self
+                        .engine_response(dependent, |engine| {
+                            engine.find_dependency_references(&item)
+                        });
+                    feedback.append_feedback(dependent_feedback);
+                    locations.extend(dependent_locations.unwrap_or_default());
+                }
+            }
+        }
+
+        let json = serde_json::to_value(locations).expect("response to json");
+        (json, feedback)
+    }
+
+    fn inlay_hints(&mut self, params: lsp::InlayHintParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        let config = self.configuration.inlay_hints;
+        self.respond_with_engine(path, |engine| engine.inlay_hints(params, config))
+    }
+
+    fn semantic_tokens_full(&mut self, params: lsp::SemanticTokensParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.semantic_tokens_full(params))
+    }
+
+    fn semantic_tokens_full_delta(
+        &mut self,
+        params: lsp::SemanticTokensDeltaParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.semantic_tokens_full_delta(params))
+    }
+
+    fn folding_range(&mut self, params: lsp::FoldingRangeParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.folding_range(params))
+    }
+
+    fn selection_range(&mut self, params: lsp::SelectionRangeParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.selection_range(params))
+    }
+
+    fn document_highlight(&mut self, params: lsp::DocumentHighlightParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.document_highlight(params))
+    }
+
+    fn code_lens(&mut self, params: lsp::CodeLensParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.code_lens(params))
+    }
+
+    fn generated_code(&mut self, params: GeneratedCodeParams) -> (Json, Feedback) {
+        let path = super::path(&params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.generated_code(params))
+    }
+
+    fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.text_document_position_params.text_document.uri);
+        self.respond_with_engine(path, |engine| engine.prepare_call_hierarchy(params))
+    }
+
+    fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.item.uri);
+        self.respond_with_engine(path, |engine| engine.incoming_calls(params))
+    }
+
+    fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> (Json, Feedback) {
+        let path = super::path(&params.item.uri);
+        self.respond_with_engine(path, |engine| engine.outgoing_calls(params))
+    }
+
+    /// Workspace symbols are not tied to any particular file, so all the
+    /// projects currently open are searched.
+    ///
+    fn workspace_symbol(&mut self, params: lsp::WorkspaceSymbolParams) -> (Json, Feedback) {
+        let mut symbols: Vec<lsp::SymbolInformation> = vec![];
+        let mut seen = HashSet::new();
+        let mut feedback = Feedback::none();
+
+        for project in self.router.projects() {
+            let engine::Response {
+                result,
+                warnings,
+                compilation,
+            } = project.engine.workspace_symbol(&params);
+            match result {
+                Ok(found) => {
+                    feedback.append_feedback(project.feedback.response(compilation, warnings));
+                    // Projects can share dependencies, so we make sure not to
+                    // report the same symbol twice.
+                    for symbol in found {
+                        if seen.insert((symbol.name.clone(), symbol.location.clone())) {
+                            symbols.push(symbol);
+                        }
+                    }
+                }
+                Err(error) => feedback.append_feedback(project.feedback.build_with_error(
+                    error,
+                    compilation,
+                    warnings,
+                )),
+            }
+        }
+
+        let symbols = sort_workspace_symbols(&params.query, symbols);
+        let json = serde_json::to_value(symbols).expect("response to json");
+        (json, feedback)
     }
 
     fn cache_file_in_memory(&mut self, path: Utf8PathBuf, text: String) -> Feedback {
@@ -377,30 +1006,76 @@ where
         Feedback::none()
     }
 
+    fn source_file_closed(&mut self, path: Utf8PathBuf) -> Feedback {
+        if let Some(uri) = url_from_path(path.as_str()) {
+            for project in self.router.projects() {
+                project.engine.document_closed(&uri);
+            }
+        }
+        self.discard_in_memory_cache(path)
+    }
+
     fn watched_files_changed(&mut self, path: Utf8PathBuf) -> Feedback {
         self.router.delete_engine_for_path(&path);
         Feedback::none()
     }
 
+    /// Compiles the projects changed since they were last compiled.
+    ///
+    /// This happens on the same thread that handles messages, so requests
+    /// received in the meantime are only answered once compilation has
+    /// finished, unless the client cancels them first.
+    ///
+    /// Compilation can't be moved to a worker thread while requests are
+    /// answered from the previous modules: type variables are shared
+    /// `Arc<RefCell<TypeVar>>`s, so the types of the modules held by an
+    /// engine and those of its compiler's module interfaces can't be used
+    /// from two threads at once.
     fn compile_please(&mut self) -> Feedback {
         let mut accumulator = Feedback::none();
         let projects = std::mem::take(&mut self.changed_projects);
+        let stale_files = self.publish_stale_diagnostics(&projects);
         for path in projects {
             let (_, feedback) = self.respond_with_engine(path, |this| this.compile_please());
             accumulator.append_feedback(feedback);
         }
+
+        // Diagnostics that were not replaced by compilation are still valid,
+        // so they are published again without being marked as stale.
+        for path in stale_files {
+            if !accumulator.diagnostics.contains_key(&path)
+                && let Some(diagnostics) = self.diagnostics.get(&path)
+            {
+                self.send_diagnostics(path, diagnostics.clone());
+            }
+        }
+
         accumulator
     }
 
     fn project_changed(&mut self, path: &Utf8Path) {
         let project_path = self.router.project_path(path);
         if let Some(project_path) = project_path {
+            // Projects depending on this one through its path compile its
+            // modules from source, so they need recompiling too in order for
+            // their diagnostics to reflect the change.
+            self.changed_projects
+                .extend(self.router.dependent_project_paths(&project_path));
             _ = self.changed_projects.insert(project_path);
         }
     }
+
+    /// The roots of the loaded projects that depend on the project a file
+    /// belongs to through its path.
+    fn dependent_projects(&self, path: &Utf8Path) -> Vec<Utf8PathBuf> {
+        match self.router.project_path(path) {
+            Some(project_path) => self.router.dependent_project_paths(&project_path),
+            None => vec![],
+        }
+    }
 }
 
-fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializeParams {
+fn initialisation_handshake(connection: &lsp_server::Connection) -> Result<InitializeParams> {
     let server_capabilities = lsp::ServerCapabilities {
         text_document_sync: Some(lsp::TextDocumentSyncCapability::Options(
             lsp::TextDocumentSyncOptions {
@@ -415,7 +1090,7 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
                 )),
             },
         )),
-        selection_range_provider: None,
+        selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
         hover_provider: Some(HoverProviderCapability::Simple(true)),
         completion_provider: Some(lsp::CompletionOptions {
             resolve_provider: None,
@@ -435,16 +1110,21 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
         }),
         definition_provider: Some(lsp::OneOf::Left(true)),
         type_definition_provider: Some(lsp::TypeDefinitionProviderCapability::Simple(true)),
-        implementation_provider: None,
+        implementation_provider: Some(lsp::ImplementationProviderCapability::Simple(true)),
         references_provider: Some(lsp::OneOf::Left(true)),
-        document_highlight_provider: None,
+        document_highlight_provider: Some(lsp::OneOf::Left(true)),
         document_symbol_provider: Some(lsp::OneOf::Left(true)),
-        workspace_symbol_provider: None,
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
         code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
-        code_lens_provider: None,
+        code_lens_provider: Some(lsp::CodeLensOptions {
+            resolve_provider: Some(false),
+        }),
         document_formatting_provider: Some(lsp::OneOf::Left(true)),
-        document_range_formatting_provider: None,
-        document_on_type_formatting_provider: None,
+        document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(lsp::DocumentOnTypeFormattingOptions {
+            first_trigger_character: "}".into(),
+            more_trigger_character: Some(vec!["\n".into()]),
+        }),
         rename_provider: Some(lsp::OneOf::Right(RenameOptions {
             prepare_provider: Some(true),
             work_done_progress_options: lsp::WorkDoneProgressOptions {
@@ -453,28 +1133,80 @@ fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializePa
         })),
         document_link_provider: None,
         color_provider: None,
-        folding_range_provider: None,
+        folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
         declaration_provider: None,
-        execute_command_provider: None,
-        workspace: None,
-        call_hierarchy_provider: None,
-        semantic_tokens_provider: None,
+        execute_command_provider: Some(lsp::ExecuteCommandOptions {
+            commands: vec![
+                MOVE_DEFINITION_COMMAND.into(),
+                CHANGE_SIGNATURE_COMMAND.into(),
+                RENAME_MODULE_COMMAND.into(),
+            ],
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
+        workspace: Some(lsp::WorkspaceServerCapabilities {
+            workspace_folders: None,
+            file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                will_rename: Some(lsp::FileOperationRegistrationOptions {
+                    filters: vec![
+                        rename_filter(
+                            "**/*.{gleam,erl,hrl,ex,js,mjs,ts}",
+                            lsp::FileOperationPatternKind::File,
+                        ),
+                        rename_filter("**/*", lsp::FileOperationPatternKind::Folder),
+                    ],
+                }),
+                ..Default::default()
+            }),
+        }),
+        call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                lsp::SemanticTokensOptions {
+                    work_done_progress_options: lsp::WorkDoneProgressOptions {
+                        work_done_progress: None,
+                    },
+                    legend: semantic_tokens::legend(),
+                    range: None,
+                    full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                },
+            ),
+        ),
         moniker_provider: None,
         linked_editing_range_provider: None,
         experimental: None,
         position_encoding: None,
         inline_value_provider: None,
-        inlay_hint_provider: None,
-        diagnostic_provider: None,
+        inlay_hint_provider: Some(lsp::OneOf::Left(true)),
+        diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+            lsp::DiagnosticOptions {
+                identifier: Some("gleam".into()),
+                inter_file_dependencies: true,
+                workspace_diagnostics: true,
+                work_done_progress_options: lsp::WorkDoneProgressOptions::default(),
+            },
+        )),
     };
     let server_capabilities_json =
         serde_json::to_value(server_capabilities).expect("server_capabilities_serde");
+    let initialisation_error = |error: String| crate::Error::LanguageServerInitialisation { error };
     let initialise_params_json = connection
         .initialize(server_capabilities_json)
-        .expect("LSP initialize");
-    let initialise_params: InitializeParams =
-        serde_json::from_value(initialise_params_json).expect("LSP InitializeParams from json");
-    initialise_params
+        .map_err(|error| initialisation_error(error.to_string()))?;
+    serde_json::from_value(initialise_params_json)
+        .map_err(|error| initialisation_error(error.to_string()))
+}
+
+fn rename_filter(glob: &str, matches: lsp::FileOperationPatternKind) -> lsp::FileOperationFilter {
+    lsp::FileOperationFilter {
+        scheme: Some("file".into()),
+        pattern: lsp::FileOperationPattern {
+            glob: glob.into(),
+            matches: Some(matches),
+            options: None,
+        },
+    }
 }
 
 fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
@@ -545,7 +1277,11 @@ fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
         } else {
             Some(related_info)
         },
-        tags: None,
+        tags: if diagnostic.tags.is_empty() {
+            None
+        } else {
+            Some(diagnostic.tags.iter().map(diagnostic_tag_to_lsp).collect())
+        },
         data: None,
     };
 
@@ -562,6 +1298,42 @@ fn diagnostic_to_lsp(diagnostic: Diagnostic) -> Vec<lsp::Diagnostic> {
     }
 }
 
+/// The data sent along with a diagnostic published while the project it
+/// belongs to is being compiled.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+struct StaleDiagnosticData {
+    /// Always `true`, so that clients can tell the diagnostic may be out of
+    /// date.
+    stale: bool,
+}
+
+/// Marks a diagnostic as coming from a compilation that is out of date.
+fn stale_diagnostic(diagnostic: lsp::Diagnostic) -> lsp::Diagnostic {
+    lsp::Diagnostic {
+        data: serde_json::to_value(StaleDiagnosticData { stale: true }).ok(),
+        ..diagnostic
+    }
+}
+
+fn diagnostic_tag_to_lsp(tag: &Tag) -> lsp::DiagnosticTag {
+    match tag {
+        Tag::Unnecessary => lsp::DiagnosticTag::UNNECESSARY,
+        Tag::Deprecated => lsp::DiagnosticTag::DEPRECATED,
+    }
+}
+
+fn formatting_edits(edits: Vec<(SrcSpan, String)>, line_numbers: &LineNumbers) -> Json {
+    let edits = edits
+        .into_iter()
+        .map(|(location, new_text)| TextEdit {
+            range: src_span_to_lsp_range(location, line_numbers),
+            new_text,
+        })
+        .collect_vec();
+    serde_json::to_value(edits).expect("to JSON value")
+}
+
 fn path_to_uri(path: Utf8PathBuf) -> Url {
     let mut file: String = "file://".into();
     file.push_str(&path.as_os_str().to_string_lossy());
@@ -107,9 +107,17 @@ fn signature_help(
         None => HashMap::new(),
     };
 
+    let labels_documentation = field_map.map(|field_map| &field_map.documentation);
+
     let printer = Printer::new();
-    let (label, parameters) =
-        print_signature_help(printer, fun_name, args, return_, &index_to_label);
+    let (label, parameters) = print_signature_help(
+        printer,
+        fun_name,
+        args,
+        return_,
+        &index_to_label,
+        labels_documentation,
+    );
 
     let active_parameter = active_parameter_index(arity, supplied_args, index_to_label)
         // If we don't want to highlight any arg in the suggestion we have to
@@ -217,7 +225,8 @@ fn active_parameter_index(
 /// To produce a signature that can be used by the LS, we need to also keep
 /// track of the arguments' positions in the printed signature. So this function
 /// prints the signature help producing at the same time a list of correct
-/// `ParameterInformation` for all its arguments.
+/// `ParameterInformation` for all its arguments, documenting the labelled ones
+/// using `labels_documentation`.
 ///
 fn print_signature_help(
     mut printer: Printer,
@@ -225,6 +234,7 @@ fn print_signature_help(
     args: Vec<Arc<Type>>,
     return_: Arc<Type>,
     index_to_label: &HashMap<u32, &EcoString>,
+    labels_documentation: Option<&HashMap<EcoString, EcoString>>,
 ) -> (String, Vec<ParameterInformation>) {
     let args_count = args.len();
     let mut signature = format!("{function_name}(");
@@ -232,17 +242,26 @@ fn print_signature_help(
 
     for (i, arg) in args.iter().enumerate() {
         let arg_start = signature.len();
-        if let Some(label) = index_to_label.get(&(i as u32)) {
+        let label = index_to_label.get(&(i as u32));
+        if let Some(label) = label {
             signature.push_str(label);
             signature.push_str(": ");
         }
         signature.push_str(&printer.pretty_print(arg, 0));
         let arg_end = signature.len();
-        let label = ParameterLabel::LabelOffsets([arg_start as u32, arg_end as u32]);
+        let documentation = label
+            .zip(labels_documentation)
+            .and_then(|(label, labels_documentation)| labels_documentation.get(*label))
+            .map(|documentation| {
+                Documentation::MarkupContent(MarkupContent {
+                    kind: MarkupKind::Markdown,
+                    value: documentation.to_string(),
+                })
+            });
 
         parameter_informations.push(ParameterInformation {
-            label,
-            documentation: None,
+            label: ParameterLabel::LabelOffsets([arg_start as u32, arg_end as u32]),
+            documentation,
         });
 
         let is_last = i == args_count - 1;
@@ -1,12 +1,29 @@
 mod action;
+mod call_hierarchy;
+mod cancellation;
+mod change_signature;
+mod code_lens;
 mod compilation;
 mod completion;
 mod definition;
+mod document_highlight;
 mod document_symbols;
+mod folding_range;
+mod generated_code;
 mod hover;
+mod implementation;
+mod inlay_hints;
+mod move_definition;
+mod progress;
+mod pull_diagnostics;
+mod reconnect;
 mod reference;
 mod rename;
+mod rename_module;
+mod selection_range;
+mod semantic_tokens;
 mod signature_help;
+mod workspace_symbols;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -44,6 +61,8 @@ pub const LSP_TEST_ROOT_PACKAGE_NAME: &str = "app";
 enum Action {
     CompilationStarted,
     CompilationFinished,
+    CompilingPackage(EcoString),
+    CompilingModule(EcoString),
     DependencyDownloadingStarted,
     DependencyDownloadingFinished,
     DownloadDependencies,
@@ -284,6 +303,14 @@ impl ProgressReporter for LanguageServerTestIO {
         self.record(Action::CompilationFinished);
     }
 
+    fn compiling_package(&self, name: &str) {
+        self.record(Action::CompilingPackage(name.into()));
+    }
+
+    fn compiling_module(&self, name: &str) {
+        self.record(Action::CompilingModule(name.into()));
+    }
+
     fn dependency_downloading_started(&self) {
         self.record(Action::DependencyDownloadingStarted);
     }
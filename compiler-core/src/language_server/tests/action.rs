@@ -70,6 +70,7 @@ const CONVERT_FROM_USE: &str = "Convert from `use`";
 const CONVERT_TO_USE: &str = "Convert to `use`";
 const EXTRACT_VARIABLE: &str = "Extract variable";
 const EXTRACT_CONSTANT: &str = "Extract constant";
+const EXTRACT_FUNCTION: &str = "Extract function";
 const EXPAND_FUNCTION_CAPTURE: &str = "Expand function capture";
 const GENERATE_DYNAMIC_DECODER: &str = "Generate dynamic decoder";
 const GENERATE_JSON_ENCODER: &str = "Generate JSON encoder";
@@ -78,10 +79,34 @@ const PATTERN_MATCH_ON_VARIABLE: &str = "Pattern match on variable";
 const GENERATE_FUNCTION: &str = "Generate function";
 const CONVERT_TO_FUNCTION_CALL: &str = "Convert to function call";
 const INLINE_VARIABLE: &str = "Inline variable";
+const INLINE_FUNCTION: &str = "Inline function";
+const INLINE_FUNCTION_AND_REMOVE_ITS_DEFINITION: &str = "Inline function and remove its definition";
 const CONVERT_TO_PIPE: &str = "Convert to pipe";
 const INTERPOLATE_STRING: &str = "Interpolate string";
 const FILL_UNUSED_FIELDS: &str = "Fill unused fields";
 const REMOVE_ALL_ECHOS_FROM_THIS_MODULE: &str = "Remove all `echo`s from this module";
+const ORGANIZE_IMPORTS: &str = "Organize imports";
+const WRAP_IN_OK: &str = "Wrap in `Ok`";
+const WRAP_IN_ERROR: &str = "Wrap in `Error`";
+const WRAP_IN_SOME: &str = "Wrap in `Some`";
+const USE_INT_TO_FLOAT: &str = "Use `int.to_float`";
+const USE_FLOAT_ROUND: &str = "Use `float.round`";
+const USE_STRING_CONCAT: &str = "Use `string.concat`";
+const USE_RESULT_TRY: &str = "Use `result.try`";
+const INT_MODULE: &str = "pub fn parse(x: String) -> Result(Int, Nil) { todo }
+pub fn to_float(x: Int) -> Float { todo }";
+const RESULT_MODULE: &str = "pub fn try(
+  result: Result(a, e),
+  fun: fn(a) -> Result(b, e),
+) -> Result(b, e) {
+  todo
+}";
+const GENERATE_VARIANT: &str = "Generate variant";
+const GENERATE_TYPE: &str = "Generate type";
+const GENERATE_TYPE_ALIAS: &str = "Generate type alias";
+const MAKE_PUBLIC: &str = "Make public";
+const MAKE_PRIVATE: &str = "Make private";
+const MAKE_INTERNAL: &str = "Make internal";
 
 macro_rules! assert_code_action {
     ($title:expr, $code:literal, $range:expr $(,)?) => {
@@ -5566,6 +5591,190 @@ pub fn main() {
     );
 }
 
+#[test]
+fn extract_function() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  let x = 1
+  let y = x + 1
+  let z = y * 2
+  z + x
+}
+",
+        find_position_of("let y").select_until(find_position_of("y * 2").with_char_offset(5))
+    );
+}
+
+#[test]
+fn extract_function_returning_the_last_expression() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        r#"pub fn main() {
+  let name = "Lucy"
+  let greeting = "Hello, " <> name
+  greeting <> "!"
+}
+"#,
+        find_position_of("let greeting")
+            .select_until(find_position_of("\"!\"").with_char_offset(3))
+    );
+}
+
+#[test]
+fn extract_function_returning_multiple_variables() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        r#"pub fn main() {
+  let a = 1
+  let b = "wibble"
+  let c = 2.0
+  #(a, b, c)
+}
+"#,
+        find_position_of("let a").select_until(find_position_of("2.0").with_char_offset(3))
+    );
+}
+
+#[test]
+fn extract_function_with_generic_arguments() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        "pub fn wibble(list: List(a), default: a) -> a {
+  let first = case list {
+    [first, ..] -> first
+    [] -> default
+  }
+  first
+}
+",
+        find_position_of("let first")
+            .select_until(find_position_of("}\n  first").with_char_offset(1))
+    );
+}
+
+#[test]
+fn extract_function_reindents_the_statements() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  let x = 1
+  let y = {
+    let z = case x {
+      1 -> 2
+      _ -> 3
+    }
+    z + 1
+  }
+  y
+}
+",
+        find_position_of("let z").select_until(find_position_of("z + 1").with_char_offset(5))
+    );
+}
+
+#[test]
+fn extract_function_from_anonymous_function() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  fn(x) {
+    let y = x * 2
+    y + 1
+  }
+}
+",
+        find_position_of("let y").select_until(find_position_of("y + 1").with_char_offset(5))
+    );
+}
+
+#[test]
+fn extract_function_selecting_whole_lines() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  let x = 1
+  let y = x + 1
+  y
+}
+",
+        find_position_of("  let y").select_until(find_position_of("  y\n"))
+    );
+}
+
+#[test]
+fn extract_function_with_variable_used_in_guard() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        "pub fn main(limit: Int) {
+  let x = 1
+  case x {
+    _ if x > limit -> 1
+    _ -> 2
+  }
+}
+",
+        find_position_of("case").select_until(find_position_of("  }\n}").with_char_offset(3))
+    );
+}
+
+#[test]
+fn extract_function_avoids_existing_names() {
+    assert_code_action!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  let x = function()
+  x + 1
+}
+
+fn function() { 1 }
+",
+        find_position_of("x + 1").select_until(find_position_of("x + 1").with_char_offset(5))
+    );
+}
+
+#[test]
+fn extract_function_is_not_available_for_partially_selected_statements() {
+    assert_no_code_actions!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  let x = 1
+  let y = x + 1
+  y
+}
+",
+        find_position_of("1").select_until(find_position_of("x + 1").with_char_offset(5))
+    );
+}
+
+#[test]
+fn extract_function_is_not_available_without_a_selection() {
+    assert_no_code_actions!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  let x = 1
+  x
+}
+",
+        find_position_of("let").to_selection()
+    );
+}
+
+#[test]
+fn extract_function_is_not_available_for_use() {
+    assert_no_code_actions!(
+        EXTRACT_FUNCTION,
+        "pub fn main() {
+  use x <- wibble()
+  x
+}
+
+fn wibble(f) { f(1) }
+",
+        find_position_of("use").select_until(find_position_of("x\n}").with_char_offset(1))
+    );
+}
+
 #[test]
 fn do_not_extract_top_level_expression_statement() {
     assert_no_code_actions!(
@@ -7104,3 +7313,1721 @@ pub fn main() {
         find_position_of("c1,").to_selection()
     );
 }
+#[test]
+fn inline_function() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  double(21)
+}
+
+fn double(x: Int) -> Int {
+  x * 2
+}
+",
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_and_remove_its_definition() {
+    assert_code_action!(
+        INLINE_FUNCTION_AND_REMOVE_ITS_DEFINITION,
+        "pub fn main() {
+  double(21)
+}
+
+/// Doubles a number.
+fn double(x: Int) -> Int {
+  x * 2
+}
+
+pub fn other() {
+  Nil
+}
+",
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_definition_is_not_removed_if_it_is_public() {
+    assert_no_code_actions!(
+        INLINE_FUNCTION_AND_REMOVE_ITS_DEFINITION,
+        "pub fn main() {
+  double(21)
+}
+
+pub fn double(x: Int) -> Int {
+  x * 2
+}
+",
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_definition_is_not_removed_if_it_is_called_elsewhere() {
+    assert_no_code_actions!(
+        INLINE_FUNCTION_AND_REMOVE_ITS_DEFINITION,
+        "pub fn main() {
+  double(21) + double(1)
+}
+
+fn double(x: Int) -> Int {
+  x * 2
+}
+",
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_with_labelled_arguments() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  subtract(from: 10, value: 3)
+}
+
+fn subtract(value value: Int, from from: Int) -> Int {
+  from - value
+}
+",
+        find_position_of("subtract").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_with_argument_used_twice() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  square(wibble())
+}
+
+fn square(x: Int) -> Int {
+  x * x
+}
+
+fn wibble() { 3 }
+",
+        find_position_of("square").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_with_argument_used_in_anonymous_function() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  later(expensive())
+}
+
+fn later(x) {
+  fn() { x }
+}
+
+fn expensive() { 3 }
+",
+        find_position_of("later(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_with_argument_used_in_case_clause() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  maybe(True, expensive())
+}
+
+fn maybe(condition, x) {
+  case condition {
+    True -> x
+    False -> 0
+  }
+}
+
+fn expensive() { 3 }
+",
+        find_position_of("maybe(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_keeps_arguments_evaluation_order() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  subtract(wibble(), wobble())
+}
+
+fn subtract(a, b) {
+  b - a
+}
+
+fn wibble() { 1 }
+fn wobble() { 2 }
+",
+        find_position_of("subtract(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_keeps_argument_before_side_effects_of_body() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  log_then_return(wibble())
+}
+
+fn log_then_return(x) {
+  log()
+  x
+}
+
+fn log() { Nil }
+fn wibble() { 1 }
+",
+        find_position_of("log_then_return(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_with_argument_evaluated_first() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  add_one(wibble())
+}
+
+fn add_one(x) {
+  x + one()
+}
+
+fn one() { 1 }
+fn wibble() { 1 }
+",
+        find_position_of("add_one(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_with_multiple_statements() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        r#"pub fn main() {
+  let name = "Lucy"
+  greet(name)
+}
+
+fn greet(name: String) -> String {
+  let greeting = "Hello, " <> name
+  greeting <> "!"
+}
+"#,
+        find_position_of("greet(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_renames_variables_to_avoid_capture() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  let result = 3
+  add_one_twice(result)
+}
+
+fn add_one_twice(x: Int) -> Int {
+  let result = x + 1
+  result + x
+}
+",
+        find_position_of("add_one_twice(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_wraps_operation_arguments() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  double(1 + 2)
+}
+
+fn double(x: Int) -> Int {
+  x * 2
+}
+",
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_wraps_operation_body() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  add_one(1) * 2
+}
+
+fn add_one(x: Int) -> Int {
+  x + 1
+}
+",
+        find_position_of("add_one").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_with_shorthand_label() {
+    assert_code_action!(
+        INLINE_FUNCTION,
+        "pub type Wibble {
+  Wibble(name: String)
+}
+
+pub fn main() {
+  make(\"Lucy\")
+}
+
+fn make(name: String) -> Wibble {
+  Wibble(name:)
+}
+",
+        find_position_of("make(").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_from_other_module() {
+    let src = "import app/math
+
+pub fn main() {
+  math.double(21)
+}
+";
+    assert_code_action!(
+        INLINE_FUNCTION,
+        TestProject::for_source(src).add_module(
+            "app/math",
+            "pub fn double(x: Int) -> Int {
+  x * 2
+}"
+        ),
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_is_not_available_for_function_referencing_other_module_values() {
+    let src = "import app/math
+
+pub fn main() {
+  math.double(21)
+}
+";
+    assert_no_code_actions!(
+        INLINE_FUNCTION,
+        TestProject::for_source(src).add_module(
+            "app/math",
+            "pub fn double(x: Int) -> Int {
+  multiply(x, 2)
+}
+
+fn multiply(a, b) { a * b }"
+        ),
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_is_not_available_for_dependency_functions() {
+    let src = "import dep
+
+pub fn main() {
+  dep.double(21)
+}
+";
+    assert_no_code_actions!(
+        INLINE_FUNCTION,
+        TestProject::for_source(src).add_dep_module(
+            "dep",
+            "pub fn double(x: Int) -> Int {
+  x * 2
+}"
+        ),
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_is_not_available_for_external_functions() {
+    assert_no_code_actions!(
+        INLINE_FUNCTION,
+        r#"pub fn main() {
+  double(21)
+}
+
+@external(erlang, "math", "double")
+fn double(x: Int) -> Int {
+  x * 2
+}
+"#,
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_is_not_available_in_pipelines() {
+    assert_no_code_actions!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  21 |> double
+}
+
+fn double(x: Int) -> Int {
+  x * 2
+}
+",
+        find_position_of("double").to_selection()
+    );
+}
+
+#[test]
+fn inline_function_is_not_available_if_a_value_would_be_shadowed() {
+    assert_no_code_actions!(
+        INLINE_FUNCTION,
+        "pub fn main() {
+  let two = 3
+  double(two)
+}
+
+fn double(x: Int) -> Int {
+  x * two()
+}
+
+fn two() { 2 }
+",
+        find_position_of("double(").to_selection()
+    );
+}
+
+#[test]
+fn organize_imports_sorts_imports() {
+    let src = "
+import wobble
+import wibble.{wibble, type Wibble}
+import gleam/list
+
+pub fn main() -> Wibble {
+  list.map([], wobble.wobble)
+  wibble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wobble", "pub fn wobble(x) { x }")
+            .add_hex_module("wibble", "pub type Wibble pub const wibble = 1")
+            .add_hex_module("gleam/list", "pub fn map(x, f) { x }"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_removes_unused_modules() {
+    let src = "
+import wobble
+import wibble
+import gleam/list as lispy
+
+pub fn main() {
+  wobble.wobble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wobble", "pub const wobble = 1")
+            .add_hex_module("wibble", "")
+            .add_hex_module("gleam/list", ""),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_removes_unused_unqualified_items() {
+    let src = "
+import wibble.{type Wubble, type Wobble, Wibble, wibble, wobble}
+
+pub fn main() -> Wubble {
+  wobble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src).add_hex_module(
+            "wibble",
+            "pub type Wobble { Wibble } pub type Wubble pub const wibble = 1 pub const wobble = 2"
+        ),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_removes_import_with_only_unused_unqualified_items() {
+    let src = "
+import wibble.{wibble}
+import wobble
+
+pub fn main() {
+  wobble.wobble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wibble", "pub const wibble = 1")
+            .add_hex_module("wobble", "pub const wobble = 1"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_keeps_module_used_qualified() {
+    let src = "
+import wibble.{wobble}
+
+pub fn main() -> wibble.Wibble {
+  wibble.Wibble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wibble", "pub type Wibble { Wibble } pub const wobble = 1"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_keeps_aliased_module_used_qualified() {
+    let src = "
+import wibble.{wibble} as wobble
+
+pub fn main() {
+  wobble.wobble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wibble", "pub const wibble = 1 pub const wobble = 2"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_merges_imports_of_the_same_module() {
+    let src = "
+import wibble.{wibble} as _
+import wobble
+import wibble.{type Wibble}
+
+pub fn main() -> Wibble {
+  wobble.wobble
+  wibble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wibble", "pub type Wibble pub const wibble = 1")
+            .add_hex_module("wobble", "pub const wobble = 1"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_merges_into_import_used_qualified() {
+    let src = "
+import wibble.{wibble} as _
+import wibble as wobble
+
+pub fn main() {
+  wobble.wobble
+  wibble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wibble", "pub const wibble = 1 pub const wobble = 2"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_keeps_comments() {
+    let src = "
+import wobble
+// Comment about wibble
+import wibble
+import unused
+
+// Another group
+import gleam/string
+import gleam/list
+
+pub fn main() {
+  wobble.wobble
+  wibble.wibble
+  list.map
+  string.length
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wobble", "pub const wobble = 1")
+            .add_hex_module("wibble", "pub const wibble = 1")
+            .add_hex_module("unused", "")
+            .add_hex_module("gleam/string", "pub fn length() { 1 }")
+            .add_hex_module("gleam/list", "pub fn map() { 1 }"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_keeps_groups_separated_by_empty_lines() {
+    let src = "
+import wobble
+import unused
+import wibble
+
+import gleam/string
+import gleam/list
+
+pub fn main() {
+  wobble.wobble
+  wibble.wibble
+  list.map
+  string.length
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wobble", "pub const wobble = 1")
+            .add_hex_module("wibble", "pub const wibble = 1")
+            .add_hex_module("unused", "")
+            .add_hex_module("gleam/string", "pub fn length() { 1 }")
+            .add_hex_module("gleam/list", "pub fn map() { 1 }"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_removes_all_imports() {
+    let src = "import wibble
+import wobble
+
+pub fn main() {
+  1
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wibble", "")
+            .add_hex_module("wobble", ""),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn organize_imports_formats_long_imports() {
+    let src = "
+import wibble.{wubble_wubble_wubble, wobble_wobble_wobble, type WibbleWibbleWibble, wibble_wibble_wibble}
+
+pub fn main() -> WibbleWibbleWibble {
+  wibble_wibble_wibble
+  wobble_wobble_wobble
+  wubble_wubble_wubble
+}
+";
+
+    assert_code_action!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src).add_hex_module(
+            "wibble",
+            "pub type WibbleWibbleWibble
+pub const wibble_wibble_wibble = 1
+pub const wobble_wobble_wobble = 1
+pub const wubble_wubble_wubble = 1"
+        ),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn no_organize_imports_when_already_organized() {
+    let src = "
+import gleam/list
+import wibble.{type Wibble, wibble}
+
+pub fn main() -> Wibble {
+  list.map
+  wibble
+}
+";
+
+    assert_no_code_actions!(
+        ORGANIZE_IMPORTS,
+        TestProject::for_source(src)
+            .add_hex_module("wibble", "pub type Wibble pub const wibble = 1")
+            .add_hex_module("gleam/list", "pub fn map() { 1 }"),
+        find_position_of("main").to_selection(),
+    );
+}
+
+#[test]
+fn wrap_in_ok() {
+    assert_code_action!(
+        WRAP_IN_OK,
+        "
+pub fn main() -> Result(Int, Nil) {
+  1
+}
+",
+        find_position_of("1").to_selection(),
+    );
+}
+
+#[test]
+fn wrap_in_error() {
+    assert_code_action!(
+        WRAP_IN_ERROR,
+        r#"
+pub fn main() -> Result(Int, String) {
+  "wibble"
+}
+"#,
+        find_position_of("wibble").to_selection(),
+    );
+}
+
+#[test]
+fn no_wrap_in_error_for_ok_value() {
+    assert_no_code_actions!(
+        WRAP_IN_ERROR,
+        "
+pub fn main() -> Result(Int, String) {
+  1
+}
+",
+        find_position_of("1").to_selection(),
+    );
+}
+
+#[test]
+fn wrap_function_argument_in_ok() {
+    assert_code_action!(
+        WRAP_IN_OK,
+        "
+pub fn main() {
+  wibble(1)
+}
+
+fn wibble(result: Result(Int, Nil)) {
+  result
+}
+",
+        find_position_of("1").to_selection(),
+    );
+}
+
+#[test]
+fn wrap_in_some() {
+    let src = "
+import gleam/option.{type Option}
+
+pub fn main() -> Option(Int) {
+  1
+}
+";
+    assert_code_action!(
+        WRAP_IN_SOME,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/option", "pub type Option(a) { Some(a) None }"),
+        find_position_of("1").to_selection(),
+    );
+}
+
+#[test]
+fn wrap_in_unqualified_some() {
+    let src = "
+import gleam/option.{type Option, Some as Just}
+
+pub fn main() -> Option(Int) {
+  1
+}
+";
+    assert_code_action!(
+        WRAP_IN_SOME,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/option", "pub type Option(a) { Some(a) None }"),
+        find_position_of("1").to_selection(),
+    );
+}
+
+#[test]
+fn use_int_to_float() {
+    let src = "
+pub fn main(x: Int) -> Float {
+  x +. 1.0
+}
+";
+    assert_code_action!(
+        USE_INT_TO_FLOAT,
+        TestProject::for_source(src).add_hex_module("gleam/int", INT_MODULE),
+        find_position_of("x +.").to_selection(),
+    );
+}
+
+#[test]
+fn no_use_int_to_float_if_stdlib_is_not_a_dependency() {
+    let src = "
+pub fn main(x: Int) -> Float {
+  x +. 1.0
+}
+";
+    assert_no_code_actions!(
+        USE_INT_TO_FLOAT,
+        TestProject::for_source(src).add_indirect_hex_module("gleam/int", INT_MODULE),
+        find_position_of("x +.").to_selection(),
+    );
+}
+
+#[test]
+fn use_int_to_float_with_aliased_import() {
+    let src = "
+import gleam/int as integer
+
+pub fn main(x: Int) -> Float {
+  integer.add(x, 1)
+  x +. 1.0
+}
+";
+    assert_code_action!(
+        USE_INT_TO_FLOAT,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", "pub fn add(a: Int, b: Int) -> Int { a + b }"),
+        find_position_of("x +.").to_selection(),
+    );
+}
+
+#[test]
+fn use_float_round() {
+    let src = "
+pub fn main(x: Float) -> Int {
+  x
+}
+";
+    assert_code_action!(
+        USE_FLOAT_ROUND,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/float", "pub fn round(x: Float) -> Int { todo }"),
+        find_position_of("x\n").to_selection(),
+    );
+}
+
+#[test]
+fn use_string_concat() {
+    let src = r#"
+pub fn main() -> String {
+  ["wibble", "wobble"]
+}
+"#;
+    assert_code_action!(
+        USE_STRING_CONCAT,
+        TestProject::for_source(src).add_hex_module(
+            "gleam/string",
+            "pub fn concat(strings: List(String)) -> String { todo }"
+        ),
+        find_position_of("[").to_selection(),
+    );
+}
+
+#[test]
+fn use_result_try_for_variable() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Result(Int, Nil) {
+  let number = int.parse(x)
+  Ok(number + 1)
+}
+";
+    assert_code_action!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn use_result_try_for_annotated_assignment() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Result(Int, Nil) {
+  let number: Int = int.parse(x)
+  Ok(number + 1)
+}
+";
+    assert_code_action!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("int.parse").to_selection(),
+    );
+}
+
+#[test]
+fn no_use_result_try_if_function_does_not_return_result() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Int {
+  let number = int.parse(x)
+  number + 1
+}
+";
+    assert_no_code_actions!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn no_use_result_try_if_error_types_differ() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Result(Int, String) {
+  let number = int.parse(x)
+  Ok(number + 1)
+}
+";
+    assert_no_code_actions!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn use_result_try_in_anonymous_function() {
+    let src = "
+import gleam/int
+
+pub fn main(xs: List(String)) -> Nil {
+  let parse = fn(x) -> Result(Int, Nil) {
+    let number = int.parse(x)
+    Ok(number + 1)
+  }
+  Nil
+}
+";
+    assert_code_action!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn no_use_result_try_in_anonymous_function_not_returning_result() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Result(Int, Nil) {
+  let add_one = fn() {
+    let number = int.parse(x)
+    number + 1
+  }
+  Ok(add_one())
+}
+";
+    assert_no_code_actions!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn no_use_result_try_in_block_not_returning_result() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Result(Int, Nil) {
+  let total = {
+    let number = int.parse(x)
+    number + 1
+  }
+  Ok(total)
+}
+";
+    assert_no_code_actions!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn use_result_try_picks_the_assignment_in_scope() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Result(Int, Nil) {
+  let number = int.parse(x)
+  let _ = fn() {
+    let number = int.parse(x)
+    number
+  }
+  Ok(number + 1)
+}
+";
+    assert_code_action!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn no_use_result_try_if_stdlib_is_not_a_dependency() {
+    let src = "
+import gleam/int
+
+pub fn main(x: String) -> Result(Int, Nil) {
+  let number = int.parse(x)
+  Ok(number + 1)
+}
+";
+    assert_no_code_actions!(
+        USE_RESULT_TRY,
+        TestProject::for_source(src)
+            .add_hex_module("gleam/int", INT_MODULE)
+            .add_indirect_hex_module("gleam/result", RESULT_MODULE),
+        find_position_of("number +").to_selection(),
+    );
+}
+
+#[test]
+fn no_wrap_in_some_if_stdlib_is_not_a_dependency() {
+    let src = "
+import gleam/option.{type Option}
+
+pub fn main() -> Option(Int) {
+  1
+}
+";
+    assert_no_code_actions!(
+        WRAP_IN_SOME,
+        TestProject::for_source(src)
+            .add_indirect_hex_module("gleam/option", "pub type Option(a) { Some(a) None }"),
+        find_position_of("1").to_selection(),
+    );
+}
+
+#[test]
+fn type_mismatch_fixes_are_not_preferred_if_there_is_more_than_one() {
+    let src = "
+pub fn main() -> Result(Int, Int) {
+  1
+}
+";
+    let range = find_position_of("1").to_selection().find_range(src);
+    let fixes = actions_with_title(
+        vec![WRAP_IN_OK, WRAP_IN_ERROR],
+        TestProject::for_source(src),
+        range,
+    );
+    assert_eq!(fixes.len(), 2);
+    assert!(fixes.iter().all(|action| action.is_preferred != Some(true)));
+}
+
+#[test]
+fn generate_variant_without_arguments() {
+    assert_code_action!(
+        GENERATE_VARIANT,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub fn main() -> Wibble {
+  Wobble
+}
+",
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_with_arguments() {
+    assert_code_action!(
+        GENERATE_VARIANT,
+        "
+pub type Wibble {
+  Wibble
+  Wobble(Int)
+}
+
+pub fn main() -> Wibble {
+  Wubble(1, 2.0, \"three\")
+}
+",
+        find_position_of("Wubble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_with_labels() {
+    assert_code_action!(
+        GENERATE_VARIANT,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub fn main() -> Wibble {
+  Wobble(1, name: \"Louis\", values: [1.0])
+}
+",
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_from_argument_type() {
+    assert_code_action!(
+        GENERATE_VARIANT,
+        "
+pub type Wibble {
+  Wibble(Int)
+}
+
+pub fn main() {
+  wobble(Wobble(True))
+}
+
+fn wobble(wibble: Wibble) -> Nil {
+  todo
+}
+",
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_multiline_type() {
+    assert_code_action!(
+        GENERATE_VARIANT,
+        "
+pub type Wibble {
+  Wibble(
+    name: String,
+    age: Int,
+  )
+}
+
+pub fn main() {
+  let wibble: Wibble = Wobble(1)
+}
+",
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_not_offered_for_type_of_another_module() {
+    let src = "
+import wibble
+
+pub fn main() -> wibble.Wibble {
+  Wobble
+}
+";
+    assert_no_code_actions!(
+        GENERATE_VARIANT,
+        TestProject::for_source(src).add_hex_module("wibble", "pub type Wibble { Wibble }"),
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_not_offered_for_unknown_type() {
+    assert_no_code_actions!(
+        GENERATE_VARIANT,
+        "
+pub fn main() {
+  Wobble(1)
+}
+",
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_not_offered_for_argument_of_unknown_type() {
+    assert_no_code_actions!(
+        GENERATE_VARIANT,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub fn main() -> Wibble {
+  Wobble(todo)
+}
+",
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_variant_not_offered_for_lowercase_name() {
+    assert_no_code_actions!(
+        GENERATE_VARIANT,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub fn main() -> Wibble {
+  wobble(1)
+}
+",
+        find_position_of("wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_in_argument_annotation() {
+    assert_code_action!(
+        GENERATE_TYPE,
+        "
+pub fn main(wibble: Wibble) -> Nil {
+  todo
+}
+
+pub fn wobble() {
+  todo
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_in_private_function_annotation() {
+    assert_code_action!(
+        GENERATE_TYPE,
+        "
+fn main(wibble: Wibble) -> Nil {
+  todo
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_with_parameters() {
+    assert_code_action!(
+        GENERATE_TYPE,
+        "
+pub fn main() -> Wibble(Int, List(String)) {
+  todo
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_in_custom_type_field() {
+    assert_code_action!(
+        GENERATE_TYPE,
+        "
+pub type Wibble {
+  Wibble(wobble: Wobble)
+}
+",
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_in_let_annotation() {
+    assert_code_action!(
+        GENERATE_TYPE,
+        "
+pub fn main() {
+  let wibble: Wibble = todo
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_alias_in_argument_annotation() {
+    assert_code_action!(
+        GENERATE_TYPE_ALIAS,
+        "
+pub fn main(wibble: Wibble) -> Nil {
+  todo
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_alias_not_offered_for_type_with_parameters() {
+    assert_no_code_actions!(
+        GENERATE_TYPE_ALIAS,
+        "
+pub const wibble: Wibble(Int) = 1
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_in_constant_annotation() {
+    assert_code_action!(
+        GENERATE_TYPE,
+        "
+pub const wibble: Wibble = 1
+
+pub fn main() {
+  wibble
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn generate_type_not_offered_for_qualified_type() {
+    let src = "
+import wibble
+
+pub fn main() -> wibble.Wobble {
+  todo
+}
+";
+    assert_no_code_actions!(
+        GENERATE_TYPE,
+        TestProject::for_source(src).add_hex_module("wibble", "pub type Wibble { Wibble }"),
+        find_position_of("Wobble").to_selection()
+    );
+}
+
+#[test]
+fn make_private_function_public() {
+    assert_code_action!(
+        MAKE_PUBLIC,
+        "
+fn wibble() {
+  1
+}
+",
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_private_constant_public() {
+    assert_code_action!(
+        MAKE_PUBLIC,
+        "
+const wibble = 1
+",
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_private_type_public() {
+    assert_code_action!(
+        MAKE_PUBLIC,
+        "
+type Wibble {
+  Wibble
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_private_function_internal() {
+    assert_code_action!(
+        MAKE_INTERNAL,
+        "
+/// Some documentation
+fn wibble() {
+  1
+}
+",
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_public_function_private() {
+    assert_code_action!(
+        MAKE_PRIVATE,
+        "
+pub fn wibble() {
+  1
+}
+",
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_public_type_alias_private() {
+    assert_code_action!(
+        MAKE_PRIVATE,
+        "
+pub type Wibble =
+  Int
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_public_opaque_type_private() {
+    assert_code_action!(
+        MAKE_PRIVATE,
+        "
+pub opaque type Wibble {
+  Wibble
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_public_constant_internal() {
+    assert_code_action!(
+        MAKE_INTERNAL,
+        "
+@deprecated(\"Use wobble\")
+pub const wibble = 1
+",
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_internal_function_public() {
+    assert_code_action!(
+        MAKE_PUBLIC,
+        "
+@internal
+pub fn wibble() {
+  1
+}
+",
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_internal_type_private() {
+    assert_code_action!(
+        MAKE_PRIVATE,
+        "
+@internal
+pub type Wibble {
+  Wibble
+}
+",
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_public_function_private_is_not_offered_if_used_by_other_modules() {
+    let src = "
+pub fn wibble() {
+  1
+}
+";
+    assert_no_code_actions!(
+        MAKE_PRIVATE,
+        TestProject::for_source(src)
+            .add_module("wobble", "import app\npub fn wobble() { app.wibble() }"),
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_public_type_private_is_not_offered_if_constructor_is_used_by_other_modules() {
+    let src = "
+pub type Wibble {
+  Wobble
+}
+";
+    assert_no_code_actions!(
+        MAKE_PRIVATE,
+        TestProject::for_source(src)
+            .add_module("wobble", "import app\npub fn wobble() { app.Wobble }"),
+        find_position_of("Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_public_function_private_is_offered_if_only_used_by_its_module() {
+    assert_code_action!(
+        MAKE_PRIVATE,
+        "
+pub fn wibble() {
+  1
+}
+
+pub fn main() {
+  wibble()
+}
+",
+        find_position_of("wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_function_public_is_not_offered_if_it_would_leak_a_private_type() {
+    assert_no_code_actions!(
+        MAKE_PUBLIC,
+        "
+type Wibble {
+  Wibble
+}
+
+fn wibble() -> Wibble {
+  Wibble
+}
+",
+        find_position_of("fn wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_function_internal_is_not_offered_if_it_would_leak_a_private_type() {
+    assert_no_code_actions!(
+        MAKE_INTERNAL,
+        "
+type Wibble {
+  Wibble
+}
+
+fn wibble(_: Wibble) -> Int {
+  1
+}
+",
+        find_position_of("fn wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_function_public_is_not_offered_if_it_would_leak_an_internal_type() {
+    assert_no_code_actions!(
+        MAKE_PUBLIC,
+        "
+@internal
+pub type Wibble {
+  Wibble
+}
+
+fn wibble() -> List(Wibble) {
+  [Wibble]
+}
+",
+        find_position_of("fn wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_private_is_not_offered_if_it_would_be_leaked() {
+    assert_no_code_actions!(
+        MAKE_PRIVATE,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub fn wibble() -> Wibble {
+  Wibble
+}
+",
+        find_position_of("type Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_internal_is_not_offered_if_it_would_be_leaked() {
+    assert_no_code_actions!(
+        MAKE_INTERNAL,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub const wibble = Wibble
+",
+        find_position_of("type Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_public_is_not_offered_if_its_constructors_would_leak_a_private_type() {
+    assert_no_code_actions!(
+        MAKE_PUBLIC,
+        "
+type Wibble {
+  Wibble(Wobble)
+}
+
+type Wobble {
+  Wobble
+}
+",
+        find_position_of("type Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_private_is_not_offered_if_a_public_type_alias_uses_it() {
+    assert_no_code_actions!(
+        MAKE_PRIVATE,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub type Wobble =
+  List(Wibble)
+",
+        find_position_of("type Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_private_is_not_offered_if_a_public_type_field_uses_it() {
+    assert_no_code_actions!(
+        MAKE_PRIVATE,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub type Wobble {
+  Wobble(wibble: Wibble)
+}
+",
+        find_position_of("type Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_alias_public_is_not_offered_if_it_would_leak_a_private_type() {
+    assert_no_code_actions!(
+        MAKE_PUBLIC,
+        "
+type Wibble {
+  Wibble
+}
+
+type Wobble =
+  List(Wibble)
+",
+        find_position_of("type Wobble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_private_is_offered_if_only_an_opaque_type_field_uses_it() {
+    assert_code_action!(
+        MAKE_PRIVATE,
+        "
+pub type Wibble {
+  Wibble
+}
+
+pub opaque type Wobble {
+  Wobble(wibble: Wibble)
+}
+",
+        find_position_of("type Wibble").to_selection()
+    );
+}
+
+#[test]
+fn make_type_internal_is_offered_if_only_used_by_internal_values() {
+    assert_code_action!(
+        MAKE_INTERNAL,
+        "
+pub type Wibble {
+  Wibble
+}
+
+@internal
+pub fn wibble() -> Wibble {
+  Wibble
+}
+",
+        find_position_of("type Wibble").to_selection()
+    );
+}
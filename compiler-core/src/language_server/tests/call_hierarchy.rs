@@ -0,0 +1,245 @@
+use lsp_types::{
+    CallHierarchyIncomingCallsParams, CallHierarchyItem, CallHierarchyOutgoingCallsParams,
+    CallHierarchyPrepareParams, Range,
+};
+
+use super::*;
+
+fn prepare_call_hierarchy(
+    tester: &TestProject<'_>,
+    position: Position,
+) -> Option<CallHierarchyItem> {
+    tester.at(position, |engine, params, _| {
+        let params = CallHierarchyPrepareParams {
+            text_document_position_params: params,
+            work_done_progress_params: Default::default(),
+        };
+        let items = engine.prepare_call_hierarchy(params).result.unwrap()?;
+        let [item] = items.as_slice() else {
+            panic!("Expected a single call hierarchy item");
+        };
+        Some(item.clone())
+    })
+}
+
+fn incoming_calls(tester: TestProject<'_>, position: Position) -> String {
+    let item = prepare_call_hierarchy(&tester, position).expect("No call hierarchy item");
+    let calls = tester.at(position, |engine, _, _| {
+        let params = CallHierarchyIncomingCallsParams {
+            item: item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.incoming_calls(params).result.unwrap().unwrap()
+    });
+
+    let mut output = show_item(&tester, &item);
+    for call in calls {
+        output.push_str("\n----- Called by ");
+        output.push_str(&show_item(&tester, &call.from));
+        output.push_str(&show_ranges(&tester, &call.from, &call.from_ranges));
+    }
+    output
+}
+
+fn outgoing_calls(tester: TestProject<'_>, position: Position) -> String {
+    let item = prepare_call_hierarchy(&tester, position).expect("No call hierarchy item");
+    let calls = tester.at(position, |engine, _, _| {
+        let params = CallHierarchyOutgoingCallsParams {
+            item: item.clone(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.outgoing_calls(params).result.unwrap().unwrap()
+    });
+
+    let mut output = show_item(&tester, &item);
+    for call in calls {
+        output.push_str("\n----- Calls ");
+        output.push_str(&show_item(&tester, &call.to));
+        output.push_str(&show_ranges(&tester, &item, &call.from_ranges));
+    }
+    output
+}
+
+/// Shows the name and module of an item, followed by the code it selects.
+///
+fn show_item(tester: &TestProject<'_>, item: &CallHierarchyItem) -> String {
+    let src = tester.src_from_module_url(&item.uri).unwrap();
+    format!(
+        "{} ({})\n{}\n",
+        item.name,
+        item.detail.as_deref().unwrap_or_default(),
+        show_range(src, item.range)
+    )
+}
+
+/// Shows the code of each range, found in the module of the given item.
+///
+fn show_ranges(tester: &TestProject<'_>, item: &CallHierarchyItem, ranges: &[Range]) -> String {
+    let src = tester.src_from_module_url(&item.uri).unwrap();
+    ranges
+        .iter()
+        .map(|range| {
+            format!(
+                "at {}:{} `{}`\n",
+                range.start.line,
+                range.start.character,
+                show_range(src, *range)
+            )
+        })
+        .collect()
+}
+
+fn show_range(src: &str, range: Range) -> &str {
+    let line_numbers = LineNumbers::new(src);
+    let start = line_numbers.byte_index(range.start.line, range.start.character);
+    let end = line_numbers.byte_index(range.end.line, range.end.character);
+    src.get(start as usize..end as usize).unwrap()
+}
+
+#[test]
+fn prepare_call_hierarchy_on_function_definition() {
+    let code = "
+pub fn main() {
+  wibble()
+}
+
+fn wibble() { Nil }
+";
+
+    let item = prepare_call_hierarchy(
+        &TestProject::for_source(code),
+        find_position_of("wibble() {").find_position(code),
+    )
+    .unwrap();
+
+    assert_eq!(item.name, "wibble");
+    assert_eq!(item.detail.as_deref(), Some("app"));
+    assert_eq!(
+        item.selection_range,
+        Range::new(Position::new(5, 3), Position::new(5, 9))
+    );
+}
+
+#[test]
+fn prepare_call_hierarchy_on_something_that_is_not_a_function() {
+    let code = "
+const wibble = 1
+
+pub fn main() {
+  let wobble = wibble
+  wobble
+}
+";
+
+    let tester = TestProject::for_source(code);
+    assert_eq!(
+        prepare_call_hierarchy(&tester, find_position_of("wibble\n").find_position(code)),
+        None
+    );
+    assert_eq!(
+        prepare_call_hierarchy(&tester, find_position_of("wobble\n}").find_position(code)),
+        None
+    );
+}
+
+#[test]
+fn incoming_calls_from_the_same_module() {
+    let code = "
+pub fn main() {
+  wibble(1)
+  wibble(2)
+  wobble()
+}
+
+fn wobble() {
+  [1, 2] |> map(wibble)
+}
+
+fn wibble(x) { x }
+
+fn map(list, fun) { todo }
+";
+
+    insta::assert_snapshot!(incoming_calls(
+        TestProject::for_source(code),
+        find_position_of("wibble(x)").find_position(code)
+    ));
+}
+
+#[test]
+fn incoming_calls_from_other_modules() {
+    let code = "
+pub fn wibble() { Nil }
+";
+
+    insta::assert_snapshot!(incoming_calls(
+        TestProject::for_source(code)
+            .add_module(
+                "wobble",
+                "import app
+
+pub fn wobble() {
+  app.wibble()
+}
+"
+            )
+            .add_module(
+                "wubble",
+                "import app.{wibble as other}
+
+pub fn wubble() {
+  other()
+}
+"
+            ),
+        find_position_of("wibble").find_position(code)
+    ));
+}
+
+#[test]
+fn outgoing_calls_to_other_modules() {
+    let code = "
+import wibble.{wobble}
+
+pub fn main() {
+  let x = helper(1)
+  wobble(x)
+  wibble.wubble(x)
+  helper(x)
+}
+
+fn helper(x) { x }
+";
+
+    insta::assert_snapshot!(outgoing_calls(
+        TestProject::for_source(code).add_hex_module(
+            "wibble",
+            "pub fn wobble(x) { x }
+pub fn wubble(x) { x }
+"
+        ),
+        find_position_of("main").find_position(code)
+    ));
+}
+
+#[test]
+fn outgoing_calls_from_call_site() {
+    let code = "
+pub fn main() {
+  wibble()
+}
+
+fn wibble() {
+  wobble()
+}
+
+fn wobble() { Nil }
+";
+
+    insta::assert_snapshot!(outgoing_calls(
+        TestProject::for_source(code),
+        find_position_of("wibble()").find_position(code)
+    ));
+}
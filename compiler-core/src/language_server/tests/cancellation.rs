@@ -0,0 +1,133 @@
+use lsp_server::{Connection, Notification, Request, RequestId};
+
+use crate::language_server::messages::{Message, MessageBuffer, Next};
+
+fn hover_request(id: i32) -> lsp_server::Message {
+    lsp_server::Message::Request(Request {
+        id: id.into(),
+        method: "textDocument/hover".into(),
+        params: serde_json::json!({
+            "textDocument": { "uri": "file:///src/app.gleam" },
+            "position": { "line": 0, "character": 0 },
+        }),
+    })
+}
+
+fn cancel_notification(id: i32) -> lsp_server::Message {
+    lsp_server::Message::Notification(Notification {
+        method: "$/cancelRequest".into(),
+        params: serde_json::json!({ "id": id }),
+    })
+}
+
+fn shutdown_request(id: i32) -> lsp_server::Message {
+    lsp_server::Message::Request(Request {
+        id: id.into(),
+        method: "shutdown".into(),
+        params: serde_json::Value::Null,
+    })
+}
+
+fn exit_notification() -> lsp_server::Message {
+    lsp_server::Message::Notification(Notification {
+        method: "exit".into(),
+        params: serde_json::Value::Null,
+    })
+}
+
+fn did_save_notification() -> lsp_server::Message {
+    lsp_server::Message::Notification(Notification {
+        method: "textDocument/didSave".into(),
+        params: serde_json::json!({
+            "textDocument": { "uri": "file:///src/app.gleam" },
+        }),
+    })
+}
+
+/// Receives the next batch of messages for the server to handle.
+fn handle(buffer: &mut MessageBuffer, server: &Connection) -> Vec<Message> {
+    loop {
+        match buffer.receive(server) {
+            Next::Handle(messages) => return messages,
+            Next::MorePlease => {}
+            Next::Stop => panic!("the server stopped"),
+        }
+    }
+}
+
+fn request_ids(messages: &[Message]) -> Vec<RequestId> {
+    messages
+        .iter()
+        .filter_map(|message| match message {
+            Message::Request(id, _) => Some(id.clone()),
+            Message::Notification(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn request_cancelled_while_waiting_to_be_handled() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+
+    client.sender.send(hover_request(1)).unwrap();
+    let messages = handle(&mut buffer, &server);
+    assert_eq!(request_ids(&messages), vec![RequestId::from(1)]);
+
+    // The client cancels the request while the server compiles before
+    // answering it.
+    client.sender.send(cancel_notification(1)).unwrap();
+    assert!(buffer.is_cancelled(&server, &RequestId::from(1)));
+}
+
+#[test]
+fn request_not_cancelled() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+
+    client.sender.send(hover_request(1)).unwrap();
+    _ = handle(&mut buffer, &server);
+
+    client.sender.send(cancel_notification(2)).unwrap();
+    assert!(!buffer.is_cancelled(&server, &RequestId::from(1)));
+}
+
+#[test]
+fn messages_received_while_looking_for_cancellations_are_kept() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+
+    client.sender.send(hover_request(1)).unwrap();
+    _ = handle(&mut buffer, &server);
+
+    client.sender.send(did_save_notification()).unwrap();
+    client.sender.send(hover_request(2)).unwrap();
+    client.sender.send(cancel_notification(2)).unwrap();
+    assert!(!buffer.is_cancelled(&server, &RequestId::from(1)));
+
+    // The messages sent after the first request are still handled, with the
+    // cancellation of the second request remembered for when its turn comes.
+    let messages = handle(&mut buffer, &server);
+    assert!(
+        messages
+            .iter()
+            .any(|message| matches!(message, Message::Notification(_)))
+    );
+    assert_eq!(request_ids(&messages), vec![RequestId::from(2)]);
+    assert!(buffer.is_cancelled(&server, &RequestId::from(2)));
+}
+
+#[test]
+fn shutdown_after_exit_was_received_while_looking_for_cancellations() {
+    let (server, client) = Connection::memory();
+    let mut buffer = MessageBuffer::new();
+
+    client.sender.send(hover_request(1)).unwrap();
+    _ = handle(&mut buffer, &server);
+
+    client.sender.send(shutdown_request(2)).unwrap();
+    client.sender.send(exit_notification()).unwrap();
+    assert!(!buffer.is_cancelled(&server, &RequestId::from(1)));
+
+    assert!(matches!(buffer.receive(&server), Next::Stop));
+}
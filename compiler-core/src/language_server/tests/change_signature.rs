@@ -0,0 +1,719 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use lsp_types::{
+    CodeActionContext, CodeActionParams, PartialResultParams, Position, Range,
+    WorkDoneProgressParams,
+};
+
+use crate::language_server::change_signature::{
+    ChangeSignatureArguments, ChangeSignatureOutcome, Parameter,
+};
+
+use super::{
+    TestProject, find_position_of,
+    move_definition::{apply_edit, show_modules},
+};
+
+fn change_signature(
+    tester: &TestProject<'_>,
+    position: Position,
+    parameters: Vec<Parameter>,
+) -> ChangeSignatureOutcome {
+    tester
+        .at(position, |engine, params, _| {
+            let arguments = ChangeSignatureArguments {
+                uri: params.text_document.uri,
+                position,
+                parameters,
+            };
+            engine.change_signature(arguments).result.unwrap()
+        })
+        .expect("No function to change")
+}
+
+fn existing(index: usize) -> Parameter {
+    Parameter::Existing { index, label: None }
+}
+
+fn labelled(index: usize, label: &str) -> Parameter {
+    Parameter::Existing {
+        index,
+        label: Some(label.into()),
+    }
+}
+
+fn new(name: &str, default: &str) -> Parameter {
+    Parameter::New {
+        name: name.into(),
+        label: None,
+        annotation: None,
+        default: default.into(),
+    }
+}
+
+macro_rules! assert_change {
+    ($code:literal, $position:expr, $parameters:expr $(,)?) => {
+        assert_change!(TestProject::for_source($code), $position, $parameters);
+    };
+
+    ($project:expr, $position:expr, $parameters:expr $(,)?) => {
+        let project = $project;
+        let src = project.src;
+        let position = $position.find_position(src);
+        let ChangeSignatureOutcome::Changed { edit, unsafe_calls } =
+            change_signature(&project, position, $parameters)
+        else {
+            panic!("The signature was not changed")
+        };
+        let result = apply_edit(&project, edit);
+
+        let before = project
+            .root_package_modules
+            .iter()
+            .map(|(name, code)| (name.to_string(), code.to_string()))
+            .chain(std::iter::once(("app".to_string(), src.to_string())))
+            .collect_vec();
+        let mut after = before.iter().cloned().collect::<HashMap<_, _>>();
+        after.extend(result);
+
+        let mut output = String::from("----- BEFORE CHANGE\n");
+        show_modules(&mut output, before.into_iter());
+        output.push_str("----- AFTER CHANGE\n");
+        show_modules(&mut output, after.into_iter());
+        if !unsafe_calls.is_empty() {
+            output.push_str("----- UNSAFE CALLS\n");
+            for location in unsafe_calls {
+                let module = project
+                    .module_name_from_url(&location.uri)
+                    .expect("Valid uri");
+                output.push_str(&format!(
+                    "{module}:{}:{}\n",
+                    location.range.start.line + 1,
+                    location.range.start.character + 1
+                ));
+            }
+        }
+
+        insta::assert_snapshot!(insta::internals::AutoName, output, src);
+    };
+}
+
+macro_rules! assert_change_refused {
+    ($code:literal, $position:expr, $parameters:expr, $reason:literal $(,)?) => {
+        let project = TestProject::for_source($code);
+        let position = $position.find_position(project.src);
+        assert_eq!(
+            change_signature(&project, position, $parameters),
+            ChangeSignatureOutcome::Refused {
+                reason: $reason.into()
+            }
+        );
+    };
+}
+
+fn change_signature_action_titles(tester: TestProject<'_>, range: Range) -> Vec<String> {
+    tester.at(Position::default(), |engine, params, _| {
+        let params = CodeActionParams {
+            text_document: params.text_document,
+            range,
+            context: CodeActionContext::default(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+        engine
+            .code_actions(params)
+            .result
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .filter(|action| {
+                action
+                    .command
+                    .as_ref()
+                    .is_some_and(|command| command.command == "gleam.changeSignature")
+            })
+            .map(|action| action.title)
+            .collect()
+    })
+}
+
+#[test]
+fn change_signature_code_actions() {
+    let src = "
+pub fn wibble(a, b, c) {
+  a + b
+}
+";
+    let project = TestProject::for_source(src);
+    let range = find_position_of("b,").to_selection().find_range(src);
+
+    assert_eq!(
+        change_signature_action_titles(project, range),
+        vec![
+            "Move parameter `b` left",
+            "Move parameter `b` right",
+            "Label parameter `b`",
+            "Add parameter after `b`",
+        ]
+    );
+}
+
+#[test]
+fn change_signature_code_actions_for_unused_parameter() {
+    let src = "
+pub fn wibble(a, b, c) {
+  a + b
+}
+";
+    let project = TestProject::for_source(src);
+    let range = find_position_of("c)").to_selection().find_range(src);
+
+    assert_eq!(
+        change_signature_action_titles(project, range),
+        vec![
+            "Move parameter `c` left",
+            "Remove unused parameter `c`",
+            "Label parameter `c`",
+            "Add parameter after `c`",
+        ]
+    );
+}
+
+#[test]
+fn no_change_signature_code_actions_for_external_function() {
+    let src = r#"
+@external(erlang, "wibble", "wobble")
+pub fn wibble(a: Int) -> Int
+"#;
+    let project = TestProject::for_source(src);
+    let range = find_position_of("a:").to_selection().find_range(src);
+
+    assert_eq!(
+        change_signature_action_titles(project, range),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn reorder_parameters() {
+    assert_change!(
+        TestProject::for_source(
+            "
+pub fn wibble(a: Int, b: String) -> Int {
+  a
+}
+
+pub fn main() {
+  wibble(1, \"a\")
+}
+"
+        )
+        .add_module(
+            "app/wobble",
+            "import app
+
+pub fn wobble() {
+  app.wibble(2, \"b\")
+}
+"
+        ),
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn reorder_parameters_of_unqualified_import() {
+    assert_change!(
+        TestProject::for_source(
+            "
+pub fn wibble(a, b) {
+  a + b
+}
+"
+        )
+        .add_module(
+            "app/wobble",
+            "import app.{wibble}
+
+pub fn wobble() {
+  wibble(1, 2)
+}
+"
+        ),
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn reorder_parameters_with_labelled_arguments() {
+    assert_change!(
+        "
+pub fn wibble(a, wobble b, wubble c) {
+  a + b + c
+}
+
+pub fn main() {
+  wibble(1, wubble: 3, wobble: 2)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), labelled(2, "wubble"), labelled(1, "wobble")],
+    );
+}
+
+#[test]
+fn label_parameter_passed_positionally_before_labelled_argument() {
+    assert_change!(
+        "
+pub fn f(a, c, x b) {
+  a + b + c
+}
+
+pub fn main() {
+  f(1, 2, x: 3)
+}
+",
+        find_position_of("f("),
+        vec![existing(0), labelled(2, "x"), labelled(1, "y")],
+    );
+}
+
+#[test]
+fn add_label() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+
+pub fn main() {
+  wibble(1, 2)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), labelled(1, "wobble")],
+    );
+}
+
+#[test]
+fn change_label() {
+    assert_change!(
+        "
+pub fn wibble(a, wobble b) {
+  a + b
+}
+
+pub fn main() {
+  let wobble = 2
+  wibble(1, wobble: 2)
+  wibble(1, wobble:)
+  wibble(1, 2)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), labelled(1, "wubble")],
+    );
+}
+
+#[test]
+fn remove_label() {
+    assert_change!(
+        "
+pub fn wibble(wubble a, wobble b) {
+  a + b
+}
+
+pub fn main() {
+  wibble(wobble: 2, wubble: 1)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), labelled(1, "wobble")],
+    );
+}
+
+#[test]
+fn add_parameter() {
+    assert_change!(
+        "
+pub fn wibble(a) {
+  a
+}
+
+pub fn main() {
+  wibble(1)
+}
+",
+        find_position_of("wibble"),
+        vec![new("b", "todo"), existing(0)],
+    );
+}
+
+#[test]
+fn add_labelled_parameter_with_annotation() {
+    assert_change!(
+        "
+pub fn wibble(a: Int) -> Int {
+  a
+}
+
+pub fn main() {
+  wibble(1)
+}
+",
+        find_position_of("wibble"),
+        vec![
+            existing(0),
+            Parameter::New {
+                name: "b".into(),
+                label: Some("wobble".into()),
+                annotation: Some("String".into()),
+                default: "\"\"".into(),
+            },
+        ],
+    );
+}
+
+#[test]
+fn remove_unused_parameter() {
+    assert_change!(
+        "
+pub fn wibble(a, b, _c) {
+  a
+}
+
+pub fn main() {
+  wibble(1, 2, 3)
+  wibble(1, [], #(1, 2))
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0)],
+    );
+}
+
+#[test]
+fn removing_parameter_reports_calls_with_side_effects() {
+    assert_change!(
+        "
+pub fn wibble(a, _b) {
+  a
+}
+
+pub fn main() {
+  wibble(1, 2)
+  wibble(1, main())
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0)],
+    );
+}
+
+#[test]
+fn reordering_arguments_with_side_effects_is_reported() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  a <> b
+}
+
+fn log(message) {
+  message
+}
+
+pub fn main() {
+  wibble(\"a\", \"b\")
+  wibble(log(\"a\"), \"b\")
+  wibble(log(\"a\"), log(\"b\"))
+}
+",
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn add_parameter_to_pipe() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+
+pub fn main() {
+  1 |> wibble(2)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), new("c", "3"), existing(1)],
+    );
+}
+
+#[test]
+fn add_parameter_to_pipe_without_parentheses() {
+    assert_change!(
+        "
+pub fn wibble(a) {
+  a
+}
+
+pub fn main() {
+  1 |> wibble
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), new("b", "2")],
+    );
+}
+
+#[test]
+fn moving_piped_parameter_is_reported() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+
+pub fn main() {
+  1 |> wibble(2)
+  wibble(1, 2)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn add_parameter_to_use() {
+    assert_change!(
+        "
+pub fn wibble(a, f) {
+  f(a)
+}
+
+pub fn main() {
+  use x <- wibble(1)
+  x
+}
+",
+        find_position_of("wibble"),
+        vec![new("b", "2"), existing(0), existing(1)],
+    );
+}
+
+#[test]
+fn add_parameter_to_use_without_parentheses() {
+    assert_change!(
+        "
+pub fn wibble(f) {
+  f()
+}
+
+pub fn main() {
+  use <- wibble
+  1
+}
+",
+        find_position_of("wibble"),
+        vec![new("a", "1"), existing(0)],
+    );
+}
+
+#[test]
+fn moving_use_callback_is_reported() {
+    assert_change!(
+        "
+pub fn wibble(a, f) {
+  f(a)
+}
+
+pub fn main() {
+  use x <- wibble(1)
+  x
+}
+",
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn reorder_parameters_of_capture() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+
+pub fn main() {
+  let f = wibble(_, 2)
+  f(1)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn removing_captured_parameter_is_reported() {
+    assert_change!(
+        "
+pub fn wibble(a, _b) {
+  a
+}
+
+pub fn main() {
+  wibble(1, _)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0)],
+    );
+}
+
+#[test]
+fn function_used_as_value_is_reported() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+
+pub fn main() {
+  apply(wibble)
+}
+
+fn apply(f) {
+  f(1, 2)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn labelling_function_used_as_value() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+
+pub fn main() {
+  apply(wibble)
+}
+
+fn apply(f) {
+  f(1, 2)
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), labelled(1, "wobble")],
+    );
+}
+
+#[test]
+fn recursive_call() {
+    assert_change!(
+        "
+pub fn wibble(a, b) {
+  case a {
+    0 -> b
+    _ -> wibble(a - 1, b)
+  }
+}
+",
+        find_position_of("wibble"),
+        vec![existing(1), existing(0)],
+    );
+}
+
+#[test]
+fn change_signature_refused_for_used_parameter() {
+    assert_change_refused!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0)],
+        "The parameter `b` is used by the function and can't be removed"
+    );
+}
+
+#[test]
+fn change_signature_refused_for_invalid_name() {
+    assert_change_refused!(
+        "
+pub fn wibble(a) {
+  a
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), new("Wobble", "1")],
+        "`Wobble` is not a valid parameter name"
+    );
+}
+
+#[test]
+fn change_signature_refused_for_duplicate_name() {
+    assert_change_refused!(
+        "
+pub fn wibble(a) {
+  a
+}
+",
+        find_position_of("wibble"),
+        vec![existing(0), new("a", "1")],
+        "There's more than one parameter named `a`"
+    );
+}
+
+#[test]
+fn change_signature_refused_for_duplicate_label() {
+    assert_change_refused!(
+        "
+pub fn wibble(a, b) {
+  a + b
+}
+",
+        find_position_of("wibble"),
+        vec![labelled(0, "wobble"), labelled(1, "wobble")],
+        "There's more than one parameter labelled `wobble`"
+    );
+}
+
+#[test]
+fn change_signature_refused_for_labelled_parameter_before_unlabelled_one() {
+    assert_change_refused!(
+        "
+pub fn wibble(a, wobble b) {
+  a + b
+}
+",
+        find_position_of("wibble"),
+        vec![labelled(1, "wobble"), existing(0)],
+        "The unlabelled parameter `a` can't come after the labelled parameter `wobble`"
+    );
+}
+
+#[test]
+fn change_signature_refused_for_external_function() {
+    assert_change_refused!(
+        r#"
+@external(erlang, "wibble", "wobble")
+pub fn wibble(a: Int) -> Int
+"#,
+        find_position_of("wibble("),
+        vec![],
+        "The signature of the external function `wibble` can't be changed"
+    );
+}
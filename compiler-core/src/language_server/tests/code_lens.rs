@@ -0,0 +1,184 @@
+use lsp_types::{CodeLens, CodeLensParams, TextDocumentIdentifier};
+
+use super::*;
+
+fn code_lenses_for(
+    src: &str,
+    params: TextDocumentPositionParams,
+    engine: &mut LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO>,
+) -> String {
+    let params = CodeLensParams {
+        text_document: params.text_document,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let lenses = engine.code_lens(params).result.unwrap();
+    show_lenses(src, lenses)
+}
+
+fn code_lenses(tester: TestProject<'_>) -> String {
+    let (mut engine, params) = tester.positioned_with_io(Position::default());
+    code_lenses_for(tester.src, params, &mut engine)
+}
+
+fn test_code_lenses(tester: TestProject<'_>, test_name: &str, src: &str) -> String {
+    let (mut engine, params) = tester.positioned_with_io_in_test(Position::default(), test_name);
+    code_lenses_for(src, params, &mut engine)
+}
+
+/// Shows each lens above the line it is placed on, along with the command it
+/// runs.
+///
+fn show_lenses(src: &str, lenses: Vec<CodeLens>) -> String {
+    let mut output = String::new();
+
+    for (line_number, line) in src.lines().enumerate() {
+        for lens in lenses
+            .iter()
+            .filter(|lens| lens.range.start.line == line_number as u32)
+        {
+            let command = lens.command.as_ref().expect("Lens command");
+            let arguments = command
+                .arguments
+                .iter()
+                .flatten()
+                .map(|argument| argument.to_string())
+                .join(", ");
+            output.push_str(&format!(
+                "[{}] {} {arguments}\n",
+                command.title, command.command
+            ));
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output
+}
+
+#[test]
+fn code_lens_for_main_function() {
+    let code = "
+pub fn main() {
+  Nil
+}
+
+pub fn other() {
+  Nil
+}
+";
+
+    insta::assert_snapshot!(code_lenses(TestProject::for_source(code)));
+}
+
+#[test]
+fn no_code_lens_for_main_function_with_arguments() {
+    let code = "
+pub fn main(args) {
+  args
+}
+";
+
+    assert_eq!(code_lenses(TestProject::for_source(code)), code);
+}
+
+#[test]
+fn no_code_lens_for_private_main_function() {
+    let code = "
+fn main() {
+  Nil
+}
+";
+
+    assert_eq!(code_lenses(TestProject::for_source(code)), code);
+}
+
+#[test]
+fn no_code_lens_for_main_function_not_supporting_the_target() {
+    let code = "
+@external(javascript, \"./ffi.mjs\", \"main\")
+pub fn main() -> Nil
+";
+
+    assert_eq!(code_lenses(TestProject::for_source(code)), code);
+}
+
+#[test]
+fn no_code_lens_for_deprecated_main_function() {
+    let code = "
+@deprecated(\"Use something else\")
+pub fn main() {
+  Nil
+}
+";
+
+    assert_eq!(code_lenses(TestProject::for_source(code)), code);
+}
+
+#[test]
+fn no_code_lens_for_internal_module() {
+    let internal = "
+pub fn main() {
+  Nil
+}
+";
+
+    let tester = TestProject::for_source("").add_module("app/internal", internal);
+    let (mut engine, _) = tester.positioned_with_io(Position::default());
+    let url = Url::from_file_path(Utf8PathBuf::from(if cfg!(target_family = "windows") {
+        r"\\?\C:\src\app\internal.gleam"
+    } else {
+        "/src/app/internal.gleam"
+    }))
+    .unwrap();
+    let params =
+        TextDocumentPositionParams::new(TextDocumentIdentifier::new(url), Position::default());
+
+    assert_eq!(code_lenses_for(internal, params, &mut engine), internal);
+}
+
+#[test]
+fn code_lens_for_tests() {
+    let code = "
+import gleeunit
+
+pub fn main() {
+  gleeunit.main()
+}
+
+pub fn addition_test() {
+  Nil
+}
+
+pub fn subtraction_test() {
+  Nil
+}
+
+fn private_test() {
+  Nil
+}
+
+pub fn helper() {
+  Nil
+}
+";
+
+    insta::assert_snapshot!(test_code_lenses(
+        TestProject::for_source("")
+            .add_hex_module("gleeunit", "pub fn main() { Nil }")
+            .add_test_module("app_test", code),
+        "app_test",
+        code
+    ));
+}
+
+#[test]
+fn no_test_code_lens_outside_of_test_modules() {
+    let code = "
+pub fn addition_test() {
+  Nil
+}
+";
+
+    assert_eq!(code_lenses(TestProject::for_source(code)), code);
+}
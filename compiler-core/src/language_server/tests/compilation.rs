@@ -137,14 +137,18 @@ fn compile_recompile() {
             // compile_please
             Action::CompilationStarted,
             Action::LockBuild,
+            Action::CompilingPackage("app".into()),
+            Action::CompilingModule("app".into()),
             Action::UnlockBuild,
             Action::CompilationFinished,
             // compile_please
             Action::CompilationStarted,
             Action::LockBuild,
+            Action::CompilingPackage("app".into()),
+            Action::CompilingModule("app".into()),
             Action::UnlockBuild,
             Action::CompilationFinished,
-            // compile_please
+            // compile_please, with nothing to compile
             Action::CompilationStarted,
             Action::LockBuild,
             Action::UnlockBuild,
@@ -197,14 +201,18 @@ fn dep_compile_recompile() {
             // compile_please
             Action::CompilationStarted,
             Action::LockBuild,
+            Action::CompilingPackage("mydep".into()),
+            Action::CompilingModule("moddy".into()),
             Action::UnlockBuild,
             Action::CompilationFinished,
             // compile_please
             Action::CompilationStarted,
             Action::LockBuild,
+            Action::CompilingPackage("mydep".into()),
+            Action::CompilingModule("moddy".into()),
             Action::UnlockBuild,
             Action::CompilationFinished,
-            // compile_please
+            // compile_please, with nothing to compile
             Action::CompilationStarted,
             Action::LockBuild,
             Action::UnlockBuild,
@@ -1905,3 +1905,111 @@ pub fn main(something: Bool) {
         Position::new(2, 9)
     );
 }
+
+#[test]
+fn completions_for_record_update_labels() {
+    let code = "
+pub type Wibble {
+  Wibble(wibble: String, wobble: Int, wubble: Float)
+}
+
+fn fun(wibble: Wibble) {
+  Wibble(..wibble, wobble: 1, )
+}
+";
+
+    assert_completion!(TestProject::for_source(code), Position::new(6, 30));
+}
+
+#[test]
+fn completions_for_imported_record_update_labels() {
+    let code = "
+import dep
+
+fn fun(wibble: dep.Wibble) {
+  dep.Wibble(..wibble, )
+}
+";
+    let dep = "
+pub type Wibble {
+  Wibble(wibble: String, wobble: Int)
+}
+";
+
+    assert_completion!(
+        TestProject::for_source(code).add_dep_module("dep", dep),
+        Position::new(4, 23)
+    );
+}
+
+#[test]
+fn completions_for_pattern_labels() {
+    let code = "
+pub type Wibble {
+  Wibble(wibble: String, wobble: Int, wubble: Float)
+}
+
+fn fun(wibble: Wibble) {
+  let Wibble(wibble:, ..) = wibble
+  wibble
+}
+";
+
+    assert_completion!(TestProject::for_source(code), Position::new(6, 21));
+}
+
+#[test]
+fn completions_for_imported_pattern_labels() {
+    let code = "
+import dep
+
+fn fun(wibble: dep.Wibble) {
+  case wibble {
+    dep.Wibble(wobble: 1, ..) -> 1
+    _ -> 2
+  }
+}
+";
+    let dep = "
+pub type Wibble {
+  Wibble(wibble: String, wobble: Int)
+}
+";
+
+    assert_completion!(
+        TestProject::for_source(code).add_dep_module("dep", dep),
+        Position::new(5, 25)
+    );
+}
+
+#[test]
+fn completions_for_type_variables_in_function_annotation() {
+    let code = "
+fn wibble(a: List(element), b: fn(x) -> y) -> Result(ok, Nil) {
+  todo
+}
+";
+
+    assert_completion!(TestProject::for_source(code), Position::new(1, 18));
+}
+
+#[test]
+fn completions_for_type_variables_in_custom_type() {
+    let code = "
+type Box(inner) {
+  Box(value: inner)
+}
+";
+
+    assert_completion!(TestProject::for_source(code), Position::new(2, 13));
+}
+
+#[test]
+fn completions_for_type_variables_in_type_alias() {
+    let code = "
+type Pair(a, b) =
+  #(a, b)
+";
+
+    assert_completion!(TestProject::for_source(code), Position::new(2, 4));
+}
@@ -0,0 +1,224 @@
+use lsp_types::{DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams};
+
+use super::*;
+
+fn document_highlights(tester: TestProject<'_>, position: Position) -> String {
+    tester.at(position, |engine, params, src| {
+        let params = DocumentHighlightParams {
+            text_document_position_params: params,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let highlights = engine.document_highlight(params).result.unwrap();
+        show_highlights(&src, position, highlights.unwrap_or_default())
+    })
+}
+
+/// Shows the source code with each highlight underlined: `w` is used for
+/// writes and `r` for reads. The position of the cursor is marked with `↑`.
+///
+fn show_highlights(src: &str, position: Position, highlights: Vec<DocumentHighlight>) -> String {
+    let mut output = String::new();
+
+    for (line_number, line) in src.lines().enumerate() {
+        let line_number = line_number as u32;
+        let underline: String = (0..line.chars().count() as u32)
+            .map(|column| {
+                let current = Position::new(line_number, column);
+                let highlight = highlights.iter().find(|highlight| {
+                    highlight.range.start <= current && current < highlight.range.end
+                });
+                match highlight.and_then(|highlight| highlight.kind) {
+                    _ if current == position => '↑',
+                    Some(DocumentHighlightKind::WRITE) => 'w',
+                    Some(_) => 'r',
+                    None => ' ',
+                }
+            })
+            .collect();
+
+        output.push_str(line);
+        output.push('\n');
+        if !underline.trim().is_empty() {
+            output.push_str(underline.trim_end());
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+#[test]
+fn highlight_local_variable() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wobble = wibble + 1
+  wibble + wobble
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("wibble +").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_pattern_variable() {
+    let code = "
+pub fn main(x) {
+  case x {
+    [first, ..] if first > 1 -> first
+    _ -> 0
+  }
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("first,").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_function_argument() {
+    let code = "
+pub fn add(first: Int, second: Int) -> Int {
+  first + second * first
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("first:").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_module_function() {
+    let code = "
+pub fn main() {
+  wibble() + wibble()
+}
+
+fn wibble() { 1 }
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("wibble()").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_imported_value() {
+    let code = "
+import wibble.{wobble}
+
+pub fn main() {
+  wobble()
+  wibble.wobble()
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code).add_module("wibble", "pub fn wobble() { Nil }"),
+        find_position_of("wobble()").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_type() {
+    let code = "
+pub type Wibble {
+  Wibble
+}
+
+pub fn main(wibble: Wibble) -> Wibble {
+  wibble
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("Wibble)").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_function_label() {
+    let code = "
+pub fn main() {
+  add(first: 1, second: 2) + add(second: 1, first: 2)
+}
+
+fn add(first a: Int, second b: Int) -> Int {
+  a + b
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("first:").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_label_from_definition() {
+    let code = "
+pub fn main() {
+  add(first: 1, second: 2)
+}
+
+fn add(first a: Int, second b: Int) -> Int {
+  a + b
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("second b").find_position(code)
+    ));
+}
+
+#[test]
+fn highlight_record_label() {
+    let code = "
+pub type Wibble {
+  Wibble(label: Int, other: Int)
+}
+
+pub fn main(wibble: Wibble) {
+  let Wibble(label:, ..) = wibble
+  let new = Wibble(label: 1, other: label)
+  Wibble(..new, label: 2)
+}
+";
+
+    insta::assert_snapshot!(document_highlights(
+        TestProject::for_source(code),
+        find_position_of("label: 1").find_position(code)
+    ));
+}
+
+#[test]
+fn no_highlight_outside_of_a_name() {
+    let code = "
+pub fn main() {
+  1 + 2
+}
+";
+
+    let position = find_position_of("1").find_position(code);
+    let highlights = TestProject::for_source(code).at(position, |engine, params, _| {
+        let params = DocumentHighlightParams {
+            text_document_position_params: params,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.document_highlight(params).result.unwrap()
+    });
+
+    assert_eq!(highlights, None);
+}
@@ -0,0 +1,158 @@
+use lsp_types::{FoldingRange, FoldingRangeParams};
+
+use super::*;
+
+fn folding_ranges(tester: TestProject<'_>) -> String {
+    tester.at(Position::default(), |engine, params, src| {
+        let params = FoldingRangeParams {
+            text_document: params.text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let ranges = engine.folding_range(params).result.unwrap();
+        show_ranges(&src, ranges)
+    })
+}
+
+/// Shows the source code followed by each folding range and the lines it
+/// would hide in the editor.
+///
+fn show_ranges(src: &str, ranges: Vec<FoldingRange>) -> String {
+    let lines = src.lines().collect_vec();
+    let mut output = format!("{src}\n");
+
+    for range in ranges {
+        let kind = range
+            .kind
+            .map(|kind| format!("{kind:?}"))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "\n----- {kind} {}..{}\n",
+            range.start_line, range.end_line
+        ));
+        for line in range.start_line..=range.end_line {
+            output.push_str(lines.get(line as usize).unwrap_or(&""));
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+#[test]
+fn folding_range_for_imports() {
+    let code = "
+import wibble
+import wobble.{type Wobble}
+import gleam/list
+
+pub fn main() {
+  Nil
+}
+";
+
+    insta::assert_snapshot!(folding_ranges(
+        TestProject::for_source(code)
+            .add_module("wibble", "")
+            .add_module("wobble", "pub type Wobble")
+            .add_module("gleam/list", "")
+    ));
+}
+
+#[test]
+fn folding_range_for_functions_and_case_clauses() {
+    let code = "
+pub fn main(x) {
+  case x {
+    1 -> {
+      let y = x + 1
+      y
+    }
+    _ ->
+      0
+  }
+}
+
+pub fn short() { 1 }
+";
+
+    insta::assert_snapshot!(folding_ranges(TestProject::for_source(code)));
+}
+
+#[test]
+fn folding_range_for_custom_types() {
+    let code = "
+pub type Wibble {
+  Wibble(
+    label: Int,
+    other: String,
+  )
+  Wobble
+}
+
+pub type Short { Short }
+";
+
+    insta::assert_snapshot!(folding_ranges(TestProject::for_source(code)));
+}
+
+#[test]
+fn folding_range_for_doc_comments() {
+    let code = "//// A module
+//// with docs
+
+/// Some
+/// documentation
+/// here
+pub fn main() {
+  Nil
+}
+
+/// Just one line
+pub fn other() {
+  Nil
+}
+";
+
+    insta::assert_snapshot!(folding_ranges(TestProject::for_source(code)));
+}
+
+#[test]
+fn folding_range_for_multiline_lists_and_records() {
+    let code = "
+pub type Wibble {
+  Wibble(first: Int, second: List(Int))
+}
+
+pub fn main() {
+  let list = [
+    1,
+    2,
+  ]
+  let wibble = Wibble(
+    first: 1,
+    second: [1, 2],
+  )
+  Wibble(..wibble,
+    first: 2,
+  )
+}
+";
+
+    insta::assert_snapshot!(folding_ranges(TestProject::for_source(code)));
+}
+
+#[test]
+fn folding_range_for_anonymous_functions() {
+    let code = "
+pub fn main() {
+  let f = fn(x) {
+    x + 1
+  }
+  use x <- f
+  x
+}
+";
+
+    insta::assert_snapshot!(folding_ranges(TestProject::for_source(code)));
+}
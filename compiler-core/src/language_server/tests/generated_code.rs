@@ -0,0 +1,204 @@
+use insta::assert_snapshot;
+use lsp_types::Position;
+
+use crate::{
+    build::Target,
+    language_server::generated_code::{GeneratedCode, GeneratedCodeParams},
+    line_numbers::LineNumbers,
+};
+
+use super::*;
+
+fn generated_code(
+    tester: TestProject<'_>,
+    target: Target,
+    position: Option<Position>,
+) -> Option<GeneratedCode> {
+    let (mut engine, params) = tester.positioned_with_io(Position::default());
+    let params = GeneratedCodeParams {
+        text_document: params.text_document,
+        target,
+        position,
+    };
+    engine.generated_code(params).result.unwrap()
+}
+
+/// Shows the generated code, followed by the part of it corresponding to the
+/// function the cursor is in.
+///
+fn show_generated_code(src: &str, target: Target, position: Option<Position>) -> String {
+    let GeneratedCode { code, range } =
+        generated_code(TestProject::for_source(src), target, position).expect("no generated code");
+
+    let mut output = format!("----- SOURCE CODE\n{src}\n\n----- GENERATED CODE\n{code}");
+    if let Some(range) = range {
+        let line_numbers = LineNumbers::new(&code);
+        let start = line_numbers.byte_index(range.start.line, range.start.character) as usize;
+        let end = line_numbers.byte_index(range.end.line, range.end.character) as usize;
+        output.push_str("\n\n----- CODE FOR THE FUNCTION UNDER THE CURSOR\n");
+        output.push_str(code.get(start..end).expect("range in generated code"));
+    }
+    output
+}
+
+#[test]
+fn generated_erlang_code() {
+    let src = "
+pub fn main() {
+  wibble(1)
+}
+
+fn wibble(n) {
+  n + 1
+}
+";
+
+    assert_snapshot!(show_generated_code(src, Target::Erlang, None));
+}
+
+#[test]
+fn generated_javascript_code() {
+    let src = "
+pub fn main() {
+  wibble(1)
+}
+
+fn wibble(n) {
+  n + 1
+}
+";
+
+    assert_snapshot!(show_generated_code(src, Target::JavaScript, None));
+}
+
+#[test]
+fn generated_erlang_code_for_function_under_cursor() {
+    let src = "
+pub fn main() {
+  wibble(1)
+}
+
+fn wibble(n) {
+  n + 1
+}
+";
+
+    let position = find_position_of("n + 1").under_char('+').find_position(src);
+    assert_snapshot!(show_generated_code(src, Target::Erlang, Some(position)));
+}
+
+#[test]
+fn generated_javascript_code_for_function_under_cursor() {
+    let src = "
+pub fn main() {
+  wibble(1)
+}
+
+fn wibble(n) {
+  n + 1
+}
+";
+
+    let position = find_position_of("wibble(1)")
+        .under_char('w')
+        .find_position(src);
+    assert_snapshot!(show_generated_code(src, Target::JavaScript, Some(position)));
+}
+
+#[test]
+fn generated_javascript_code_for_function_with_escaped_name() {
+    let src = "
+pub fn class() {
+  1
+}
+
+pub fn main() {
+  class()
+}
+";
+
+    let position = find_position_of("1").under_char('1').find_position(src);
+    assert_snapshot!(show_generated_code(src, Target::JavaScript, Some(position)));
+}
+
+#[test]
+fn no_generated_range_outside_of_functions() {
+    let src = "
+pub const wibble = 1
+
+pub fn main() {
+  wibble
+}
+";
+
+    let position = find_position_of("wibble")
+        .under_char('w')
+        .find_position(src);
+    let code = generated_code(TestProject::for_source(src), Target::Erlang, Some(position))
+        .expect("no generated code");
+    assert_eq!(code.range, None);
+}
+
+#[test]
+fn no_generated_code_for_module_with_errors() {
+    let src = "
+pub fn main() {
+  1 + \"wibble\"
+}
+";
+
+    let code = generated_code(TestProject::for_source(src), Target::Erlang, None);
+    assert_eq!(code, None);
+}
+
+#[test]
+fn generated_erlang_code_for_function_on_the_same_line_as_another() {
+    let src = "
+pub fn main() { wibble(1) } fn wibble(n) { n + 1 }
+";
+
+    let position = find_position_of("n + 1").under_char('+').find_position(src);
+    assert_snapshot!(show_generated_code(src, Target::Erlang, Some(position)));
+}
+
+#[test]
+fn generated_javascript_code_uses_indirect_standard_library() {
+    let src = "
+pub fn main() {
+  echo 1
+}
+";
+
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = TestProject::for_source(src).build_engine(&mut io);
+    _ = io.hex_dep_module("gleam_stdlib", "gleam/dict", "pub type Dict(key, value)");
+    let toml_path = engine.paths.build_packages_package_config("gleam_stdlib");
+    write_toml_from_manifest(
+        &mut engine,
+        toml_path,
+        ManifestPackage {
+            name: "gleam_stdlib".into(),
+            source: ManifestPackageSource::Hex {
+                outer_checksum: Base16Checksum(vec![]),
+            },
+            build_tools: vec!["gleam".into()],
+            ..Default::default()
+        },
+    );
+    _ = io.src_module("app", src);
+    assert!(engine.compile_please().result.is_ok());
+
+    let params = GeneratedCodeParams {
+        text_document: TestProject::for_source(src)
+            .build_path(Position::default())
+            .text_document,
+        target: Target::JavaScript,
+        position: None,
+    };
+    let GeneratedCode { code, .. } = engine
+        .generated_code(params)
+        .result
+        .unwrap()
+        .expect("no generated code");
+    assert!(code.contains("stdlib$dict"));
+}
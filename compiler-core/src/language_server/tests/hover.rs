@@ -1312,6 +1312,75 @@ pub fn main() {
     );
 }
 
+#[test]
+fn hover_for_documented_label_in_expression() {
+    let code = "
+/// Adds two numbers.
+///
+/// - `wibble`: the first number.
+/// - `wobble`: the second number.
+fn add(wibble a, wobble b) {
+  a + b
+}
+
+pub fn main() {
+  add(wibble: 1, wobble: 2)
+}
+";
+
+    assert_hover!(
+        TestProject::for_source(code),
+        find_position_of("wobble: 2").under_char('o')
+    );
+}
+
+#[test]
+fn hover_for_documented_label_of_function_in_other_module() {
+    let code = "
+import example
+
+pub fn main() {
+  example.add(wibble: 1, wobble: 2)
+}
+";
+
+    assert_hover!(
+        TestProject::for_source(code).add_dep_module(
+            "example",
+            "
+/// - `wibble`: the first number.
+pub fn add(wibble a, wobble b) {
+  a + b
+}
+"
+        ),
+        find_position_of("wibble: 1").under_char('i')
+    );
+}
+
+#[test]
+fn hover_for_documented_label_in_pattern() {
+    let code = "
+type Wibble {
+  Wibble(
+    /// The wibble of a wibble.
+    wibble: Int,
+    wobble: Int,
+  )
+}
+
+pub fn main() {
+  let Wibble(wibble: _, wobble: _) = todo
+  todo
+}
+";
+
+    assert_hover!(
+        TestProject::for_source(code),
+        find_position_of("wibble: _").under_char('l')
+    );
+}
+
 #[test]
 fn hover_for_pattern_in_use() {
     let code = "
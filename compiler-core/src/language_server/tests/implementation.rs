@@ -0,0 +1,434 @@
+use lsp_types::{GotoDefinitionParams, Location, Range};
+
+use super::*;
+
+fn implementation(
+    project: &TestProject<'_>,
+    native_files: &[(&str, &str)],
+    position: Position,
+) -> Vec<Location> {
+    let mut io = LanguageServerTestIO::new();
+    let mut engine = project.build_engine(&mut io);
+
+    for (path, code) in native_files {
+        io.module(&io.paths.root().join(path), code);
+    }
+    _ = io.src_module("app", project.src);
+    let response = engine.compile_please();
+    assert!(response.result.is_ok());
+
+    let params = GotoDefinitionParams {
+        text_document_position_params: project.build_path(position),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    engine.goto_implementation(params).result.unwrap()
+}
+
+fn pretty_implementation(
+    project: TestProject<'_>,
+    native_files: &[(&str, &str)],
+    position_finder: PositionFinder,
+) -> String {
+    let position = position_finder.find_position(project.src);
+    let locations = implementation(&project, native_files, position);
+
+    let src = hover::show_hover(
+        project.src,
+        Range {
+            start: position,
+            end: position,
+        },
+        position,
+    );
+
+    let destinations = locations
+        .iter()
+        .map(|location| {
+            let pretty_destination = location
+                .uri
+                .path_segments()
+                .expect("a location to jump to")
+                // To make snapshots the same both on windows and unix systems we need
+                // to discard windows' `C:` path segment at the beginning of a uri.
+                .skip_while(|segment| *segment == "C:" || segment.is_empty())
+                .join("/");
+
+            let code = native_files
+                .iter()
+                .find(|(path, _)| *path == pretty_destination)
+                .map(|(_, code)| *code)
+                .expect("a native file to jump to");
+
+            let destination_code = hover::show_hover(code, location.range, location.range.start);
+            format!(
+                "----- Jumped to `{pretty_destination}`
+{destination_code}"
+            )
+        })
+        .join("\n\n");
+
+    format!(
+        "----- Jumping from `src/app.gleam`
+{src}
+{destinations}",
+    )
+}
+
+macro_rules! assert_implementation {
+    ($src:literal, $native_files:expr, $position:expr $(,)?) => {
+        let project = TestProject::for_source($src);
+        assert_implementation!(project, $native_files, $position);
+    };
+    ($project:expr, $native_files:expr, $position:expr $(,)?) => {
+        let output = pretty_implementation($project, $native_files, $position);
+        insta::assert_snapshot!(insta::internals::AutoName, output);
+    };
+}
+
+macro_rules! assert_no_implementation {
+    ($src:literal, $native_files:expr, $position:expr $(,)?) => {
+        let project = TestProject::for_source($src);
+        let position = $position.find_position(project.src);
+        let locations = implementation(&project, $native_files, position);
+        assert_eq!(locations, vec![]);
+    };
+}
+
+const ERLANG_FFI: &str = "-module(my_ffi).
+-export([wibble/0, wobble/1]).
+
+wibble() ->
+    ok.
+
+wobble(X) ->
+    X.
+";
+
+const JAVASCRIPT_FFI: &str = "import { Ok } from \"./gleam.mjs\";
+
+export function wibble() {
+  return undefined;
+}
+
+export const wobble = (x) => x;
+";
+
+#[test]
+fn goto_erlang_implementation_of_called_function() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "wobble")
+fn wobble(x: Int) -> Int
+
+pub fn main() {
+  wobble(1)
+}
+"#,
+        &[("src/my_ffi.erl", ERLANG_FFI)],
+        find_position_of("wobble(1)"),
+    );
+}
+
+#[test]
+fn goto_erlang_implementation_from_function_definition() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "wibble")
+pub fn wibble() -> Nil
+"#,
+        &[("src/my_ffi.erl", ERLANG_FFI)],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn goto_erlang_implementation_in_nested_directory() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "wibble")
+pub fn wibble() -> Nil
+"#,
+        &[("src/app/internal/my_ffi.erl", ERLANG_FFI)],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn goto_erlang_implementation_in_test_directory() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "wibble")
+pub fn wibble() -> Nil
+"#,
+        &[("test/my_ffi.erl", ERLANG_FFI)],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn goto_erlang_implementation_with_quoted_name() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "receive")
+pub fn do_receive() -> Nil
+"#,
+        &[(
+            "src/my_ffi.erl",
+            "-module(my_ffi).
+-export(['receive'/0]).
+
+'receive'() ->
+    ok.
+"
+        )],
+        find_position_of("do_receive"),
+    );
+}
+
+#[test]
+fn goto_erlang_implementation_falls_back_to_export() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "wibble")
+pub fn wibble() -> Nil
+"#,
+        &[(
+            "src/my_ffi.erl",
+            "-module(my_ffi).
+-export([wobble/0, wibble/0]).
+
+-define(WIBBLE, wibble).
+?WIBBLE() -> ok.
+"
+        )],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn goto_erlang_implementation_falls_back_to_start_of_file() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "wabble")
+pub fn wabble() -> Nil
+"#,
+        &[("src/my_ffi.erl", ERLANG_FFI)],
+        find_position_of("wabble()"),
+    );
+}
+
+#[test]
+fn goto_javascript_implementation_of_function() {
+    assert_implementation!(
+        r#"
+@external(javascript, "./my_ffi.mjs", "wibble")
+pub fn wibble() -> Nil {
+  Nil
+}
+"#,
+        &[("src/my_ffi.mjs", JAVASCRIPT_FFI)],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn goto_javascript_implementation_of_constant() {
+    assert_implementation!(
+        r#"
+@external(javascript, "./my_ffi.mjs", "wobble")
+pub fn wobble(x: Int) -> Int {
+  x
+}
+"#,
+        &[("src/my_ffi.mjs", JAVASCRIPT_FFI)],
+        find_position_of("wobble("),
+    );
+}
+
+#[test]
+fn goto_javascript_implementation_in_parent_directory() {
+    let src = r#"
+import app/wibble
+
+pub fn main() {
+  wibble.wibble()
+}
+"#;
+
+    assert_implementation!(
+        TestProject::for_source(src).add_module(
+            "app/wibble",
+            r#"
+@external(erlang, "my_ffi", "wibble")
+@external(javascript, "../my_ffi.mjs", "wibble")
+pub fn wibble() -> Nil
+"#
+        ),
+        &[("src/my_ffi.mjs", JAVASCRIPT_FFI)],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn goto_implementation_for_both_targets() {
+    assert_implementation!(
+        r#"
+@external(erlang, "my_ffi", "wibble")
+@external(javascript, "./my_ffi.mjs", "wibble")
+pub fn wibble() -> Nil
+
+pub fn main() {
+  wibble()
+}
+"#,
+        &[
+            ("src/my_ffi.mjs", JAVASCRIPT_FFI),
+            ("src/my_ffi.erl", ERLANG_FFI),
+        ],
+        find_position_of("wibble()").nth_occurrence(2),
+    );
+}
+
+#[test]
+fn goto_implementation_in_other_module() {
+    let src = "
+import wibble
+
+pub fn main() {
+  wibble.wibble()
+}
+";
+
+    assert_implementation!(
+        TestProject::for_source(src).add_module(
+            "wibble",
+            r#"
+@external(erlang, "my_ffi", "wibble")
+pub fn wibble() -> Nil
+"#
+        ),
+        &[("src/my_ffi.erl", ERLANG_FFI)],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn goto_implementation_in_hex_dependency() {
+    let src = "
+import wibble.{wibble}
+
+pub fn main() {
+  wibble()
+}
+";
+
+    assert_implementation!(
+        TestProject::for_source(src).add_hex_module(
+            "wibble",
+            r#"
+@external(erlang, "wibble_ffi", "wibble")
+@external(javascript, "./wibble_ffi.mjs", "wibble")
+pub fn wibble() -> Nil
+"#
+        ),
+        &[
+            ("build/packages/hex/src/wibble_ffi.erl", ERLANG_FFI),
+            ("build/packages/hex/src/wibble_ffi.mjs", JAVASCRIPT_FFI),
+        ],
+        find_position_of("wibble()"),
+    );
+}
+
+#[test]
+fn no_implementation_for_dependency_test_directory() {
+    let src = "
+import wibble
+
+pub fn main() {
+  wibble.wibble()
+}
+";
+
+    let project = TestProject::for_source(src).add_hex_module(
+        "wibble",
+        r#"
+@external(erlang, "wibble_ffi", "wibble")
+pub fn wibble() -> Nil
+"#,
+    );
+    let position = find_position_of("wibble()").find_position(src);
+    let locations = implementation(
+        &project,
+        &[("build/packages/hex/test/wibble_ffi.erl", ERLANG_FFI)],
+        position,
+    );
+    assert_eq!(locations, vec![]);
+}
+
+#[test]
+fn no_implementation_for_erlang_standard_library() {
+    assert_no_implementation!(
+        r#"
+@external(erlang, "erlang", "self")
+pub fn self() -> Nil
+"#,
+        &[],
+        find_position_of("self()"),
+    );
+}
+
+#[test]
+fn no_implementation_for_javascript_package() {
+    assert_no_implementation!(
+        r#"
+@external(javascript, "node:process", "exit")
+pub fn exit() -> Nil {
+  Nil
+}
+"#,
+        &[],
+        find_position_of("exit()"),
+    );
+}
+
+#[test]
+fn no_implementation_for_gleam_function() {
+    assert_no_implementation!(
+        r#"
+pub fn wibble() -> Nil {
+  Nil
+}
+
+pub fn main() {
+  wibble()
+}
+"#,
+        &[("src/my_ffi.erl", ERLANG_FFI)],
+        find_position_of("wibble()").nth_occurrence(2),
+    );
+}
+
+#[test]
+fn goto_implementation_location_is_a_file_url() {
+    let project = TestProject::for_source(
+        r#"
+@external(erlang, "my_ffi", "wibble")
+pub fn wibble() -> Nil
+"#,
+    );
+    let position = find_position_of("wibble()").find_position(project.src);
+    let path = "src/native code/my_ffi.erl";
+    let locations = implementation(&project, &[(path, ERLANG_FFI)], position);
+
+    let expected = LanguageServerTestIO::new().paths.root().join(path);
+    let uris = locations
+        .into_iter()
+        .map(|location| location.uri)
+        .collect_vec();
+    assert_eq!(
+        uris,
+        vec![crate::language_server::url_from_path(expected.as_str()).unwrap()]
+    );
+    assert!(uris[0].as_str().ends_with("/src/native%20code/my_ffi.erl"));
+}
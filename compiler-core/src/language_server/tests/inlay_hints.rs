@@ -0,0 +1,246 @@
+use lsp_types::{InlayHintLabel, InlayHintParams, Range};
+
+use crate::language_server::configuration::{Configuration, InlayHintsConfig};
+
+use super::*;
+
+fn inlay_hints(tester: TestProject<'_>, config: InlayHintsConfig) -> String {
+    tester.at(Position::default(), |engine, params, src| {
+        let params = InlayHintParams {
+            text_document: params.text_document,
+            range: Range::new(Position::new(0, 0), Position::new(u32::MAX, 0)),
+            work_done_progress_params: Default::default(),
+        };
+        let hints = engine.inlay_hints(params, config).result.unwrap();
+        show_hints(&src, hints)
+    })
+}
+
+/// Shows the source code with each hint inserted where the editor would
+/// display it, surrounded by square brackets.
+///
+fn show_hints(src: &str, hints: Vec<lsp_types::InlayHint>) -> String {
+    let line_numbers = LineNumbers::new(src);
+    let mut output = src.to_string();
+
+    for hint in hints.into_iter().rev() {
+        let InlayHintLabel::String(label) = hint.label else {
+            panic!("Expected a string label");
+        };
+        let index = line_numbers.byte_index(hint.position.line, hint.position.character);
+        output.insert_str(index as usize, &format!("[{label}]"));
+    }
+
+    output
+}
+
+const ALL_HINTS: InlayHintsConfig = InlayHintsConfig {
+    let_bindings: true,
+    pipelines: true,
+    function_returns: true,
+};
+
+#[test]
+fn inlay_hints_for_let_bindings() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let #(wobble, wubble) = #(1.0, \"wubble\")
+  let assert [first, ..] = [wibble]
+  let annotated: Int = first
+  wobble
+}
+";
+
+    insta::assert_snapshot!(inlay_hints(TestProject::for_source(code), ALL_HINTS));
+}
+
+#[test]
+fn inlay_hints_for_function_returns() {
+    let code = "
+pub fn main() {
+  let add = fn(a, b) { a + b }
+  add(1, 2)
+}
+
+pub fn annotated() -> Int {
+  1
+}
+
+pub fn identity(x) {
+  x
+}
+";
+
+    insta::assert_snapshot!(inlay_hints(TestProject::for_source(code), ALL_HINTS));
+}
+
+#[test]
+fn inlay_hints_name_type_variables_separately_for_each_function() {
+    let code = "
+pub fn first(x) {
+  x
+}
+
+pub fn second(x, y) {
+  let pair = #(y, x)
+  pair
+}
+";
+
+    insta::assert_snapshot!(inlay_hints(TestProject::for_source(code), ALL_HINTS));
+}
+
+#[test]
+fn inlay_hints_for_multiline_pipelines() {
+    let code = "
+pub fn main() {
+  [1, 2, 3]
+  |> map(fn(x: Int) -> Float { todo })
+  |> length
+}
+
+fn map(list: List(a), fun: fn(a) -> b) -> List(b) {
+  todo
+}
+
+fn length(list: List(a)) -> Int {
+  todo
+}
+";
+
+    insta::assert_snapshot!(inlay_hints(TestProject::for_source(code), ALL_HINTS));
+}
+
+#[test]
+fn inlay_hints_for_pipelines_show_last_step_on_each_line() {
+    let code = "
+pub fn main() -> Int {
+  1 |> add(2)
+  |> add(3) |> add(4)
+}
+
+fn add(a: Int, b: Int) -> Int {
+  a + b
+}
+";
+
+    insta::assert_snapshot!(inlay_hints(TestProject::for_source(code), ALL_HINTS));
+}
+
+#[test]
+fn no_inlay_hints_for_single_line_pipelines() {
+    let code = "
+pub fn main() -> Int {
+  1 |> add(2) |> add(3)
+}
+
+fn add(a: Int, b: Int) -> Int {
+  a + b
+}
+";
+
+    assert_eq!(inlay_hints(TestProject::for_source(code), ALL_HINTS), code);
+}
+
+#[test]
+fn inlay_hints_use_import_aliases() {
+    let code = "
+import wibble.{type Wibble as Wobble} as wubble
+
+pub fn main() {
+  let one = wubble.wibble()
+  let other = wubble.other()
+  #(one, other)
+}
+";
+
+    insta::assert_snapshot!(inlay_hints(
+        TestProject::for_source(code).add_module(
+            "wibble",
+            "pub type Wibble { Wibble }
+pub type Other { Other }
+pub fn wibble() -> Wibble { Wibble }
+pub fn other() -> Other { Other }"
+        ),
+        ALL_HINTS
+    ));
+}
+
+#[test]
+fn inlay_hints_can_be_disabled() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  wibble
+  |> add(1)
+  |> add(2)
+}
+
+fn add(a: Int, b: Int) {
+  a + b
+}
+";
+
+    insta::assert_snapshot!(inlay_hints(
+        TestProject::for_source(code),
+        InlayHintsConfig {
+            let_bindings: false,
+            pipelines: true,
+            function_returns: false,
+        }
+    ));
+}
+
+#[test]
+fn inlay_hints_are_limited_to_the_requested_range() {
+    let code = "
+pub fn main() {
+  let wibble = 1
+  let wobble = 2
+  wibble + wobble
+}
+";
+
+    let hints = TestProject::for_source(code).at(Position::default(), |engine, params, _| {
+        let params = InlayHintParams {
+            text_document: params.text_document,
+            range: Range::new(Position::new(3, 0), Position::new(4, 0)),
+            work_done_progress_params: Default::default(),
+        };
+        engine.inlay_hints(params, ALL_HINTS).result.unwrap()
+    });
+
+    assert_eq!(hints.len(), 1);
+    assert_eq!(hints.first().unwrap().position, Position::new(3, 12));
+}
+
+#[test]
+fn inlay_hints_configuration_from_initialisation_options() {
+    let options = serde_json::json!({
+        "inlayHints": { "pipelines": false }
+    });
+
+    assert_eq!(
+        Configuration::from_initialisation_options(Some(&options)).inlay_hints,
+        InlayHintsConfig {
+            let_bindings: true,
+            pipelines: false,
+            function_returns: true,
+        }
+    );
+}
+
+#[test]
+fn inlay_hints_configuration_defaults_when_options_are_invalid() {
+    let options = serde_json::json!({ "inlayHints": 1 });
+
+    assert_eq!(
+        Configuration::from_initialisation_options(Some(&options)),
+        Configuration::default()
+    );
+    assert_eq!(
+        Configuration::from_initialisation_options(None),
+        Configuration::default()
+    );
+}
@@ -0,0 +1,637 @@
+use std::collections::HashMap;
+
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{
+    CodeAction, CodeActionContext, CodeActionParams, DocumentChangeOperation, DocumentChanges,
+    OneOf, PartialResultParams, Position, Range, ResourceOp, TextEdit, Url, WorkDoneProgressParams,
+};
+
+use crate::language_server::move_definition::{MoveDefinitionArguments, MoveOutcome};
+
+use super::{TestProject, find_position_of};
+
+fn move_definition(tester: &TestProject<'_>, position: Position, module: &str) -> MoveOutcome {
+    move_definition_to(tester, position, Some(module.into()))
+}
+
+fn move_definition_to(
+    tester: &TestProject<'_>,
+    position: Position,
+    module: Option<EcoString>,
+) -> MoveOutcome {
+    tester
+        .at(position, |engine, params, _| {
+            let arguments = MoveDefinitionArguments {
+                uri: params.text_document.uri,
+                position,
+                module,
+            };
+            engine.move_definition(arguments).result.unwrap()
+        })
+        .expect("No definition to move")
+}
+
+/// The code of each module after applying the edit, by module name.
+///
+pub fn apply_edit(
+    tester: &TestProject<'_>,
+    edit: lsp_types::WorkspaceEdit,
+) -> HashMap<String, String> {
+    let mut changes: HashMap<Url, Vec<TextEdit>> = edit.changes.unwrap_or_default();
+    let mut created = vec![];
+    match edit.document_changes {
+        Some(DocumentChanges::Operations(operations)) => {
+            for operation in operations {
+                match operation {
+                    DocumentChangeOperation::Op(ResourceOp::Create(create)) => {
+                        created.push(create.uri)
+                    }
+                    DocumentChangeOperation::Op(_) => panic!("Unexpected resource operation"),
+                    DocumentChangeOperation::Edit(edit) => changes
+                        .entry(edit.text_document.uri)
+                        .or_default()
+                        .extend(edit.edits.into_iter().map(|edit| match edit {
+                            OneOf::Left(edit) => edit,
+                            OneOf::Right(annotated) => annotated.text_edit,
+                        })),
+                }
+            }
+        }
+        Some(DocumentChanges::Edits(_)) => panic!("Unexpected document edits"),
+        None => {}
+    }
+
+    changes
+        .into_iter()
+        .map(|(uri, edits)| {
+            let module_name = tester.module_name_from_url(&uri).expect("Valid uri");
+            let code = match tester.src_from_module_url(&uri) {
+                Some(code) => code,
+                None if created.contains(&uri) => "",
+                None => panic!("Edit to unknown module {module_name}"),
+            };
+            (module_name, super::apply_code_edit(code, edits))
+        })
+        .collect()
+}
+
+pub fn show_modules(output: &mut String, modules: impl Iterator<Item = (String, String)>) {
+    for (name, code) in modules.sorted() {
+        output.push_str(&format!("-- {name}.gleam\n{code}\n\n"));
+    }
+}
+
+macro_rules! assert_move {
+    ($code:literal, $position:expr, $module:literal $(,)?) => {
+        assert_move!(TestProject::for_source($code), $position, $module);
+    };
+
+    ($project:expr, $position:expr, $module:literal $(,)?) => {
+        let project = $project;
+        let src = project.src;
+        let position = $position.find_position(src);
+        let MoveOutcome::Moved {
+            edit,
+            private_items,
+        } = move_definition(&project, position, $module)
+        else {
+            panic!("The definition was not moved")
+        };
+        let result = apply_edit(&project, edit);
+
+        let before = project
+            .root_package_modules
+            .iter()
+            .map(|(name, code)| (name.to_string(), code.to_string()))
+            .chain(std::iter::once(("app".to_string(), src.to_string())))
+            .collect_vec();
+        let mut after = before.iter().cloned().collect::<HashMap<_, _>>();
+        after.extend(result);
+
+        let mut output = String::from("----- BEFORE MOVE\n");
+        show_modules(&mut output, before.into_iter());
+        output.push_str("----- AFTER MOVE\n");
+        show_modules(&mut output, after.into_iter());
+        if !private_items.is_empty() {
+            output.push_str(&format!(
+                "----- PRIVATE ITEMS\n{}\n",
+                private_items.iter().join(", ")
+            ));
+        }
+
+        insta::assert_snapshot!(insta::internals::AutoName, output, src);
+    };
+}
+
+macro_rules! assert_move_refused {
+    ($project:expr, $position:expr, $module:literal, $reason:literal $(,)?) => {
+        let project = $project;
+        let position = $position.find_position(project.src);
+        assert_eq!(
+            move_definition(&project, position, $module),
+            MoveOutcome::Refused {
+                reason: $reason.into()
+            }
+        );
+    };
+}
+
+fn move_actions(tester: TestProject<'_>, range: Range) -> Vec<CodeAction> {
+    tester.at(Position::default(), |engine, params, _| {
+        let params = CodeActionParams {
+            text_document: params.text_document,
+            range,
+            context: CodeActionContext::default(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        };
+        engine
+            .code_actions(params)
+            .result
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .filter(|action| action.title.starts_with("Move "))
+            .collect()
+    })
+}
+
+fn move_action_titles(tester: TestProject<'_>, range: Range) -> Vec<String> {
+    move_actions(tester, range)
+        .into_iter()
+        .map(|action| action.title)
+        .collect()
+}
+
+#[test]
+fn move_definition_code_actions() {
+    let src = "
+pub fn wibble() {
+  1
+}
+";
+    let project = TestProject::for_source(src)
+        .add_module("app/wobble", "")
+        .add_module("app/other", "");
+    let range = find_position_of("wibble").to_selection().find_range(src);
+
+    assert_eq!(move_action_titles(project, range), vec!["Move definition…"]);
+}
+
+#[test]
+fn move_definition_code_action_leaves_destination_to_the_client() {
+    let src = "
+pub fn wibble() {
+  1
+}
+";
+    let project = TestProject::for_source(src).add_module("app/wobble", "");
+    let range = find_position_of("wibble").to_selection().find_range(src);
+
+    let command = move_actions(project, range)
+        .into_iter()
+        .next()
+        .and_then(|action| action.command)
+        .expect("No move definition code action");
+    let arguments = command
+        .arguments
+        .into_iter()
+        .flatten()
+        .map(|argument| serde_json::from_value::<MoveDefinitionArguments>(argument).unwrap())
+        .map(|arguments| (arguments.position, arguments.module))
+        .collect_vec();
+
+    assert_eq!(command.command, "gleam.promptMoveDefinition");
+    assert_eq!(arguments, vec![(Position::new(1, 0), None)]);
+}
+
+#[test]
+fn move_definition_without_destination_is_refused() {
+    let src = "
+pub fn wibble() {
+  1
+}
+";
+    let project = TestProject::for_source(src);
+    let position = find_position_of("wibble").find_position(src);
+
+    assert_eq!(
+        move_definition_to(&project, position, None),
+        MoveOutcome::Refused {
+            reason: "Choose the module to move `wibble` to".into()
+        }
+    );
+}
+
+#[test]
+fn no_move_definition_code_actions_inside_body() {
+    let src = "
+pub fn wibble() {
+  1
+}
+";
+    let project = TestProject::for_source(src).add_module("app/wobble", "");
+    let range = find_position_of("1").to_selection().find_range(src);
+
+    assert_eq!(move_action_titles(project, range), Vec::<String>::new());
+}
+
+#[test]
+fn move_private_function() {
+    assert_move!(
+        TestProject::for_source(
+            "
+fn wibble() {
+  1
+}
+
+pub fn main() {
+  2
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_with_documentation_and_attributes() {
+    assert_move!(
+        TestProject::for_source(
+            "
+/// Some documentation
+@deprecated(\"Use something else\")
+pub fn wibble() {
+  1
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_updates_qualified_references() {
+    assert_move!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  1
+}
+
+pub fn other() {
+  2
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n")
+        .add_module(
+            "app/user",
+            "import app
+
+pub fn main() {
+  app.wibble() + app.other()
+}
+"
+        ),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_removes_import_only_used_for_it() {
+    assert_move!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  1
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n")
+        .add_module(
+            "app/user",
+            "import app
+import gleam
+
+pub fn main() {
+  app.wibble()
+}
+"
+        ),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_updates_unqualified_imports() {
+    assert_move!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  1
+}
+
+pub fn other() {
+  2
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n")
+        .add_module(
+            "app/user",
+            "import app.{other, wibble as w}
+import app/wobble.{wobble}
+
+pub fn main() {
+  w() + other() + wobble()
+}
+"
+        ),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_used_by_its_module() {
+    assert_move!(
+        TestProject::for_source(
+            "
+fn wibble() {
+  1
+}
+
+pub fn main() {
+  wibble()
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_using_other_definitions_of_its_module() {
+    assert_move!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  helper() + public()
+}
+
+fn helper() {
+  1
+}
+
+pub fn public() {
+  2
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_adds_needed_imports() {
+    assert_move!(
+        TestProject::for_source(
+            "
+import app/one.{type One, one}
+import app/two as other
+
+pub fn wibble(x: One) -> Int {
+  one(x) + other.two()
+}
+"
+        )
+        .add_module(
+            "app/one",
+            "pub type One { One }\npub fn one(x: One) { 1 }\n"
+        )
+        .add_module("app/two", "pub fn two() { 2 }\n")
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_extends_existing_imports() {
+    assert_move!(
+        TestProject::for_source(
+            "
+import app/one.{type One, one}
+import app/two
+
+pub fn wibble(x: One) -> Int {
+  one(x) + two.two()
+}
+"
+        )
+        .add_module(
+            "app/one",
+            "pub type One { One }\npub fn one(x: One) { 1 }\n"
+        )
+        .add_module("app/two", "pub fn two() { 2 }\n")
+        .add_module(
+            "app/wobble",
+            "import app/one
+import app/two as too
+
+pub fn wobble() { one.One too.two() }
+"
+        ),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_using_destination_module() {
+    assert_move!(
+        TestProject::for_source(
+            "
+import app/wobble
+
+pub fn wibble() {
+  wobble.wobble()
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_used_by_destination_module() {
+    assert_move!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  1
+}
+"
+        )
+        .add_module(
+            "app/wobble",
+            "import app.{wibble}
+
+pub fn wobble() { wibble() }
+"
+        ),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_custom_type_with_constructors() {
+    assert_move!(
+        TestProject::for_source(
+            "
+pub type Wibble {
+  Wibble(Int)
+  Wobble
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n")
+        .add_module(
+            "app/user",
+            "import app.{type Wibble, Wibble}
+
+pub fn main() -> Wibble {
+  case Wibble(1) {
+    Wibble(_) -> app.Wobble
+    app.Wobble -> Wibble(2)
+  }
+}
+"
+        ),
+        find_position_of("Wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_constant() {
+    assert_move!(
+        TestProject::for_source(
+            "
+pub const wibble = 1
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n")
+        .add_module(
+            "app/user",
+            "import app
+
+pub fn main() {
+  app.wibble
+}
+"
+        ),
+        find_position_of("wibble"),
+        "app/wobble"
+    );
+}
+
+#[test]
+fn move_function_to_new_module() {
+    assert_move!(
+        TestProject::for_source(
+            "
+import app/one
+
+pub fn wibble() {
+  one.one()
+}
+"
+        )
+        .add_module("app/one", "pub fn one() { 1 }\n")
+        .add_module(
+            "app/user",
+            "import app
+
+pub fn main() {
+  app.wibble()
+}
+"
+        ),
+        find_position_of("wibble"),
+        "app/new"
+    );
+}
+
+#[test]
+fn move_function_creating_import_cycle_is_refused() {
+    assert_move_refused!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  helper()
+}
+
+pub fn helper() {
+  1
+}
+
+pub fn main() {
+  wibble()
+}
+"
+        )
+        .add_module("app/wobble", "pub fn wobble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble",
+        "Moving `wibble` to `app/wobble` would create an import cycle"
+    );
+}
+
+#[test]
+fn move_function_to_module_defining_the_same_name_is_refused() {
+    assert_move_refused!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  1
+}
+"
+        )
+        .add_module("app/wobble", "fn wibble() { 1 }\n"),
+        find_position_of("wibble"),
+        "app/wobble",
+        "`app/wobble` already defines `wibble`"
+    );
+}
+
+#[test]
+fn move_function_to_invalid_module_is_refused() {
+    assert_move_refused!(
+        TestProject::for_source(
+            "
+pub fn wibble() {
+  1
+}
+"
+        ),
+        find_position_of("wibble"),
+        "app/Wobble",
+        "`app/Wobble` is not a valid module name"
+    );
+}
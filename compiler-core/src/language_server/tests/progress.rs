@@ -0,0 +1,72 @@
+use lsp_server::Connection;
+use lsp_types::{
+    InitializeParams, NumberOrString, ProgressParams, ProgressParamsValue, WorkDoneProgress,
+    WorkDoneProgressCreateParams,
+};
+
+use crate::language_server::progress::{ConnectionProgressReporter, ProgressReporter};
+
+/// The token of each progress related message sent to the client, along with
+/// either the method of the request creating it or the kind of progress
+/// reported with it.
+fn progress_messages(client: &Connection) -> Vec<(NumberOrString, String)> {
+    client
+        .receiver
+        .try_iter()
+        .map(|message| match message {
+            lsp_server::Message::Request(request) => {
+                let params: WorkDoneProgressCreateParams =
+                    serde_json::from_value(request.params).unwrap();
+                (params.token, request.method)
+            }
+            lsp_server::Message::Notification(notification) => {
+                let params: ProgressParams = serde_json::from_value(notification.params).unwrap();
+                let kind = match params.value {
+                    ProgressParamsValue::WorkDone(work_done) => match work_done {
+                        WorkDoneProgress::Begin(_) => "begin",
+                        WorkDoneProgress::Report(_) => "report",
+                        WorkDoneProgress::End(_) => "end",
+                    },
+                };
+                (params.token, kind.into())
+            }
+            lsp_server::Message::Response(_) => panic!("unexpected response"),
+        })
+        .collect()
+}
+
+#[test]
+fn each_compilation_and_download_reports_progress_with_a_new_token() {
+    let (server, client) = Connection::memory();
+    let reporter = ConnectionProgressReporter::new(&server, &InitializeParams::default());
+
+    reporter.compilation_started();
+    reporter.compiling_module("app");
+    reporter.compilation_finished();
+    reporter.compilation_started();
+    reporter.compilation_finished();
+    reporter.dependency_downloading_started();
+    reporter.dependency_downloading_finished();
+
+    let token = |name: &str| NumberOrString::String(name.into());
+    let message = |token: &NumberOrString, kind: &str| (token.clone(), kind.to_string());
+    let create = "window/workDoneProgress/create";
+    let first = token("compiling-gleam-1");
+    let second = token("compiling-gleam-2");
+    let download = token("downloading-dependencies-1");
+    assert_eq!(
+        progress_messages(&client),
+        vec![
+            message(&first, create),
+            message(&first, "begin"),
+            message(&first, "report"),
+            message(&first, "end"),
+            message(&second, create),
+            message(&second, "begin"),
+            message(&second, "end"),
+            message(&download, create),
+            message(&download, "begin"),
+            message(&download, "end"),
+        ]
+    );
+}
@@ -0,0 +1,336 @@
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    ClientCapabilities, DiagnosticClientCapabilities, DiagnosticWorkspaceClientCapabilities,
+    DocumentDiagnosticReport, DocumentDiagnosticReportResult, InitializeParams,
+    TextDocumentClientCapabilities, WorkspaceClientCapabilities, WorkspaceDiagnosticReportResult,
+    WorkspaceDocumentDiagnosticReport,
+};
+
+use crate::language_server::server::LanguageServer;
+
+use super::*;
+
+pub const APP_URI: &str = "file:///src/app.gleam";
+
+pub const UNUSED_VARIABLE: &str = "pub fn main() {
+  let wibble = 1
+  Nil
+}
+";
+
+pub fn unused_variable_messages() -> Vec<String> {
+    vec![
+        "Unused variable\n\nThis variable is never used.".to_string(),
+        "You can ignore it with an underscore: `_wibble`.".to_string(),
+    ]
+}
+
+fn pull_capabilities() -> ClientCapabilities {
+    ClientCapabilities {
+        text_document: Some(TextDocumentClientCapabilities {
+            diagnostic: Some(DiagnosticClientCapabilities::default()),
+            ..Default::default()
+        }),
+        workspace: Some(WorkspaceClientCapabilities {
+            diagnostic: Some(DiagnosticWorkspaceClientCapabilities {
+                refresh_support: Some(true),
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+/// A project with a single `app` module.
+pub fn project_io(code: &str) -> LanguageServerTestIO {
+    let io = LanguageServerTestIO::new();
+    io.module(
+        &io.paths.root_config(),
+        "name = \"app\"\nversion = \"1.0.0\"\n",
+    );
+    let _ = io.src_module("app", code);
+    io
+}
+
+/// Sends the messages of a whole session from the client: the server is
+/// initialised, handles the given messages and is then shut down.
+pub fn send_session(client: &Connection, capabilities: ClientCapabilities, messages: Vec<Message>) {
+    let params = InitializeParams {
+        capabilities,
+        ..Default::default()
+    };
+    let send = |message| client.sender.send(message).unwrap();
+    send(request(
+        0,
+        "initialize",
+        serde_json::to_value(params).unwrap(),
+    ));
+    send(notification("initialized", serde_json::json!({})));
+    for message in messages {
+        send(message);
+    }
+    send(request(1000, "shutdown", serde_json::Value::Null));
+    send(notification("exit", serde_json::Value::Null));
+}
+
+/// Runs a language server for a project with a single `app` module until it
+/// has handled all the given messages, returning everything it sent to the
+/// client.
+fn run_server(
+    capabilities: ClientCapabilities,
+    code: &str,
+    messages: Vec<Message>,
+) -> Vec<Message> {
+    let (server, client) = Connection::memory();
+    let messages = std::iter::once(did_open_notification(code))
+        .chain(messages)
+        .collect();
+    send_session(&client, capabilities, messages);
+
+    LanguageServer::new(&server, project_io(code))
+        .unwrap()
+        .run()
+        .unwrap();
+    client.receiver.try_iter().collect()
+}
+
+pub fn request(id: i32, method: &str, params: serde_json::Value) -> Message {
+    Message::Request(Request {
+        id: id.into(),
+        method: method.into(),
+        params,
+    })
+}
+
+pub fn notification(method: &str, params: serde_json::Value) -> Message {
+    Message::Notification(Notification {
+        method: method.into(),
+        params,
+    })
+}
+
+pub fn hover_request(id: i32) -> Message {
+    request(
+        id,
+        "textDocument/hover",
+        serde_json::json!({
+            "textDocument": { "uri": APP_URI },
+            "position": { "line": 0, "character": 0 },
+        }),
+    )
+}
+
+fn document_diagnostic_request(id: i32) -> Message {
+    request(
+        id,
+        "textDocument/diagnostic",
+        serde_json::json!({ "textDocument": { "uri": APP_URI } }),
+    )
+}
+
+pub fn did_open_notification(text: &str) -> Message {
+    notification(
+        "textDocument/didOpen",
+        serde_json::json!({
+            "textDocument": {
+                "uri": APP_URI,
+                "languageId": "gleam",
+                "version": 0,
+                "text": text,
+            },
+        }),
+    )
+}
+
+fn did_change_notification(text: &str) -> Message {
+    notification(
+        "textDocument/didChange",
+        serde_json::json!({
+            "textDocument": { "uri": APP_URI, "version": 1 },
+            "contentChanges": [{ "text": text }],
+        }),
+    )
+}
+
+fn response(sent: &[Message], id: i32) -> serde_json::Value {
+    sent.iter()
+        .find_map(|message| match message {
+            Message::Response(Response {
+                id: response_id,
+                result: Some(result),
+                ..
+            }) if *response_id == RequestId::from(id) => Some(result.clone()),
+            _ => None,
+        })
+        .expect("no response to the request")
+}
+
+fn document_diagnostic_messages(sent: &[Message], id: i32) -> Vec<String> {
+    let report: DocumentDiagnosticReportResult =
+        serde_json::from_value(response(sent, id)).unwrap();
+    let DocumentDiagnosticReportResult::Report(DocumentDiagnosticReport::Full(report)) = report
+    else {
+        panic!("expected a full diagnostic report")
+    };
+    report
+        .full_document_diagnostic_report
+        .items
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect()
+}
+
+pub fn notifications<'a>(sent: &'a [Message], method: &str) -> Vec<&'a Notification> {
+    sent.iter()
+        .filter_map(|message| match message {
+            Message::Notification(notification) if notification.method == method => {
+                Some(notification)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+pub fn requests<'a>(sent: &'a [Message], method: &str) -> Vec<&'a Request> {
+    sent.iter()
+        .filter_map(|message| match message {
+            Message::Request(request) if request.method == method => Some(request),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn document_diagnostic_returns_diagnostics_of_the_file() {
+    let sent = run_server(
+        pull_capabilities(),
+        UNUSED_VARIABLE,
+        vec![document_diagnostic_request(1)],
+    );
+
+    assert_eq!(
+        document_diagnostic_messages(&sent, 1),
+        unused_variable_messages()
+    );
+}
+
+#[test]
+fn diagnostics_are_not_published_to_clients_that_pull_them() {
+    let sent = run_server(
+        pull_capabilities(),
+        UNUSED_VARIABLE,
+        vec![document_diagnostic_request(1)],
+    );
+
+    assert!(notifications(&sent, "textDocument/publishDiagnostics").is_empty());
+    assert_eq!(requests(&sent, "workspace/diagnostic/refresh").len(), 1);
+}
+
+#[test]
+fn diagnostics_are_published_to_clients_that_do_not_pull_them() {
+    let sent = run_server(
+        ClientCapabilities::default(),
+        UNUSED_VARIABLE,
+        vec![hover_request(1)],
+    );
+
+    assert!(!notifications(&sent, "textDocument/publishDiagnostics").is_empty());
+    assert!(requests(&sent, "workspace/diagnostic/refresh").is_empty());
+}
+
+#[test]
+fn document_diagnostic_reflects_the_latest_edit() {
+    let sent = run_server(
+        pull_capabilities(),
+        UNUSED_VARIABLE,
+        vec![
+            document_diagnostic_request(1),
+            did_change_notification("pub fn main() {\n  Nil\n}\n"),
+            document_diagnostic_request(2),
+        ],
+    );
+
+    assert_eq!(
+        document_diagnostic_messages(&sent, 1),
+        unused_variable_messages()
+    );
+    assert!(document_diagnostic_messages(&sent, 2).is_empty());
+}
+
+#[test]
+fn workspace_diagnostic_returns_diagnostics_of_all_files() {
+    let sent = run_server(
+        pull_capabilities(),
+        UNUSED_VARIABLE,
+        vec![request(
+            1,
+            "workspace/diagnostic",
+            serde_json::json!({ "previousResultIds": [] }),
+        )],
+    );
+
+    let report: WorkspaceDiagnosticReportResult =
+        serde_json::from_value(response(&sent, 1)).unwrap();
+    let WorkspaceDiagnosticReportResult::Report(report) = report else {
+        panic!("expected a full diagnostic report")
+    };
+    let files = report
+        .items
+        .into_iter()
+        .map(|item| match item {
+            WorkspaceDocumentDiagnosticReport::Full(report) => (
+                report.uri.to_string(),
+                report
+                    .full_document_diagnostic_report
+                    .items
+                    .into_iter()
+                    .map(|diagnostic| diagnostic.message)
+                    .collect_vec(),
+            ),
+            WorkspaceDocumentDiagnosticReport::Unchanged(_) => {
+                panic!("expected a full diagnostic report")
+            }
+        })
+        .collect_vec();
+
+    assert_eq!(
+        files,
+        vec![(APP_URI.to_string(), unused_variable_messages())]
+    );
+}
+
+#[test]
+fn published_diagnostics_are_marked_as_stale_while_compiling() {
+    let sent = run_server(
+        ClientCapabilities::default(),
+        UNUSED_VARIABLE,
+        vec![
+            hover_request(1),
+            did_change_notification(UNUSED_VARIABLE),
+            hover_request(2),
+        ],
+    );
+
+    let data = notifications(&sent, "textDocument/publishDiagnostics")
+        .into_iter()
+        .map(|notification| {
+            let params: lsp_types::PublishDiagnosticsParams =
+                serde_json::from_value(notification.params.clone()).unwrap();
+            params
+                .diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.data)
+                .collect_vec()
+        })
+        .collect_vec();
+    let stale = Some(serde_json::json!({ "stale": true }));
+
+    assert_eq!(
+        data,
+        vec![
+            vec![None, None],
+            vec![stale.clone(), stale],
+            vec![None, None],
+        ]
+    );
+}
@@ -0,0 +1,48 @@
+use lsp_server::Connection;
+use lsp_types::{ClientCapabilities, PublishDiagnosticsParams};
+
+use crate::language_server::server::LanguageServer;
+
+use super::pull_diagnostics::{
+    APP_URI, UNUSED_VARIABLE, did_open_notification, hover_request, notifications, project_io,
+    send_session, unused_variable_messages,
+};
+
+#[test]
+fn reconnected_client_gets_diagnostics_without_compiling_again() {
+    let (server, client) = Connection::memory();
+    send_session(
+        &client,
+        ClientCapabilities::default(),
+        vec![did_open_notification(UNUSED_VARIABLE), hover_request(1)],
+    );
+    let mut language_server = LanguageServer::new(&server, project_io(UNUSED_VARIABLE)).unwrap();
+    language_server.run().unwrap();
+    _ = client.receiver.try_iter().count();
+
+    // A new client connects once the previous one has gone.
+    send_session(&client, ClientCapabilities::default(), vec![]);
+    language_server.reconnect();
+    language_server.run().unwrap();
+    let sent = client.receiver.try_iter().collect::<Vec<_>>();
+
+    let published = notifications(&sent, "textDocument/publishDiagnostics")
+        .into_iter()
+        .map(|notification| {
+            let params: PublishDiagnosticsParams =
+                serde_json::from_value(notification.params.clone()).unwrap();
+            let messages = params
+                .diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect::<Vec<_>>();
+            (params.uri.to_string(), messages)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        published,
+        vec![(APP_URI.to_string(), unused_variable_messages())]
+    );
+    assert!(notifications(&sent, "$/progress").is_empty());
+}
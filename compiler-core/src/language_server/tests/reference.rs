@@ -757,3 +757,86 @@ pub fn main() -> Wibble {
         find_position_of("mod.Wibble").under_char('W'),
     );
 }
+
+fn find_dependency_references(
+    tester: &TestProject<'_>,
+    position: Position,
+) -> Option<HashMap<String, Vec<Range>>> {
+    let locations = tester.at(position, |engine, params, _| {
+        let item = engine.referenced_module_item(&params).result.unwrap()?;
+        Some(engine.find_dependency_references(&item).result.unwrap())
+    })?;
+    let mut references: HashMap<String, Vec<Range>> = HashMap::new();
+
+    for location in locations {
+        let module_name = tester
+            .module_name_from_url(&location.uri)
+            .expect("Valid uri");
+        _ = references
+            .entry(module_name)
+            .or_default()
+            .push(location.range);
+    }
+
+    Some(references)
+}
+
+#[test]
+fn dependency_references_only_include_dependent_modules() {
+    let src = "
+import shared
+
+pub fn main() {
+  shared.wibble()
+}
+";
+    let other = "
+import shared.{wibble}
+
+pub fn other() {
+  wibble()
+}
+";
+    let project = TestProject::for_source(src)
+        .add_module("other", other)
+        .add_dep_module(
+            "shared",
+            "
+pub fn wibble() { Nil }
+
+pub fn wobble() {
+  wibble()
+}
+",
+        );
+    let position = find_position_of("wibble").find_position(src);
+    let result = find_dependency_references(&project, position).expect("References not found");
+
+    // The dependency's own modules are searched by its own engine.
+    assert!(!result.contains_key("shared"));
+
+    let output = format!(
+        "-- other.gleam\n{}\n\n-- app.gleam\n{}",
+        show_references(other, None, result.get("other").unwrap_or(&Vec::new())),
+        show_references(
+            src,
+            Some(position),
+            result.get("app").unwrap_or(&Vec::new())
+        )
+    );
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn no_dependency_references_for_local_variable() {
+    let project = TestProject::for_source(
+        "
+pub fn main() {
+  let wibble = 10
+  wibble
+}
+",
+    );
+    let position = find_position_of("wibble").find_position(project.src);
+    assert_eq!(find_dependency_references(&project, position), None);
+}
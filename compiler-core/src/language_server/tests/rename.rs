@@ -1331,3 +1331,79 @@ pub type Wobble {
         find_position_of("Wibble")
     );
 }
+
+fn rename_dependency_references(
+    tester: &TestProject<'_>,
+    new_name: &str,
+    position: Position,
+) -> Option<HashMap<String, String>> {
+    let edit = tester.at(position, |engine, params, _| {
+        let item = engine.renamed_module_item(&params).result.unwrap()?;
+        Some(
+            engine
+                .rename_dependency_references(&item, new_name)
+                .result
+                .unwrap(),
+        )
+    })?;
+    Some(apply_code_edit(tester, edit.changes?))
+}
+
+#[test]
+fn rename_dependency_references_in_dependent_modules() {
+    let src = "
+import shared
+
+pub fn main() {
+  shared.wibble()
+}
+";
+    let other = "
+import shared.{wibble}
+
+pub fn other() {
+  wibble()
+}
+";
+    let project = TestProject::for_source(src)
+        .add_module("other", other)
+        .add_dep_module(
+            "shared",
+            "
+pub fn wibble() { Nil }
+
+pub fn wobble() {
+  wibble()
+}
+",
+        );
+    let position = find_position_of("wibble").find_position(src);
+    let result = rename_dependency_references(&project, "wubble", position).expect("Rename failed");
+
+    // The dependency's own modules are renamed by its own engine.
+    assert!(!result.contains_key("shared"));
+
+    let output = format!(
+        "-- other.gleam\n{}\n\n-- app.gleam\n{}",
+        result.get("other").expect("other is renamed"),
+        result.get("app").expect("app is renamed"),
+    );
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn no_dependency_rename_for_unqualified_import_alias() {
+    let src = "
+import shared.{wibble}
+
+pub fn main() {
+  wibble()
+}
+";
+    let project = TestProject::for_source(src).add_dep_module("shared", "pub fn wibble() { Nil }");
+    let position = find_position_of("wibble()").find_position(src);
+    assert_eq!(
+        rename_dependency_references(&project, "wubble", position),
+        None
+    );
+}
@@ -0,0 +1,483 @@
+use lsp_types::{
+    DocumentChangeOperation, DocumentChanges, FileRename, OneOf, RenameFilesParams, ResourceOp,
+    WorkspaceEdit,
+};
+
+use crate::language_server::{
+    rename_module::{RenameModuleArguments, RenameModuleOutcome},
+    url_from_path,
+};
+
+use super::*;
+
+/// A project made of the `app` module, some other modules and some native
+/// files, with paths relative to the project root.
+///
+struct Project<'a> {
+    modules: Vec<(&'a str, &'a str)>,
+    files: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Project<'a> {
+    fn new(app: &'a str) -> Self {
+        Self {
+            modules: vec![("src/app.gleam", app)],
+            files: vec![],
+        }
+    }
+
+    fn module(mut self, path: &'a str, code: &'a str) -> Self {
+        self.modules.push((path, code));
+        self
+    }
+
+    fn file(mut self, path: &'a str, code: &'a str) -> Self {
+        self.files.push((path, code));
+        self
+    }
+
+    fn engine(&self) -> LanguageServerEngine<LanguageServerTestIO, LanguageServerTestIO> {
+        let io = LanguageServerTestIO::new();
+        let mut engine = setup_engine(&io);
+        for (path, code) in self.modules.iter().chain(self.files.iter()) {
+            io.module(&io.paths.root().join(path), code);
+        }
+        let response = engine.compile_please();
+        assert!(response.result.is_ok());
+        engine
+    }
+
+    fn uri(&self, path: &str) -> Url {
+        let path = ProjectPaths::at_filesystem_root().root().join(path);
+        url_from_path(path.as_str()).unwrap()
+    }
+
+    fn rename_module(&self, path: &str, new_name: &str) -> RenameModuleOutcome {
+        let arguments = RenameModuleArguments {
+            uri: self.uri(path),
+            module: new_name.into(),
+        };
+        self.engine()
+            .rename_module(arguments)
+            .result
+            .unwrap()
+            .expect("No module to rename")
+    }
+
+    fn will_rename_files(&self, files: &[(&str, &str)]) -> Option<WorkspaceEdit> {
+        let files = files
+            .iter()
+            .map(|(old, new)| FileRename {
+                old_uri: self.uri(old).to_string(),
+                new_uri: self.uri(new).to_string(),
+            })
+            .collect();
+        self.engine()
+            .will_rename_files(RenameFilesParams { files })
+            .result
+            .unwrap()
+    }
+
+    /// Shows the edited files and the renamed ones, in the order the client
+    /// applies them.
+    ///
+    fn show_edit(&self, edit: WorkspaceEdit) -> String {
+        let Some(DocumentChanges::Operations(operations)) = edit.document_changes else {
+            panic!("Expected document change operations")
+        };
+
+        let mut output = String::new();
+        for operation in operations {
+            match operation {
+                DocumentChangeOperation::Edit(edit) => {
+                    let path = pretty_path(&edit.text_document.uri);
+                    let code = self
+                        .modules
+                        .iter()
+                        .chain(self.files.iter())
+                        .find(|(file, _)| *file == path)
+                        .map(|(_, code)| *code)
+                        .expect("Edit to unknown file");
+                    let edits = edit
+                        .edits
+                        .into_iter()
+                        .map(|edit| match edit {
+                            OneOf::Left(edit) => edit,
+                            OneOf::Right(annotated) => annotated.text_edit,
+                        })
+                        .collect();
+                    let code = apply_code_edit(code, edits);
+                    output.push_str(&format!("----- EDIT {path}\n{code}\n\n"));
+                }
+                DocumentChangeOperation::Op(ResourceOp::Rename(rename)) => {
+                    output.push_str(&format!(
+                        "----- RENAME {} -> {}\n\n",
+                        pretty_path(&rename.old_uri),
+                        pretty_path(&rename.new_uri)
+                    ));
+                }
+                DocumentChangeOperation::Op(_) => panic!("Unexpected resource operation"),
+            }
+        }
+        output
+    }
+}
+
+fn pretty_path(uri: &Url) -> String {
+    uri.path_segments()
+        .expect("a file path")
+        // To make snapshots the same both on windows and unix systems we need
+        // to discard windows' `C:` path segment at the beginning of a uri.
+        .skip_while(|segment| *segment == "C:")
+        .join("/")
+}
+
+macro_rules! assert_rename_module {
+    ($project:expr, $path:literal, $new_name:literal $(,)?) => {
+        let project = $project;
+        let RenameModuleOutcome::Renamed { edit } = project.rename_module($path, $new_name) else {
+            panic!("The module was not renamed")
+        };
+        insta::assert_snapshot!(insta::internals::AutoName, project.show_edit(edit));
+    };
+}
+
+macro_rules! assert_rename_module_refused {
+    ($project:expr, $path:literal, $new_name:literal, $reason:literal $(,)?) => {
+        assert_eq!(
+            $project.rename_module($path, $new_name),
+            RenameModuleOutcome::Refused {
+                reason: $reason.into()
+            }
+        );
+    };
+}
+
+macro_rules! assert_will_rename_files {
+    ($project:expr, $files:expr $(,)?) => {
+        let project = $project;
+        let edit = project
+            .will_rename_files($files)
+            .expect("Renaming the files should produce an edit");
+        insta::assert_snapshot!(insta::internals::AutoName, project.show_edit(edit));
+    };
+}
+
+#[test]
+fn rename_module_updates_imports_and_qualified_references() {
+    assert_rename_module!(
+        Project::new(
+            "import wibble
+
+pub fn main() -> wibble.Wibble {
+  let wobble = wibble.wobble()
+  case wobble {
+    wibble.Wibble -> wibble.Wibble
+  }
+}
+"
+        )
+        .module(
+            "src/wibble.gleam",
+            "pub type Wibble { Wibble }
+
+pub fn wobble() { Wibble }
+"
+        ),
+        "src/wibble.gleam",
+        "wubble",
+    );
+}
+
+#[test]
+fn rename_module_keeps_aliases() {
+    assert_rename_module!(
+        Project::new(
+            "import wibble as w
+
+pub fn main() {
+  w.wobble()
+}
+"
+        )
+        .module("src/wibble.gleam", "pub fn wobble() { Nil }"),
+        "src/wibble.gleam",
+        "wubble",
+    );
+}
+
+#[test]
+fn rename_module_updates_unqualified_imports() {
+    assert_rename_module!(
+        Project::new(
+            "import wibble.{type Wibble, Wibble, wobble}
+
+pub fn main() -> Wibble {
+  wobble()
+  wibble.wobble()
+  Wibble
+}
+"
+        )
+        .module(
+            "src/wibble.gleam",
+            "pub type Wibble { Wibble }
+
+pub fn wobble() { Nil }
+"
+        ),
+        "src/wibble.gleam",
+        "wubble",
+    );
+}
+
+#[test]
+fn rename_module_to_nested_module() {
+    assert_rename_module!(
+        Project::new(
+            "import wibble/wobble
+
+pub fn main() {
+  wobble.wobble()
+}
+"
+        )
+        .module("src/wibble/wobble.gleam", "pub fn wobble() { Nil }"),
+        "src/wibble/wobble.gleam",
+        "wubble/wobble",
+    );
+}
+
+#[test]
+fn rename_module_with_same_last_segment_keeps_qualified_references() {
+    assert_rename_module!(
+        Project::new(
+            "import wibble
+
+pub fn main() {
+  wibble.wobble()
+}
+"
+        )
+        .module("src/wibble.gleam", "pub fn wobble() { Nil }"),
+        "src/wibble.gleam",
+        "wubble/wibble",
+    );
+}
+
+#[test]
+fn rename_module_aliases_import_if_new_name_is_taken() {
+    assert_rename_module!(
+        Project::new(
+            "import wibble
+import wobble/wubble
+
+pub fn main() {
+  wibble.wibble()
+  wubble.wubble()
+}
+"
+        )
+        .module("src/wibble.gleam", "pub fn wibble() { Nil }")
+        .module("src/wobble/wubble.gleam", "pub fn wubble() { Nil }"),
+        "src/wibble.gleam",
+        "wubble",
+    );
+}
+
+#[test]
+fn rename_module_updates_other_importing_modules() {
+    assert_rename_module!(
+        Project::new("pub fn main() { Nil }")
+            .module("src/wibble.gleam", "pub fn wibble() { Nil }")
+            .module(
+                "src/wobble.gleam",
+                "import wibble
+
+pub fn wobble() {
+  wibble.wibble()
+}
+"
+            )
+            .module(
+                "test/wibble_test.gleam",
+                "import wibble
+
+pub fn wibble_test() {
+  wibble.wibble()
+}
+"
+            ),
+        "src/wibble.gleam",
+        "wubble",
+    );
+}
+
+#[test]
+fn rename_module_renames_ffi_files() {
+    assert_rename_module!(
+        Project::new(
+            "import wibble
+
+pub fn main() {
+  wibble.wibble()
+}
+"
+        )
+        .module(
+            "src/wibble.gleam",
+            r#"@external(erlang, "wibble_ffi", "wibble")
+@external(javascript, "./wibble_ffi.mjs", "wibble")
+pub fn wibble() -> Nil
+"#
+        )
+        .file(
+            "src/wibble_ffi.erl",
+            "-module(wibble_ffi).
+-export([wibble/0]).
+
+wibble() -> nil.
+"
+        )
+        .file(
+            "src/wibble_ffi.mjs",
+            "export function wibble() {
+  return undefined;
+}
+"
+        )
+        .file("src/wobble_ffi.mjs", ""),
+        "src/wibble.gleam",
+        "wubble",
+    );
+}
+
+#[test]
+fn rename_module_updates_externals_of_other_modules() {
+    assert_rename_module!(
+        Project::new(
+            r#"@external(erlang, "wibble_ffi", "wibble")
+@external(javascript, "./wibble_ffi.mjs", "wibble")
+pub fn wibble() -> Nil
+"#
+        )
+        .module("src/wibble.gleam", "pub fn wibble() { Nil }")
+        .file("src/wibble_ffi.erl", "-module(wibble_ffi).\n")
+        .file("src/wibble_ffi.mjs", ""),
+        "src/wibble.gleam",
+        "wobble/wubble",
+    );
+}
+
+#[test]
+fn rename_module_to_other_directory_updates_relative_javascript_imports() {
+    assert_rename_module!(
+        Project::new("pub fn main() { Nil }")
+            .module(
+                "src/wibble.gleam",
+                r#"@external(erlang, "shared_ffi", "wibble")
+@external(javascript, "./shared_ffi.mjs", "wibble")
+pub fn wibble() -> Nil
+"#
+            )
+            .file("src/shared_ffi.mjs", ""),
+        "src/wibble.gleam",
+        "wobble/wibble",
+    );
+}
+
+#[test]
+fn rename_module_refused_if_module_exists() {
+    assert_rename_module_refused!(
+        Project::new("pub fn main() { Nil }")
+            .module("src/wibble.gleam", "pub fn wibble() { Nil }")
+            .module("src/wobble.gleam", "pub fn wobble() { Nil }"),
+        "src/wibble.gleam",
+        "wobble",
+        "A module called `wobble` already exists",
+    );
+}
+
+#[test]
+fn rename_module_refused_for_invalid_name() {
+    assert_rename_module_refused!(
+        Project::new("pub fn main() { Nil }").module("src/wibble.gleam", "pub fn wibble() { Nil }"),
+        "src/wibble.gleam",
+        "Wobble",
+        "`Wobble` is not a valid module name",
+    );
+}
+
+#[test]
+fn rename_module_refused_for_same_name() {
+    assert_rename_module_refused!(
+        Project::new("pub fn main() { Nil }").module("src/wibble.gleam", "pub fn wibble() { Nil }"),
+        "src/wibble.gleam",
+        "wibble",
+        "The module is already called `wibble`",
+    );
+}
+
+#[test]
+fn will_rename_gleam_file() {
+    assert_will_rename_files!(
+        Project::new(
+            "import wibble
+
+pub fn main() {
+  wibble.wibble()
+}
+"
+        )
+        .module(
+            "src/wibble.gleam",
+            r#"@external(erlang, "wibble_ffi", "wibble")
+pub fn wibble() -> Nil
+"#
+        )
+        .file("src/wibble_ffi.erl", "-module(wibble_ffi).\n"),
+        &[("src/wibble.gleam", "src/wobble.gleam")],
+    );
+}
+
+#[test]
+fn will_rename_directory() {
+    assert_will_rename_files!(
+        Project::new(
+            "import wibble/wobble
+import wibble/wubble as w
+
+pub fn main() {
+  wobble.wobble()
+  w.wubble()
+}
+"
+        )
+        .module("src/wibble/wobble.gleam", "pub fn wobble() { Nil }")
+        .module("src/wibble/wubble.gleam", "pub fn wubble() { Nil }"),
+        &[("src/wibble", "src/wabble")],
+    );
+}
+
+#[test]
+fn will_rename_javascript_file() {
+    assert_will_rename_files!(
+        Project::new(
+            r#"@external(erlang, "wibble_ffi", "wibble")
+@external(javascript, "./wibble_ffi.mjs", "wibble")
+pub fn wibble() -> Nil
+"#
+        )
+        .file("src/wibble_ffi.mjs", ""),
+        &[("src/wibble_ffi.mjs", "src/ffi/wobble.mjs")],
+    );
+}
+
+#[test]
+fn will_rename_unrelated_file() {
+    let project = Project::new("pub fn main() { Nil }").file("src/wibble.txt", "");
+    assert_eq!(
+        project.will_rename_files(&[("src/wibble.txt", "src/wobble.txt")]),
+        None
+    );
+}
@@ -0,0 +1,134 @@
+use lsp_types::{Range, SelectionRange, SelectionRangeParams};
+
+use super::*;
+
+fn selection_range(tester: TestProject<'_>, position: Position) -> String {
+    tester.at(position, |engine, params, src| {
+        let params = SelectionRangeParams {
+            text_document: params.text_document,
+            positions: vec![position],
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let ranges = engine.selection_range(params).result.unwrap();
+        let [range] = ranges.as_slice() else {
+            panic!("Expected a single selection range");
+        };
+        show_selection(&src, range)
+    })
+}
+
+/// Shows the text selected by each step of the selection range, starting
+/// from the innermost one.
+///
+fn show_selection(src: &str, range: &SelectionRange) -> String {
+    let line_numbers = LineNumbers::new(src);
+    let mut output = String::new();
+    let mut current = Some(range);
+
+    while let Some(range) = current {
+        let start = line_numbers.byte_index(range.range.start.line, range.range.start.character);
+        let end = line_numbers.byte_index(range.range.end.line, range.range.end.character);
+        output.push_str("-----\n");
+        output.push_str(src.get(start as usize..end as usize).unwrap());
+        output.push('\n');
+        current = range.parent.as_deref();
+    }
+
+    output
+}
+
+#[test]
+fn selection_range_in_expression() {
+    let code = "
+pub fn main() {
+  let x = 1
+  let y = add(x, 2 * 3)
+  y
+}
+
+fn add(a, b) { a + b }
+";
+
+    insta::assert_snapshot!(selection_range(
+        TestProject::for_source(code),
+        find_position_of("2 *").find_position(code)
+    ));
+}
+
+#[test]
+fn selection_range_in_case_clause() {
+    let code = "
+pub fn main(x) {
+  case x {
+    [first, ..] -> first
+    [] -> 0
+  }
+}
+";
+
+    insta::assert_snapshot!(selection_range(
+        TestProject::for_source(code),
+        find_position_of("first,").find_position(code)
+    ));
+}
+
+#[test]
+fn selection_range_in_function_argument_annotation() {
+    let code = "
+pub fn main(x: List(Int)) -> Int {
+  0
+}
+";
+
+    insta::assert_snapshot!(selection_range(
+        TestProject::for_source(code),
+        find_position_of("Int)").find_position(code)
+    ));
+}
+
+#[test]
+fn selection_range_in_custom_type() {
+    let code = "
+pub type Wibble {
+  Wibble(label: Int)
+  Wobble
+}
+";
+
+    insta::assert_snapshot!(selection_range(
+        TestProject::for_source(code),
+        find_position_of("Int").find_position(code)
+    ));
+}
+
+#[test]
+fn selection_range_for_multiple_positions() {
+    let code = "
+pub fn main() {
+  1
+}
+
+pub fn other() {
+  2
+}
+";
+
+    let ranges = TestProject::for_source(code).at(Position::default(), |engine, params, _| {
+        let params = SelectionRangeParams {
+            text_document: params.text_document,
+            positions: vec![Position::new(2, 2), Position::new(6, 2)],
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        engine.selection_range(params).result.unwrap()
+    });
+
+    assert_eq!(
+        ranges.iter().map(|range| range.range).collect_vec(),
+        vec![
+            Range::new(Position::new(2, 2), Position::new(2, 3)),
+            Range::new(Position::new(6, 2), Position::new(6, 3)),
+        ]
+    );
+}
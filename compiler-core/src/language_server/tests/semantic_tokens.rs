@@ -0,0 +1,357 @@
+use lsp_types::{
+    SemanticToken, SemanticTokensDeltaParams, SemanticTokensFullDeltaResult, SemanticTokensParams,
+    SemanticTokensResult,
+};
+
+use crate::language_server::semantic_tokens::legend;
+
+use super::*;
+
+fn semantic_tokens(tester: TestProject<'_>) -> String {
+    tester.at(Position::default(), |engine, params, src| {
+        let params = SemanticTokensParams {
+            text_document: params.text_document,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = engine.semantic_tokens_full(params);
+
+        let Some(SemanticTokensResult::Tokens(tokens)) = response.result.unwrap() else {
+            panic!("Expected all the semantic tokens");
+        };
+        show_tokens(&src, &tokens.data)
+    })
+}
+
+/// Decodes the tokens, showing each one next to the name it refers to.
+///
+fn show_tokens(src: &str, tokens: &[SemanticToken]) -> String {
+    let legend = legend();
+    let lines = src.lines().collect_vec();
+    let mut line = 0;
+    let mut start = 0;
+    let mut output = String::new();
+
+    for token in tokens {
+        if token.delta_line == 0 {
+            start += token.delta_start;
+        } else {
+            line += token.delta_line;
+            start = token.delta_start;
+        }
+
+        let name = lines
+            .get(line as usize)
+            .and_then(|text| text.get(start as usize..(start + token.length) as usize))
+            .unwrap_or_default();
+        let token_type = legend
+            .token_types
+            .get(token.token_type as usize)
+            .unwrap()
+            .as_str();
+        let modifiers = legend
+            .token_modifiers
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| token.token_modifiers_bitset & (1 << index) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+            .join(", ");
+
+        output.push_str(&format!(
+            "{line}:{start} {name} {token_type} [{modifiers}]\n"
+        ));
+    }
+
+    output
+}
+
+#[test]
+fn semantic_tokens_for_functions_and_variables() {
+    let code = "
+pub fn add(first: Int, to second: Int) -> Int {
+  let result = first + second
+  result
+}
+
+fn main() {
+  add(1, to: 2)
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_for_custom_types() {
+    let code = "
+pub type Wibble(a) {
+  Wibble(wibble: a, wobble: Int)
+  Wobble
+}
+
+pub fn main(wibble: Wibble(Int)) -> Int {
+  case wibble {
+    Wibble(wibble: value, ..) -> value + wibble.wobble
+    Wobble -> 0
+  }
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_for_module_select() {
+    let code = "
+import wibble as wobble
+
+pub fn main() -> wobble.Wibble {
+  wobble.Wibble(wobble.value)
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code).add_module(
+        "wibble",
+        "pub type Wibble { Wibble(Int) }
+pub const value = 1"
+    )));
+}
+
+#[test]
+fn semantic_tokens_for_unqualified_imports() {
+    let code = "
+import wibble.{type Wibble, Wibble, wobble as wubble}
+
+pub fn main() -> Wibble {
+  Wibble(wubble())
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code).add_module(
+        "wibble",
+        "pub type Wibble { Wibble(Int) }
+pub fn wobble() { 1 }"
+    )));
+}
+
+#[test]
+fn semantic_tokens_mark_unused_items() {
+    let code = "
+import wibble
+import wobble.{wubble}
+
+fn unused_function() {
+  let unused_variable = 1
+  Nil
+}
+
+pub fn main() {
+  Nil
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(
+        TestProject::for_source(code)
+            .add_module("wibble", "pub fn wibble() { 1 }")
+            .add_module("wobble", "pub fn wubble() { 1 }")
+    ));
+}
+
+#[test]
+fn semantic_tokens_mark_deprecated_items() {
+    let code = "
+import wibble
+
+@deprecated(\"Use something else\")
+pub fn old() { 1 }
+
+pub fn main() {
+  old() + wibble.wibble()
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code).add_module(
+        "wibble",
+        "@deprecated(\"Use something else\")
+pub fn wibble() { 1 }"
+    )));
+}
+
+#[test]
+fn semantic_tokens_mark_internal_and_external_functions() {
+    let code = "
+@internal
+pub fn internal() { 1 }
+
+@external(erlang, \"wibble\", \"wobble\")
+@external(javascript, \"./wibble.mjs\", \"wobble\")
+fn external() -> Int
+
+pub fn main() {
+  internal() + external()
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_for_constants_and_type_aliases() {
+    let code = "
+pub type Pair(a) = #(a, a)
+
+const pair: Pair(Int) = #(1, 2)
+
+pub fn main() {
+  pair
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_ignore_code_generated_by_the_compiler() {
+    let code = "
+pub type Wibble {
+  Wibble(wibble: Int)
+}
+
+fn apply(value: a, fun: fn(a) -> b) -> b {
+  fun(value)
+}
+
+pub fn main() {
+  use Wibble(wibble:) <- apply(Wibble(1))
+  wibble |> apply(fn(x) { x })
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(TestProject::for_source(code)));
+}
+
+#[test]
+fn semantic_tokens_delta() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+    let path = io.src_module(
+        "app",
+        "pub fn main() {
+  let wibble = 1
+  wibble
+}
+",
+    );
+    let _ = engine.compile_please();
+
+    let url = Url::from_file_path(path.as_std_path()).unwrap();
+    let response = engine.semantic_tokens_full(SemanticTokensParams {
+        text_document: TextDocumentIdentifier::new(url.clone()),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let Some(SemanticTokensResult::Tokens(full)) = response.result.unwrap() else {
+        panic!("Expected all the semantic tokens");
+    };
+
+    let _ = io.src_module(
+        "app",
+        "pub fn main() {
+  let wibble = 1
+  let wobble = wibble
+  wobble
+}
+",
+    );
+    let _ = engine.compile_please();
+
+    let response = engine.semantic_tokens_full_delta(SemanticTokensDeltaParams {
+        text_document: TextDocumentIdentifier::new(url),
+        previous_result_id: full.result_id.unwrap(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let Some(SemanticTokensFullDeltaResult::TokensDelta(delta)) = response.result.unwrap() else {
+        panic!("Expected a delta of the semantic tokens");
+    };
+
+    // The last token is encoded relative to the one before it, so from the
+    // client's point of view only two tokens have been inserted after the
+    // `wibble` definition.
+    assert_eq!(delta.edits.len(), 1);
+    let edit = delta.edits.first().unwrap();
+    assert_eq!(edit.start, 10);
+    assert_eq!(edit.delete_count, 0);
+    assert_eq!(edit.data.as_ref().map(Vec::len), Some(2));
+}
+
+#[test]
+fn semantic_tokens_delta_with_unknown_previous_result() {
+    let code = "pub fn main() { 1 }";
+
+    let result = TestProject::for_source(code).at(Position::default(), |engine, params, _| {
+        engine
+            .semantic_tokens_full_delta(SemanticTokensDeltaParams {
+                text_document: params.text_document,
+                previous_result_id: "wibble".into(),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+            })
+            .result
+            .unwrap()
+    });
+
+    assert!(matches!(
+        result,
+        Some(SemanticTokensFullDeltaResult::Tokens(_))
+    ));
+}
+
+#[test]
+fn semantic_tokens_are_forgotten_when_document_is_closed() {
+    let io = LanguageServerTestIO::new();
+    let mut engine = setup_engine(&io);
+    let path = io.src_module("app", "pub fn main() { 1 }");
+    let _ = engine.compile_please();
+
+    let url = Url::from_file_path(path.as_std_path()).unwrap();
+    let response = engine.semantic_tokens_full(SemanticTokensParams {
+        text_document: TextDocumentIdentifier::new(url.clone()),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let Some(SemanticTokensResult::Tokens(full)) = response.result.unwrap() else {
+        panic!("Expected all the semantic tokens");
+    };
+
+    engine.document_closed(&url);
+
+    let response = engine.semantic_tokens_full_delta(SemanticTokensDeltaParams {
+        text_document: TextDocumentIdentifier::new(url),
+        previous_result_id: full.result_id.unwrap(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    assert!(matches!(
+        response.result.unwrap(),
+        Some(SemanticTokensFullDeltaResult::Tokens(_))
+    ));
+}
+
+#[test]
+fn semantic_tokens_for_import_of_module_named_like_part_of_keyword() {
+    let code = "
+import im
+import port
+
+pub fn main() {
+  im.wibble() + port.wobble()
+}
+";
+
+    insta::assert_snapshot!(semantic_tokens(
+        TestProject::for_source(code)
+            .add_module("im", "pub fn wibble() { 1 }")
+            .add_module("port", "pub fn wobble() { 1 }")
+    ));
+}
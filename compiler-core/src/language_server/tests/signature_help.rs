@@ -41,6 +41,14 @@ fn pretty_signature_help(signature_help: SignatureHelp) -> String {
         None => "No documentation".to_string(),
     };
 
+    let parameter_documentation = match active_parameter
+        .and_then(|i| parameters.get(i as usize))
+        .and_then(|parameter| parameter.documentation.as_ref())
+    {
+        Some(d) => format!("\n\nParameter documentation:\n{d:#?}"),
+        None => "".to_string(),
+    };
+
     let label = match active_parameter {
         None => label.to_string(),
         Some(i) => match parameters.get(i as usize) {
@@ -57,7 +65,7 @@ fn pretty_signature_help(signature_help: SignatureHelp) -> String {
         },
     };
 
-    format!("{label}\n\n{documentation}")
+    format!("{label}\n\n{documentation}{parameter_documentation}")
 }
 
 #[macro_export]
@@ -451,3 +459,81 @@ pub fn main() {
         find_position_of(r#"Pokemon(name: "Jirachi",)"#).under_last_char()
     );
 }
+
+#[test]
+pub fn help_shows_documentation_of_labelled_parameter() {
+    assert_signature_help!(
+        r#"
+/// Wobbles the wibble.
+///
+/// - `times`: how many times the wibble is wobbled.
+///   It must be positive.
+///
+pub fn wobble(wibble: Int, times times: Int) { Nil }
+
+pub fn main() {
+    wobble(1, )
+}
+    "#,
+        find_position_of("wobble(1, )").under_last_char()
+    );
+}
+
+#[test]
+pub fn help_shows_documentation_of_labelled_parameter_from_other_module() {
+    let code = r#"
+import example
+pub fn main() {
+  example.wobble(1, )
+}
+"#;
+
+    assert_signature_help!(
+        TestProject::for_source(code).add_dep_module(
+            "example",
+            r#"
+/// Wobbles the wibble.
+///
+/// - `times`: how many times the wibble is wobbled.
+pub fn wobble(wibble: Int, times times: Int) { Nil }
+"#
+        ),
+        find_position_of("wobble(1, )").under_last_char()
+    );
+}
+
+#[test]
+pub fn help_shows_documentation_of_labelled_record_field() {
+    assert_signature_help!(
+        r#"
+pub type Pokemon {
+    Pokemon(
+      /// The name of the Pokemon.
+      name: String,
+      level: Int,
+    )
+}
+
+pub fn main() {
+    Pokemon()
+}
+    "#,
+        find_position_of("Pokemon()").under_last_char()
+    );
+}
+
+#[test]
+pub fn help_does_not_show_documentation_of_unknown_labels() {
+    assert_signature_help!(
+        r#"
+/// - `wibble`: this is not a label.
+/// - `times`: how many times the wibble is wobbled.
+pub fn wobble(wibble: Int, times times: Int) { Nil }
+
+pub fn main() {
+    wobble()
+}
+    "#,
+        find_position_of("wobble()").under_last_char()
+    );
+}
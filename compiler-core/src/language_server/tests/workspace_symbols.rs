@@ -0,0 +1,190 @@
+use lsp_types::{SymbolInformation, SymbolTag, WorkspaceSymbolParams};
+
+use crate::language_server::workspace_symbols::sort_workspace_symbols;
+
+use super::*;
+
+fn workspace_symbols(tester: TestProject<'_>, query: &str) -> String {
+    show_symbols(find_symbols(tester, query))
+}
+
+fn find_symbols(tester: TestProject<'_>, query: &str) -> Vec<SymbolInformation> {
+    tester.at(Position::default(), |engine, _param, _| {
+        let params = WorkspaceSymbolParams {
+            query: query.into(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let response = engine.workspace_symbol(&params);
+        response.result.unwrap()
+    })
+}
+
+fn show_symbols(symbols: Vec<SymbolInformation>) -> String {
+    symbols
+        .into_iter()
+        .map(|symbol| {
+            let deprecated = match symbol.tags {
+                Some(tags) if tags.contains(&SymbolTag::DEPRECATED) => " [deprecated]",
+                Some(_) | None => "",
+            };
+            format!(
+                "{} {:?} in {} at {}:{}{deprecated}",
+                symbol.name,
+                symbol.kind,
+                symbol.container_name.unwrap_or_default(),
+                symbol.location.range.start.line,
+                symbol.location.range.start.character,
+            )
+        })
+        .join("\n")
+}
+
+#[test]
+fn workspace_symbols_exact_match() {
+    let code = "
+pub fn wibble() { 1 }
+
+pub fn wobble() { 2 }
+";
+
+    insta::assert_snapshot!(workspace_symbols(TestProject::for_source(code), "wibble"));
+}
+
+#[test]
+fn workspace_symbols_fuzzy_match() {
+    let code = "
+pub fn wibble_wobble() { 1 }
+
+pub fn wobble() { 2 }
+
+pub fn wibble() { 3 }
+";
+
+    insta::assert_snapshot!(workspace_symbols(TestProject::for_source(code), "wwo"));
+}
+
+#[test]
+fn workspace_symbols_best_matches_come_first() {
+    let code = "
+pub fn get_wibble() { 1 }
+
+pub fn wibble_wobble() { 2 }
+
+pub fn wibble() { 3 }
+";
+
+    insta::assert_snapshot!(workspace_symbols(TestProject::for_source(code), "wibble"));
+}
+
+#[test]
+fn workspace_symbols_matching_is_case_insensitive() {
+    let code = "
+pub type WibbleWobble {
+  WibbleWobble
+  Wobble(Int)
+}
+
+pub const wibble_wobble = 1
+";
+
+    insta::assert_snapshot!(workspace_symbols(TestProject::for_source(code), "ww"));
+}
+
+#[test]
+fn workspace_symbols_includes_all_kinds_of_definitions() {
+    let code = "
+pub type Wibble {
+  Wibble(Int)
+  Wobble
+}
+
+pub type Wobbles = Wibble
+
+const wibble_constant = 1
+
+fn wibble_function() { wibble_constant }
+";
+
+    insta::assert_snapshot!(workspace_symbols(TestProject::for_source(code), "w"));
+}
+
+#[test]
+fn workspace_symbols_from_other_root_package_modules() {
+    let code = "
+pub fn wibble() { 1 }
+";
+
+    insta::assert_snapshot!(workspace_symbols(
+        TestProject::for_source(code)
+            .add_module("app/wobble", "pub fn wibble_wobble() { 1 }")
+            .add_module("app/private", "fn private_wibble() { 1 }"),
+        "wibble"
+    ));
+}
+
+#[test]
+fn workspace_symbols_from_dependencies_only_include_public_definitions() {
+    let code = "
+pub fn main() { 1 }
+";
+
+    insta::assert_snapshot!(workspace_symbols(
+        TestProject::for_source(code)
+            .add_dep_module(
+                "dep",
+                "pub fn public_wibble() { private_wibble() }
+fn private_wibble() { 1 }"
+            )
+            .add_hex_module(
+                "hex",
+                "pub type HexWibble
+
+@internal
+pub fn internal_wibble() { 1 }"
+            ),
+        "wibble"
+    ));
+}
+
+#[test]
+fn workspace_symbols_marks_deprecated_definitions() {
+    let code = "
+@deprecated(\"Use wobble instead\")
+pub fn wibble() { 1 }
+
+pub fn wobble() { 2 }
+";
+
+    insta::assert_snapshot!(workspace_symbols(TestProject::for_source(code), "w"));
+}
+
+#[test]
+fn workspace_symbols_no_match() {
+    let code = "
+pub fn wibble() { 1 }
+";
+
+    assert_eq!(
+        workspace_symbols(TestProject::for_source(code), "wobble"),
+        ""
+    );
+}
+
+#[test]
+fn workspace_symbols_from_different_projects_are_sorted_together() {
+    let one = "
+pub fn wobble_wibble() { 1 }
+pub fn wibble() { 2 }
+";
+    let other = "
+pub fn wibble_wobble() { 3 }
+";
+
+    let symbols = find_symbols(TestProject::for_source(one), "wibble")
+        .into_iter()
+        .chain(find_symbols(TestProject::for_source(other), "wibble"))
+        .collect_vec();
+
+    insta::assert_snapshot!(show_symbols(sort_workspace_symbols("wibble", symbols)));
+}
@@ -0,0 +1,209 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use ecow::EcoString;
+use itertools::Itertools;
+use lsp_types::{Location, SymbolInformation, SymbolKind};
+
+use crate::{
+    ast::SrcSpan,
+    type_::{Deprecation, ModuleInterface, ValueConstructorVariant},
+};
+
+use super::{
+    compiler::ModuleSourceInformation, engine::make_deprecated_symbol_tag, src_span_to_lsp_range,
+    url_from_path,
+};
+
+/// Finds all the top level definitions of the project's modules matching the
+/// given query, best matches first.
+///
+/// Modules belonging to the root package have all of their definitions
+/// included, while dependencies only have their public definitions included
+/// as the private ones cannot be used from the root package.
+///
+pub fn find_workspace_symbols(
+    query: &str,
+    root_package: &str,
+    modules: &im::HashMap<EcoString, ModuleInterface>,
+    sources: &HashMap<EcoString, ModuleSourceInformation>,
+) -> Vec<SymbolInformation> {
+    let mut matches = vec![];
+
+    for module in modules.values() {
+        let Some(source_information) = sources.get(&module.name) else {
+            continue;
+        };
+        let is_root_package = module.package == root_package;
+
+        let mut add_symbol =
+            |name: &EcoString, kind: SymbolKind, location: SrcSpan, deprecation: &Deprecation| {
+                let Some(score) = fuzzy_match(query, name) else {
+                    return;
+                };
+                let Some(uri) = url_from_path(source_information.path.as_str()) else {
+                    return;
+                };
+
+                // The 'deprecated' field is deprecated, but we have to specify it anyway
+                // to be able to construct the 'SymbolInformation' type, so
+                // we suppress the warning. We specify 'None' as specifying 'Some'
+                // is what is actually deprecated.
+                #[allow(deprecated)]
+                let symbol = SymbolInformation {
+                    name: name.to_string(),
+                    kind,
+                    tags: make_deprecated_symbol_tag(deprecation),
+                    deprecated: None,
+                    location: Location {
+                        uri,
+                        range: src_span_to_lsp_range(location, &source_information.line_numbers),
+                    },
+                    container_name: Some(module.name.to_string()),
+                };
+                matches.push((score, symbol));
+            };
+
+        for (name, type_) in &module.types {
+            // Imported and prelude types are not defined in this module.
+            if type_.module != module.name {
+                continue;
+            }
+            if !is_root_package && !type_.publicity.is_public() {
+                continue;
+            }
+            add_symbol(name, SymbolKind::CLASS, type_.origin, &type_.deprecation);
+        }
+
+        for (name, value) in &module.values {
+            if !is_root_package && !value.publicity.is_public() {
+                continue;
+            }
+            let (kind, location) = match &value.variant {
+                ValueConstructorVariant::ModuleFn {
+                    module: value_module,
+                    location,
+                    ..
+                } if value_module == &module.name => (SymbolKind::FUNCTION, *location),
+                ValueConstructorVariant::ModuleConstant {
+                    module: value_module,
+                    location,
+                    ..
+                } if value_module == &module.name => (SymbolKind::CONSTANT, *location),
+                ValueConstructorVariant::Record {
+                    module: value_module,
+                    location,
+                    arity,
+                    ..
+                } if value_module == &module.name => {
+                    let kind = if *arity == 0 {
+                        SymbolKind::ENUM_MEMBER
+                    } else {
+                        SymbolKind::CONSTRUCTOR
+                    };
+                    (kind, *location)
+                }
+                ValueConstructorVariant::ModuleFn { .. }
+                | ValueConstructorVariant::ModuleConstant { .. }
+                | ValueConstructorVariant::Record { .. }
+                | ValueConstructorVariant::LocalVariable { .. }
+                | ValueConstructorVariant::LocalConstant { .. } => continue,
+            };
+            add_symbol(name, kind, location, &value.deprecation);
+        }
+    }
+
+    matches
+        .into_iter()
+        .sorted_by(compare_matches)
+        .map(|(_, symbol)| symbol)
+        .collect_vec()
+}
+
+/// Sorts the symbols matching the given query, best matches first, in the
+/// same order as `find_workspace_symbols`. This is used to merge the symbols
+/// found in different projects.
+///
+pub fn sort_workspace_symbols(
+    query: &str,
+    symbols: Vec<SymbolInformation>,
+) -> Vec<SymbolInformation> {
+    symbols
+        .into_iter()
+        .map(|symbol| (fuzzy_match(query, &symbol.name).unwrap_or(0), symbol))
+        .sorted_by(compare_matches)
+        .map(|(_, symbol)| symbol)
+        .collect_vec()
+}
+
+fn compare_matches(
+    (one_score, one): &(u32, SymbolInformation),
+    (other_score, other): &(u32, SymbolInformation),
+) -> Ordering {
+    other_score
+        .cmp(one_score)
+        .then_with(|| one.name.cmp(&other.name))
+        .then_with(|| one.container_name.cmp(&other.container_name))
+        .then_with(|| one.location.range.start.cmp(&other.location.range.start))
+}
+
+/// Checks if all the characters of the query appear in the given name, in the
+/// same order but not necessarily one after the other, ignoring their case.
+/// For example `wwo` matches `wibble_wobble`.
+///
+/// If the name matches, this returns a score: the higher the score, the better
+/// the match. Exact matches come first, then prefixes, and then names where the
+/// characters of the query match consecutive characters or the start of words.
+/// An empty query matches any name.
+///
+fn fuzzy_match(query: &str, name: &str) -> Option<u32> {
+    let query = query.chars().flat_map(char::to_lowercase).collect_vec();
+
+    let mut score = 0;
+    let mut query_index = 0;
+    let mut previous_match: Option<usize> = None;
+    let mut previous_char: Option<char> = None;
+
+    for (index, char) in name.chars().enumerate() {
+        let previous = previous_char.replace(char);
+        let Some(query_char) = query.get(query_index) else {
+            break;
+        };
+        if !char.to_lowercase().eq(std::iter::once(*query_char)) {
+            continue;
+        }
+
+        score += 1;
+        match previous {
+            None => score += 8,
+            Some(previous) if is_word_start(previous, char) => score += 4,
+            Some(_) => {}
+        }
+        if previous_match.is_some_and(|previous| previous + 1 == index) {
+            score += 4;
+        }
+
+        previous_match = Some(index);
+        query_index += 1;
+    }
+
+    if query_index < query.len() {
+        return None;
+    }
+
+    let lowercase_name = name.to_lowercase();
+    let query = query.into_iter().collect::<String>();
+    if lowercase_name == query {
+        score += 100;
+    } else if lowercase_name.starts_with(&query) {
+        score += 50;
+    }
+
+    Some(score)
+}
+
+/// A word starts after an underscore, or with an uppercase letter following a
+/// lowercase one in PascalCase names.
+///
+fn is_word_start(previous: char, current: char) -> bool {
+    previous == '_' || (previous.is_lowercase() && current.is_uppercase())
+}
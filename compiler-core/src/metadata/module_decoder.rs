@@ -649,11 +649,16 @@ impl ModuleDecoder {
                 FieldMap {
                     arity: reader.get_arity(),
                     fields: read_hashmap!(&reader.get_fields()?, self, u32),
+                    documentation: read_hashmap!(&reader.get_documentation()?, self, text),
                 }
             }),
         })
     }
 
+    fn text(&self, reader: &text::Reader<'_>) -> Result<EcoString> {
+        self.string(*reader)
+    }
+
     fn u32(&self, i: &boxed_u_int32::Reader<'_>) -> Result<u32> {
         Ok(i.get_value())
     }
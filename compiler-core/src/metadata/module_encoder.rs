@@ -461,12 +461,22 @@ impl<'a> ModuleEncoder<'a> {
 
     fn build_field_map(&mut self, mut builder: field_map::Builder<'_>, field_map: &FieldMap) {
         builder.set_arity(field_map.arity);
-        let mut builder = builder.init_fields(field_map.fields.len() as u32);
+        let mut fields = builder
+            .reborrow()
+            .init_fields(field_map.fields.len() as u32);
         for (i, (name, &position)) in field_map.fields.iter().enumerate() {
-            let mut field = builder.reborrow().get(i as u32);
+            let mut field = fields.reborrow().get(i as u32);
             field.set_key(name);
             field.init_value().set_value(position);
         }
+        let mut documentation = builder.init_documentation(field_map.documentation.len() as u32);
+        for (i, (label, label_documentation)) in field_map.documentation.iter().enumerate() {
+            let mut property = documentation.reborrow().get(i as u32);
+            property.set_key(label);
+            property
+                .set_value(label_documentation.as_str())
+                .expect("capnp encode");
+        }
     }
 
     fn build_constant(&mut self, mut builder: constant::Builder<'_>, constant: &TypedConstant) {
@@ -728,6 +728,62 @@ fn module_fn_value_with_field_map() {
                     field_map: Some(FieldMap {
                         arity: 20,
                         fields: [("ok".into(), 5), ("ko".into(), 7)].into(),
+                        documentation: HashMap::new(),
+                    }),
+                    external_erlang: None,
+                    external_javascript: None,
+                    module: "a".into(),
+                    arity: 5,
+                    location: SrcSpan { start: 2, end: 11 },
+                    implementations: Implementations {
+                        gleam: true,
+                        uses_erlang_externals: false,
+                        uses_javascript_externals: false,
+                        can_run_on_erlang: true,
+                        can_run_on_javascript: true,
+                    },
+                },
+            },
+        )]
+        .into(),
+        line_numbers: LineNumbers::new(""),
+        src_path: "some_path".into(),
+        minimum_required_version: Version::new(0, 1, 0),
+        type_aliases: HashMap::new(),
+        documentation: Vec::new(),
+        contains_echo: false,
+
+        references: References::default(),
+    };
+
+    assert_eq!(roundtrip(&module), module);
+}
+
+#[test]
+fn module_fn_value_with_field_map_documentation() {
+    let module = ModuleInterface {
+        warnings: vec![],
+        is_internal: false,
+        package: "some_package".into(),
+        origin: Origin::Src,
+        name: "a".into(),
+        types: HashMap::new(),
+        types_value_constructors: HashMap::new(),
+        accessors: HashMap::new(),
+        values: [(
+            "one".into(),
+            ValueConstructor {
+                publicity: Publicity::Public,
+                deprecation: Deprecation::NotDeprecated,
+                type_: type_::int(),
+                variant: ValueConstructorVariant::ModuleFn {
+                    documentation: Some("wubble!".into()),
+                    name: "one".into(),
+                    field_map: Some(FieldMap {
+                        arity: 20,
+                        fields: [("ok".into(), 5), ("ko".into(), 7)].into(),
+                        documentation: [("ok".into(), "The `ok` label.\nIt is documented.".into())]
+                            .into(),
                     }),
                     external_erlang: None,
                     external_javascript: None,
@@ -833,6 +889,7 @@ fn record_value_with_field_map() {
                         arity: random.r#gen(),
                         fields: [("ok".into(), random.r#gen()), ("ko".into(), random.r#gen())]
                             .into(),
+                        documentation: HashMap::new(),
                     }),
                     arity: random.r#gen(),
                     variants_count: random.r#gen(),
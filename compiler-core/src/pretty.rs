@@ -598,6 +598,15 @@ impl<'a> Document<'a> {
         }
     }
 
+    /// A string that takes no space when laying out the document, for text
+    /// that is removed again once the document has been printed.
+    pub fn zero_width_string(string: EcoString) -> Self {
+        Document::EcoString {
+            graphemes: 0,
+            string,
+        }
+    }
+
     pub fn group(self) -> Self {
         Self::Group(Box::new(self))
     }
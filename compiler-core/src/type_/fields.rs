@@ -8,6 +8,8 @@ use std::collections::{HashMap, HashSet};
 pub struct FieldMap {
     pub arity: u32,
     pub fields: HashMap<EcoString, u32>,
+    /// The documentation of the labelled fields that have one.
+    pub documentation: HashMap<EcoString, EcoString>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -18,6 +20,7 @@ impl FieldMap {
         Self {
             arity,
             fields: HashMap::new(),
+            documentation: HashMap::new(),
         }
     }
 
@@ -253,7 +256,72 @@ impl FieldMapBuilder {
         Ok(())
     }
 
+    /// Documents a label that has already been added to the field map.
+    ///
+    pub fn document(&mut self, label: &EcoString, documentation: EcoString) {
+        if self.field_map.fields.contains_key(label) {
+            let _ = self
+                .field_map
+                .documentation
+                .insert(label.clone(), documentation);
+        }
+    }
+
     pub fn finish(self) -> Option<FieldMap> {
         self.field_map.into_option()
     }
 }
+
+/// The labelled arguments of a function are documented in the function's own
+/// documentation, with a list item starting with the label in backticks
+/// followed by a colon. Any following line indented further than the item
+/// is part of its documentation:
+///
+/// ```gleam
+/// /// Keeps the elements of a list for which the given function returns
+/// /// `True`.
+/// ///
+/// /// - `keeping`: the function deciding which elements are kept. It is
+/// ///   called once for each element.
+/// ///
+/// pub fn filter(list: List(a), keeping predicate: fn(a) -> Bool) -> List(a)
+/// ```
+///
+/// This returns the labels documented this way, along with their
+/// documentation.
+///
+pub fn labels_documentation(documentation: &str) -> Vec<(EcoString, EcoString)> {
+    let mut labels = vec![];
+    let mut lines = documentation.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let indentation = line.len() - line.trim_start().len();
+        let Some(item) = line
+            .trim_start()
+            .strip_prefix("- `")
+            .or_else(|| line.trim_start().strip_prefix("* `"))
+        else {
+            continue;
+        };
+        let Some((label, text)) = item.split_once("`:") else {
+            continue;
+        };
+        if label.is_empty() || label.contains(char::is_whitespace) {
+            continue;
+        }
+
+        let mut text = text.trim().to_string();
+        while let Some(next) = lines.next_if(|next| {
+            !next.trim().is_empty() && next.len() - next.trim_start().len() > indentation
+        }) {
+            text.push('\n');
+            text.push_str(next.trim());
+        }
+
+        if !text.is_empty() {
+            labels.push((label.into(), text.into()));
+        }
+    }
+
+    labels
+}
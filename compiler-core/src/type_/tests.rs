@@ -598,6 +598,7 @@ fn field_map_reorder_test() {
             let fm = FieldMap {
                 arity: self.arity,
                 fields: self.fields,
+                documentation: HashMap::new(),
             };
             let location = SrcSpan { start: 0, end: 0 };
             assert_eq!(self.expected_result, fm.reorder(&mut args, location));
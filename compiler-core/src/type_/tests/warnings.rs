@@ -1,7 +1,7 @@
 use super::*;
 use crate::{
     assert_js_no_warnings, assert_js_warning, assert_no_warnings, assert_warning,
-    assert_warnings_with_gleam_version,
+    assert_warnings_with_gleam_version, diagnostic::Tag,
 };
 
 #[test]
@@ -3014,3 +3014,42 @@ pub fn main(a) {
 "
     );
 }
+
+fn warning_tags(src: &str) -> Vec<Vec<Tag>> {
+    get_warnings(src, vec![], Target::Erlang, None)
+        .iter()
+        .map(|warning| warning.to_diagnostic().tags)
+        .collect_vec()
+}
+
+#[test]
+fn unused_warnings_are_tagged_as_unnecessary() {
+    assert_eq!(
+        warning_tags(
+            "import gleam
+fn private() { 1 }
+pub fn main() { let x = 1 Nil }"
+        ),
+        vec![vec![Tag::Unnecessary]; 3]
+    );
+}
+
+#[test]
+fn deprecated_item_warning_is_tagged_as_deprecated() {
+    assert_eq!(
+        warning_tags(
+            r#"@deprecated("Use something else")
+pub fn wibble() { 1 }
+pub fn main() { wibble() }"#
+        ),
+        vec![vec![Tag::Deprecated]]
+    );
+}
+
+#[test]
+fn other_warnings_have_no_tags() {
+    assert_eq!(
+        warning_tags("pub fn main() { todo }"),
+        vec![Vec::<Tag>::new()]
+    );
+}
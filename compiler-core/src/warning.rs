@@ -230,6 +230,7 @@ impl Warning {
                 title: "Deprecated main function".into(),
                 text: message.into(),
                 level: diagnostic::Level::Warning,
+                tags: vec![],
                 location: None,
                 hint: None,
             },
@@ -244,6 +245,7 @@ and is not recommended for public use",
                     title: "Internal main function".into(),
                     text: wrap(message.as_str()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: None,
                     hint: None,
                 }
@@ -255,6 +257,7 @@ Module names must begin with a lowercase letter and contain
 only lowercase alphanumeric characters or underscores."
                     .into(),
                 level: diagnostic::Level::Warning,
+                tags: vec![],
                 location: None,
                 hint: Some(format!(
                     "Rename `{path}` to be valid, or remove this file from the project source."
@@ -275,6 +278,7 @@ like this: `[item, ..list]`.",
 
                 hint: None,
                 level: diagnostic::Level::Warning,
+                tags: vec![],
                 location: Some(Location {
                     label: diagnostic::Label {
                         text: Some("This spread should be preceded by a comma".into()),
@@ -299,6 +303,7 @@ like this: `[item, ..list]`.",
                 ),
                 hint: None,
                 level: diagnostic::Level::Warning,
+                tags: vec![],
                 location: Some(Location {
                     label: diagnostic::Label {
                         text: Some("This spread should be preceded by a comma".into()),
@@ -319,6 +324,7 @@ like this: `[item, ..list]`.",
                 text: wrap("This syntax for pattern matching on a record is deprecated."),
                 hint: None,
                 level: diagnostic::Level::Warning,
+                tags: vec![],
                 location: Some(Location {
                     label: diagnostic::Label {
                         text: Some("This should be preceded by a comma".into()),
@@ -342,6 +348,7 @@ To match on all possible lists, use the `_` catch-all pattern instead.",
                 ),
                 hint: None,
                 level: diagnostic::Level::Warning,
+                tags: vec![],
                 location: Some(Location {
                     label: diagnostic::Label {
                         text: Some("This can be replaced with `_`".into()),
@@ -370,6 +377,7 @@ To match on all possible lists, use the `_` catch-all pattern instead.",
                     )),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         label: diagnostic::Label {
                             text: Some(format!("This should be replaced with `{full_name}`")),
@@ -424,6 +432,7 @@ A use expression must always be followed by at least one expression.",
                         title,
                         text,
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             path: path.to_path_buf(),
                             src: src.clone(),
@@ -444,6 +453,7 @@ A use expression must always be followed by at least one expression.",
                         "If you are sure you don't need it you can assign it to `_`.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -460,6 +470,7 @@ A use expression must always be followed by at least one expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![diagnostic::Tag::Unnecessary],
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -478,6 +489,7 @@ A use expression must always be followed by at least one expression.",
                         "Add some fields to change or replace it with the record itself.".into(),
                     ),
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -494,6 +506,7 @@ A use expression must always be followed by at least one expression.",
                     text: "".into(),
                     hint: Some("It is better style to use the record creation syntax.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -523,6 +536,7 @@ A use expression must always be followed by at least one expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        tags: vec![diagnostic::Tag::Unnecessary],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -553,6 +567,7 @@ A use expression must always be followed by at least one expression.",
                         text: "".into(),
                         hint: Some("You can safely remove it.".into()),
                         level: diagnostic::Level::Warning,
+                        tags: vec![diagnostic::Tag::Unnecessary],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -570,6 +585,7 @@ A use expression must always be followed by at least one expression.",
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![diagnostic::Tag::Unnecessary],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -598,6 +614,7 @@ Hint: You can safely remove it.
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        tags: vec![diagnostic::Tag::Unnecessary],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -615,6 +632,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![diagnostic::Tag::Unnecessary],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -631,6 +649,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![diagnostic::Tag::Unnecessary],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -647,6 +666,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove it.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![diagnostic::Tag::Unnecessary],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -663,6 +683,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: origin.how_to_ignore(),
                     level: diagnostic::Level::Warning,
+                    tags: vec![diagnostic::Tag::Unnecessary],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -678,6 +699,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -693,6 +715,7 @@ Hint: You can safely remove it.
                     text: "".into(),
                     hint: Some("You can safely remove this.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -722,6 +745,7 @@ need to know if the list is empty or not.
                         text,
                         hint,
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -755,6 +779,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -790,6 +815,7 @@ Run this command to add it to your dependencies:
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        tags: vec![diagnostic::Tag::Deprecated],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -818,6 +844,7 @@ on a variant of a type which is never present.\n",
                         text,
                         hint: Some("It can be safely removed.".into()),
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -855,6 +882,7 @@ See: https://tour.gleam.run/flow-control/multiple-subjects/"
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             src: src.clone(),
                             path: path.to_path_buf(),
@@ -875,6 +903,7 @@ can already tell which branch is going to match with this value.",
                     ),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -891,6 +920,7 @@ can already tell which branch is going to match with this value.",
                     text: "This type has no constructors so making it opaque is redundant.".into(),
                     hint: Some("Remove the `opaque` qualifier from the type definition.".into()),
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         src: src.clone(),
                         path: path.to_path_buf(),
@@ -907,6 +937,7 @@ can already tell which branch is going to match with this value.",
                     text: "".into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    tags: vec![diagnostic::Tag::Unnecessary],
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -940,6 +971,7 @@ hidden from the package's documentation.",
                         text,
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -957,6 +989,7 @@ hidden from the package's documentation.",
                         .into(),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         label: diagnostic::Label {
                             text: Some("You can remove this".into()),
@@ -1013,6 +1046,7 @@ See: https://tour.gleam.run/advanced-features/{name}/"
                         text: wrap(&text),
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -1053,6 +1087,7 @@ should be printing always panics."
                         text: wrap(text),
                         hint: None,
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: None,
@@ -1075,6 +1110,7 @@ See: https://tour.gleam.run/functions/pipelines/",
                     ),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         label: diagnostic::Label {
                             text: Some("You can safely remove this".into()),
@@ -1137,6 +1173,7 @@ See: https://tour.gleam.run/functions/pipelines/",
     gleam = \">= {minimum_required_version}\""
                         )),
                         level: diagnostic::Level::Warning,
+                        tags: vec![],
                         location: Some(Location {
                             label: diagnostic::Label {
                                 text: Some(format!(
@@ -1163,6 +1200,7 @@ information.",
                     ),
                     hint: None,
                     level: diagnostic::Level::Warning,
+                    tags: vec![],
                     location: Some(Location {
                         path: path.to_path_buf(),
                         src: src.clone(),
@@ -36,7 +36,7 @@ pub fn prepare(path: &str, mode: Mode) -> String {
         config,
         options,
         vec![],
-        telemetry,
+        Box::new(telemetry),
         Rc::new(warnings.clone()),
         ProjectPaths::new(root),
         filesystem.clone(),